@@ -0,0 +1,50 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+
+pub use rpc_impl_Migrations::gen_server::Migrations as MigrationsServer;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationState {
+	NotStarted,
+	InProgress,
+	Completed,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+	pub name: String,
+	pub state: MigrationState,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationsStatus {
+	pub fully_upgraded: bool,
+	pub migrations: Vec<MigrationStatus>,
+}
+
+#[rpc(server)]
+pub trait Migrations {
+	/// The state of every migration known to the runtime at the best block
+	#[rpc(name = "migrations_status")]
+	fn status(&self) -> Result<MigrationsStatus>;
+}
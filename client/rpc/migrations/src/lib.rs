@@ -0,0 +1,79 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use fc_rpc::internal_err;
+use jsonrpc_core::Result as RpcResult;
+pub use moonbeam_rpc_core_migrations::{
+	MigrationState, MigrationStatus, Migrations as MigrationsT, MigrationsServer, MigrationsStatus,
+};
+use sp_api::{BlockId, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, sync::Arc};
+
+use moonbeam_rpc_primitives_migrations::{
+	MigrationState as RuntimeMigrationState, MigrationsRuntimeApi,
+};
+
+pub struct Migrations<B: BlockT, C> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> Migrations<B, C> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C> MigrationsT for Migrations<B, C>
+where
+	C: ProvideRuntimeApi<B>,
+	C: HeaderBackend<B>,
+	C: Send + Sync + 'static,
+	B: BlockT + Send + Sync + 'static,
+	C::Api: MigrationsRuntimeApi<B>,
+{
+	fn status(&self) -> RpcResult<MigrationsStatus> {
+		let best_block: BlockId<B> = BlockId::Hash(self.client.info().best_hash);
+		let api = self.client.runtime_api();
+
+		let fully_upgraded = api.is_fully_upgraded(&best_block).map_err(|err| {
+			internal_err(format!("fetch runtime upgrade state failed: {:?}", err))
+		})?;
+		let migrations = api
+			.migrations(&best_block)
+			.map_err(|err| internal_err(format!("fetch runtime migrations failed: {:?}", err)))?
+			.into_iter()
+			.map(|migration| MigrationStatus {
+				name: String::from_utf8_lossy(&migration.name).into_owned(),
+				state: match migration.state {
+					RuntimeMigrationState::NotStarted => MigrationState::NotStarted,
+					RuntimeMigrationState::InProgress => MigrationState::InProgress,
+					RuntimeMigrationState::Completed => MigrationState::Completed,
+				},
+			})
+			.collect();
+
+		Ok(MigrationsStatus {
+			fully_upgraded,
+			migrations,
+		})
+	}
+}
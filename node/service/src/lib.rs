@@ -44,6 +44,7 @@ use cumulus_primitives_parachain_inherent::{
 };
 use nimbus_consensus::{build_nimbus_consensus, BuildNimbusConsensusParams};
 use nimbus_primitives::NimbusId;
+use polkadot_primitives::v1::{Block as PBlock, Hash as PHash};
 
 pub use sc_executor::NativeExecutor;
 use sc_executor::{native_executor_instance, NativeExecutionDispatch};
@@ -53,6 +54,8 @@ use sc_service::{
 };
 use sp_api::ConstructRuntimeApi;
 use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use sp_trie::StorageProof;
 use std::sync::Arc;
 
 pub use client::*;
@@ -409,6 +412,47 @@ impl fp_rpc::ConvertTransaction<moonbeam_core_primitives::UncheckedExtrinsic>
 	}
 }
 
+/// The relay chain storage key of BABE's `AuthorVrfRandomness`, from which pallet-randomness reads
+/// the relay randomness.
+fn relay_randomness_key() -> Vec<u8> {
+	[
+		sp_core::twox_128(b"Babe"),
+		sp_core::twox_128(b"AuthorVrfRandomness"),
+	]
+	.concat()
+}
+
+/// Add BABE's `AuthorVrfRandomness` at the relay parent to the relay state proof of the parachain
+/// inherent, which otherwise only proves the storage items read by the parachain system pallet.
+fn with_relay_randomness_proof(
+	mut parachain_inherent: ParachainInherentData,
+	relay_chain_backend: &impl sc_client_api::Backend<PBlock>,
+	relay_parent: PHash,
+) -> ParachainInherentData {
+	let proof = relay_chain_backend
+		.state_at(BlockId::Hash(relay_parent))
+		.map_err(|e| e.to_string())
+		.and_then(|state| {
+			sp_state_machine::prove_read(state, &[relay_randomness_key()])
+				.map_err(|e| e.to_string())
+		});
+
+	match proof {
+		Ok(proof) => {
+			parachain_inherent.relay_chain_state =
+				StorageProof::merge(vec![parachain_inherent.relay_chain_state, proof]);
+		}
+		// The block is still built, pallet-randomness just can't record the relay randomness.
+		Err(e) => log::warn!(
+			"Failed to prove the relay randomness at {:?}: {}",
+			relay_parent,
+			e
+		),
+	}
+
+	parachain_inherent
+}
+
 /// Start a node with the given parachain `Configuration` and relay chain `Configuration`.
 ///
 /// This is the actual implementation that is abstract over the executor and the runtime api.
@@ -601,7 +645,14 @@ where
 					&*relay_chain_backend,
 					&validation_data,
 					id,
-				);
+				)
+				.map(|parachain_inherent| {
+					with_relay_randomness_proof(
+						parachain_inherent,
+						&*relay_chain_backend,
+						relay_parent,
+					)
+				});
 				async move {
 					let time = sp_timestamp::InherentDataProvider::from_system_time();
 
@@ -36,6 +36,7 @@ use fc_rpc_core::types::FilterPool;
 use futures::StreamExt;
 use jsonrpc_pubsub::manager::SubscriptionManager;
 use moonbeam_core_primitives::{Block, Hash};
+use moonbeam_rpc_migrations::{Migrations, MigrationsServer};
 use moonbeam_rpc_txpool::{TxPool, TxPoolServer};
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 use sc_client_api::{
@@ -130,6 +131,9 @@ where
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
 		client.clone(),
 	)));
+	io.extend_with(MigrationsServer::to_delegate(Migrations::new(
+		client.clone(),
+	)));
 	// TODO: are we supporting signing?
 	let signers = Vec::new();
 
@@ -15,10 +15,24 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Minimal Pallet that stores the numeric Ethereum-style chain id in the runtime.
+//!
+//! The chain id can be changed by root, eg. when relaunching or forking a testnet. The chain ids
+//! used before are kept, and the CheckRetiredChainId signed extension rejects Ethereum
+//! transactions signed for one of them. Once a chain id was retired, it also rejects the
+//! transactions that are not signed for a chain id (pre EIP-155), as they could come from the
+//! chain using it.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::pallet;
+use frame_support::{pallet, traits::IsSubType};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::{fmt::Debug, marker::PhantomData};
 
 pub use pallet::*;
 
@@ -26,6 +40,8 @@ pub use pallet::*;
 pub mod pallet {
 
 	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
 
 	/// The Ethereum Chain Id Pallet
 	#[pallet::pallet]
@@ -33,7 +49,13 @@ pub mod pallet {
 
 	/// Configuration trait of this pallet.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {}
+	pub trait Config: frame_system::Config {
+		/// Overarching event type
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// The maximum number of retired chain ids
+		#[pallet::constant]
+		type MaxPreviousChainIds: Get<u32>;
+	}
 
 	impl<T: Config> Get<u64> for Pallet<T> {
 		fn get() -> u64 {
@@ -41,10 +63,30 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event {
+		/// The chain id was changed \[old chain id, new chain id\]
+		ChainIdChanged(u64, u64),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The chain id already is the given one
+		ChainIdUnchanged,
+		/// The current chain id cannot be retired as MaxPreviousChainIds are retired already
+		TooManyPreviousChainIds,
+	}
+
 	#[pallet::storage]
 	#[pallet::getter(fn chain_id)]
 	pub type ChainId<T> = StorageValue<_, u64, ValueQuery>;
 
+	/// The chain ids used before the current one, at most MaxPreviousChainIds
+	#[pallet::storage]
+	#[pallet::getter(fn previous_chain_ids)]
+	pub type PreviousChainIds<T> = StorageValue<_, Vec<u64>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(Default)]
 	pub struct GenesisConfig {
@@ -57,4 +99,117 @@ pub mod pallet {
 			ChainId::<T>::put(self.chain_id);
 		}
 	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Change the chain id. The current one is retired, so that transactions signed for it
+		/// are no longer valid. Retired chain ids can be set again. Fails if MaxPreviousChainIds
+		/// are retired already, unless the new chain id is one of them.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn set_chain_id(origin: OriginFor<T>, chain_id: u64) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			let old_chain_id = ChainId::<T>::get();
+			ensure!(old_chain_id != chain_id, Error::<T>::ChainIdUnchanged);
+
+			PreviousChainIds::<T>::try_mutate(|previous| {
+				previous.retain(|id| *id != chain_id);
+				ensure!(
+					previous.len() < T::MaxPreviousChainIds::get() as usize,
+					Error::<T>::TooManyPreviousChainIds
+				);
+				previous.push(old_chain_id);
+				Ok::<_, Error<T>>(())
+			})?;
+			ChainId::<T>::put(chain_id);
+			Self::deposit_event(Event::ChainIdChanged(old_chain_id, chain_id));
+
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether the chain id was used by this chain before the current one
+		pub fn is_retired(chain_id: u64) -> bool {
+			PreviousChainIds::<T>::get().contains(&chain_id)
+		}
+
+		/// Whether the chain id was changed since genesis
+		pub fn has_retired_chain_ids() -> bool {
+			!PreviousChainIds::<T>::get().is_empty()
+		}
+	}
+}
+
+/// The custom error code of Ethereum transactions signed for a retired chain id
+pub const RETIRED_CHAIN_ID_ERROR: u8 = 10;
+
+/// The custom error code of Ethereum transactions not signed for a chain id, once a chain id was
+/// retired
+pub const UNPROTECTED_TRANSACTION_ERROR: u8 = 11;
+
+/// Rejects Ethereum transactions signed for a chain id that was retired by set_chain_id, so
+/// they can't be replayed on this chain. Once a chain id was retired, transactions not signed for
+/// a chain id (pre EIP-155) are rejected too, as they may have been sent to the chain using it.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckRetiredChainId<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckRetiredChainId<T> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckRetiredChainId<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> Debug for CheckRetiredChainId<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "CheckRetiredChainId")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T> SignedExtension for CheckRetiredChainId<T>
+where
+	T: Config + pallet_ethereum::Config + Send + Sync,
+	<T as frame_system::Config>::Call: IsSubType<pallet_ethereum::Call<T>>,
+{
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+	const IDENTIFIER: &'static str = "CheckRetiredChainId";
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate_unsigned(
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if let Some(pallet_ethereum::Call::transact(transaction)) = call.is_sub_type() {
+			match transaction.signature.chain_id() {
+				Some(chain_id) if Pallet::<T>::is_retired(chain_id) => {
+					return InvalidTransaction::Custom(RETIRED_CHAIN_ID_ERROR).into();
+				}
+				None if Pallet::<T>::has_retired_chain_ids() => {
+					return InvalidTransaction::Custom(UNPROTECTED_TRANSACTION_ERROR).into();
+				}
+				_ => (),
+			}
+		}
+
+		Ok(ValidTransaction::default())
+	}
 }
@@ -21,6 +21,24 @@
 //! be that the state cache eliminates this cost almost entirely. I wonder if that can or should be
 //! reflected in the weight calculation.
 //!
+//! In addition to the mode-wide filters, governance can pause individual calls by their
+//! `(pallet_name, call_name)` pair. Paused calls are rejected in both normal and maintenance mode
+//! until they are explicitly unpaused. The runtime declares a set of pallets whose calls can never
+//! be paused so that the chain cannot be locked out of its own recovery path. Mandatory calls
+//! (inherents) are never filtered, as a block without them would be invalid.
+//!
+//! While in maintenance mode, inbound XCM is not executed either. The runtime routes its
+//! downward and horizontal message handlers through this pallet, which stores any message it
+//! receives during maintenance instead of executing it. Once normal operation resumes the backlog
+//! is handed, in order and bounded by the available weight, to the normal handlers from
+//! `on_idle` and ahead of any newly received message.
+//!
+//! The runtime can also decide which pallets' hooks keep running during maintenance. To do so it
+//! passes `ExecutiveHooks` to the executive instead of `AllPallets`, and configures the hooks to
+//! use in each mode. Only hooks that pick up where they left off should be suppressed, so that the
+//! skipped work is postponed rather than lost; the parachain-staking round transition is such an
+//! example. Runtime upgrade hooks always run regardless of the mode.
+//!
 //! Possible future improvements
 //! 1. This could be more configureable by letting the runtime developer specify a type (probably an
 //! enum) that can be converted into a filter. Similar end result (but different implementation) as
@@ -36,10 +54,6 @@
 //! 4. Maintenance mode timeout. To avoid getting stuck in maintenance mode. It could automatically
 //! switch back to normal mode after a pre-decided number of blocks. Maybe there could be an
 //! extrinsic to extend the maintenance time.
-//!
-//! 5. Let the runtime developer configure which pallets' on_initialize and on_finalize hooks get
-//! called. This would allow to determine whether eg staking elections should still occur and
-//! democracy referenda still mature
 
 #![allow(non_camel_case_types)]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -53,11 +67,39 @@ use frame_support::pallet;
 
 pub use pallet::*;
 
+/// Lets other pallets put the chain in and out of maintenance mode without going through the
+/// MaintenanceOrigin, eg. while a multi-block migration is running.
+pub trait MaintenanceModeControl {
+	/// Whether the chain is currently in maintenance mode
+	fn is_in_maintenance_mode() -> bool;
+	/// Place the chain in maintenance mode. Does nothing if it already is.
+	fn enter_maintenance_mode();
+	/// Return the chain to normal operating mode. Does nothing if it already is.
+	fn resume_normal_operation();
+}
+
+impl MaintenanceModeControl for () {
+	fn is_in_maintenance_mode() -> bool {
+		false
+	}
+	fn enter_maintenance_mode() {}
+	fn resume_normal_operation() {}
+}
+
 #[pallet]
 pub mod pallet {
+	use super::MaintenanceModeControl;
+	use cumulus_primitives_core::{
+		relay_chain::BlockNumber as RelayBlockNumber, DmpMessageHandler, ParaId, XcmpMessageHandler,
+	};
+	use frame_support::dispatch::GetDispatchInfo;
 	use frame_support::pallet_prelude::*;
-	use frame_support::traits::{Contains, EnsureOrigin};
+	use frame_support::traits::{
+		CallMetadata, Contains, EnsureOrigin, GetCallMetadata, OffchainWorker, OnFinalize, OnIdle,
+		OnInitialize, OnRuntimeUpgrade,
+	};
 	use frame_system::pallet_prelude::*;
+	use sp_std::{convert::TryFrom, vec::Vec};
 
 	/// Pallet for migrations
 	#[pallet::pallet]
@@ -81,8 +123,83 @@ pub mod pallet {
 		/// able to return to normal mode. For example, if your MaintenanceOrigin is a council, make
 		/// sure that your councilors can still cast votes.
 		type MaintenanceOrigin: EnsureOrigin<Self::Origin>;
+		/// The pallets whose calls can never be paused, identified by their name in the runtime.
+		/// This should at least contain this pallet itself and whatever pallets the
+		/// MaintenanceOrigin relies on, so that a paused call can always be unpaused, as well as
+		/// the pallets providing inherents.
+		type UnpausablePallets: Contains<Vec<u8>>;
+		/// The maximum length of a pallet or call name that can be paused
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		/// The handler that executes downward messages when not in maintenance mode
+		type NormalDmpHandler: DmpMessageHandler;
+		/// The handler that executes horizontal messages when not in maintenance mode
+		type NormalXcmpHandler: XcmpMessageHandler;
+		/// The maximum size of a single storage entry of the XCM queue. Larger inbound messages
+		/// are split across several entries.
+		#[pallet::constant]
+		type MaxQueuedXcmSize: Get<u32>;
+		/// The executive hooks used in normal operating mode. Use the runtime's AllPallets here
+		/// unless you want to change the behaviour of the hooks outside of maintenance.
+		type NormalExecutiveHooks: OnRuntimeUpgrade
+			+ OnInitialize<Self::BlockNumber>
+			+ OnIdle<Self::BlockNumber>
+			+ OnFinalize<Self::BlockNumber>
+			+ OffchainWorker<Self::BlockNumber>;
+		/// The executive hooks used in maintenance mode. Leaving a pallet out of these suppresses
+		/// its on_initialize, on_idle, on_finalize and offchain_worker hooks during maintenance.
+		/// The on_runtime_upgrade hook is always taken from NormalExecutiveHooks.
+		type MaintenanceExecutiveHooks: OnRuntimeUpgrade
+			+ OnInitialize<Self::BlockNumber>
+			+ OnIdle<Self::BlockNumber>
+			+ OnFinalize<Self::BlockNumber>
+			+ OffchainWorker<Self::BlockNumber>;
 	}
 
+	/// A pallet or call name as stored in the paused calls set
+	pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLength>;
+
+	/// An inbound XCM message that was received while in maintenance mode
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, MaxEncodedLen)]
+	pub enum QueuedXcmMessage<Data> {
+		/// A message from the relay chain
+		Downward {
+			sent_at: RelayBlockNumber,
+			data: Data,
+		},
+		/// A message from a sibling parachain
+		Horizontal {
+			sender: u32,
+			sent_at: RelayBlockNumber,
+			data: Data,
+		},
+	}
+
+	impl<Data> QueuedXcmMessage<Data> {
+		fn map_data<NewData>(self, f: impl FnOnce(Data) -> NewData) -> QueuedXcmMessage<NewData> {
+			match self {
+				QueuedXcmMessage::Downward { sent_at, data } => QueuedXcmMessage::Downward {
+					sent_at,
+					data: f(data),
+				},
+				QueuedXcmMessage::Horizontal {
+					sender,
+					sent_at,
+					data,
+				} => QueuedXcmMessage::Horizontal {
+					sender,
+					sent_at,
+					data: f(data),
+				},
+			}
+		}
+	}
+
+	/// A chunk of at most MaxQueuedXcmSize bytes of a queued XCM message
+	pub type XcmChunkOf<T> = BoundedVec<u8, <T as Config>::MaxQueuedXcmSize>;
+
+	pub type QueuedXcmMessageOf<T> = QueuedXcmMessage<XcmChunkOf<T>>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
 	pub enum Event {
@@ -90,6 +207,10 @@ pub mod pallet {
 		EnteredMaintenanceMode,
 		/// The chain returned to its normal operating state
 		NormalOperationResumed,
+		/// A call was paused \[pallet_name, call_name\]
+		CallPaused(Vec<u8>, Vec<u8>),
+		/// A call was unpaused \[pallet_name, call_name\]
+		CallUnpaused(Vec<u8>, Vec<u8>),
 	}
 
 	/// An error that can occur while executing this pallet's extrinsics.
@@ -99,6 +220,14 @@ pub mod pallet {
 		AlreadyInMaintenanceMode,
 		/// The chain cannot resume normal operation because it is not in maintenance mode
 		NotInMaintenanceMode,
+		/// The call cannot be paused because its pallet is in the unpausable set
+		PalletCannotBePaused,
+		/// The call cannot be paused because it is already paused
+		CallAlreadyPaused,
+		/// The call cannot be unpaused because it is not paused
+		CallNotPaused,
+		/// The pallet or call name exceeds MaxNameLength
+		NameTooLong,
 	}
 
 	#[pallet::storage]
@@ -106,6 +235,46 @@ pub mod pallet {
 	/// Whether the site is in maintenance mode
 	type MaintenanceMode<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn paused_calls)]
+	/// The set of calls that are currently paused, keyed by (pallet_name, call_name)
+	type PausedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, (NameOf<T>, NameOf<T>), (), OptionQuery>;
+
+	#[pallet::storage]
+	/// Inbound XCM messages waiting to be executed, keyed by their position in the queue
+	type QueuedXcmMessages<T: Config> =
+		StorageMap<_, Twox64Concat, u32, QueuedXcmMessageOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	/// The data of queued XCM messages that did not fit in their queue entry, keyed by the
+	/// position of the message in the queue and the index of the chunk
+	type QueuedXcmMessageChunks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, u32, XcmChunkOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn xcm_queue_head)]
+	/// Index of the next queued XCM message to be executed
+	type XcmQueueHead<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn xcm_queue_tail)]
+	/// Index at which the next inbound XCM message will be queued
+	type XcmQueueTail<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Drain the XCM backlog with whatever weight is left at the end of the block
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let weight = T::DbWeight::get().read;
+			if MaintenanceMode::<T>::get() {
+				return weight;
+			}
+
+			weight + Self::service_xcm_queue(remaining_weight.saturating_sub(weight))
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Place the chain in maintenance mode
@@ -161,6 +330,190 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Pause a single call, identified by its pallet and call names as they appear in the
+		/// runtime metadata. The call will be filtered regardless of the operating mode.
+		///
+		/// Weight cost is:
+		/// * One DB read to ensure the call is not already paused
+		/// * Two DB writes - 1 for the paused set and 1 for the event
+		#[pallet::weight(T::DbWeight::get().read + 2 * T::DbWeight::get().write)]
+		pub fn pause_call(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			call_name: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			// Ensure Origin
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+
+			// Never allow pausing a pallet we may need to get out of this situation
+			ensure!(
+				!T::UnpausablePallets::contains(&pallet_name),
+				Error::<T>::PalletCannotBePaused
+			);
+
+			let key =
+				Self::paused_call_key(&pallet_name, &call_name).ok_or(Error::<T>::NameTooLong)?;
+			ensure!(
+				!PausedCalls::<T>::contains_key(&key),
+				Error::<T>::CallAlreadyPaused
+			);
+
+			// Write to storage
+			PausedCalls::<T>::insert(key, ());
+
+			// Event
+			<Pallet<T>>::deposit_event(Event::CallPaused(pallet_name, call_name));
+
+			Ok(().into())
+		}
+
+		/// Unpause a call previously paused with `pause_call`
+		///
+		/// Weight cost is:
+		/// * One DB read to ensure the call is paused
+		/// * Two DB writes - 1 for the paused set and 1 for the event
+		#[pallet::weight(T::DbWeight::get().read + 2 * T::DbWeight::get().write)]
+		pub fn unpause_call(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			call_name: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			// Ensure Origin
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+
+			let key =
+				Self::paused_call_key(&pallet_name, &call_name).ok_or(Error::<T>::CallNotPaused)?;
+			ensure!(
+				PausedCalls::<T>::contains_key(&key),
+				Error::<T>::CallNotPaused
+			);
+
+			// Write to storage
+			PausedCalls::<T>::remove(key);
+
+			// Event
+			<Pallet<T>>::deposit_event(Event::CallUnpaused(pallet_name, call_name));
+
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Build the paused calls storage key, returning None if either name is too long to
+		/// have ever been paused.
+		fn paused_call_key(pallet_name: &[u8], call_name: &[u8]) -> Option<(NameOf<T>, NameOf<T>)> {
+			let pallet_name = NameOf::<T>::try_from(pallet_name.to_vec()).ok()?;
+			let call_name = NameOf::<T>::try_from(call_name.to_vec()).ok()?;
+			Some((pallet_name, call_name))
+		}
+
+		/// Whether there are no inbound XCM messages waiting to be executed
+		pub fn xcm_queue_is_empty() -> bool {
+			XcmQueueHead::<T>::get() == XcmQueueTail::<T>::get()
+		}
+
+		/// Store an inbound XCM message at the back of the queue, returning the weight consumed.
+		/// Messages larger than MaxQueuedXcmSize are split into chunks, so that none is lost.
+		fn enqueue_xcm_message(
+			mut data: Vec<u8>,
+			to_message: impl FnOnce(XcmChunkOf<T>) -> QueuedXcmMessageOf<T>,
+		) -> Weight {
+			let chunk_size = T::MaxQueuedXcmSize::get().max(1) as usize;
+			let rest = data.split_off(data.len().min(chunk_size));
+			let to_chunk = |data: Vec<u8>| {
+				XcmChunkOf::<T>::try_from(data).expect("chunk is at most MaxQueuedXcmSize; qed")
+			};
+
+			let tail = XcmQueueTail::<T>::get();
+			QueuedXcmMessages::<T>::insert(tail, to_message(to_chunk(data)));
+			let mut writes = 2;
+			for (index, chunk) in rest.chunks(chunk_size).enumerate() {
+				QueuedXcmMessageChunks::<T>::insert(tail, index as u32, to_chunk(chunk.to_vec()));
+				writes += 1;
+			}
+			XcmQueueTail::<T>::put(tail.wrapping_add(1));
+
+			T::DbWeight::get().reads_writes(1, writes)
+		}
+
+		/// Storing inbound messages cannot be refused without losing them, as they are handed only
+		/// once. Their number and size per block are bounded by the relay chain configuration, so
+		/// the weight reported by the handlers is capped at the `max_weight` reserved for them.
+		fn bound_handler_weight(weight: Weight, max_weight: Weight) -> Weight {
+			weight.min(max_weight)
+		}
+
+		/// Remove the queued XCM message at `index` along with the rest of its data. Returns the
+		/// message and the number of chunks it was split into.
+		fn take_xcm_message(index: u32) -> Option<(QueuedXcmMessage<Vec<u8>>, u32)> {
+			let mut chunks = 0;
+			let message = QueuedXcmMessages::<T>::take(index)?.map_data(|data| {
+				let mut data = data.into_inner();
+				while let Some(chunk) = QueuedXcmMessageChunks::<T>::take(index, chunks) {
+					data.extend(chunk.into_inner());
+					chunks += 1;
+				}
+				data
+			});
+
+			Some((message, chunks))
+		}
+
+		/// Hand queued XCM messages, oldest first, to the normal handlers until the queue is
+		/// empty or `max_weight` is exhausted. Returns the weight consumed.
+		pub(crate) fn service_xcm_queue(max_weight: Weight) -> Weight {
+			// Reading the head and tail, plus taking one message, looking for its first extra
+			// chunk and moving the head
+			let mut weight_used = T::DbWeight::get().reads(2);
+			let per_message_weight = T::DbWeight::get().reads_writes(2, 2);
+
+			let mut head = XcmQueueHead::<T>::get();
+			let tail = XcmQueueTail::<T>::get();
+
+			while head != tail && weight_used.saturating_add(per_message_weight) < max_weight {
+				weight_used += per_message_weight;
+				let available_weight = max_weight - weight_used;
+
+				if let Some((message, chunks)) = Self::take_xcm_message(head) {
+					let chunks_weight =
+						T::DbWeight::get().reads_writes(chunks as Weight, chunks as Weight);
+					weight_used = weight_used.saturating_add(chunks_weight);
+					let available_weight = available_weight.saturating_sub(chunks_weight);
+
+					let message_weight = match message {
+						QueuedXcmMessage::Downward { sent_at, data } => {
+							T::NormalDmpHandler::handle_dmp_messages(
+								sp_std::iter::once((sent_at, data)),
+								available_weight,
+							)
+						}
+						QueuedXcmMessage::Horizontal {
+							sender,
+							sent_at,
+							data,
+						} => T::NormalXcmpHandler::handle_xcmp_messages(
+							sp_std::iter::once((ParaId::from(sender), sent_at, &data[..])),
+							available_weight,
+						),
+					};
+					weight_used = weight_used.saturating_add(message_weight);
+				}
+
+				head = head.wrapping_add(1);
+			}
+
+			XcmQueueHead::<T>::put(head);
+
+			weight_used
+		}
+
+		/// Whether the given call has been paused through `pause_call`
+		pub fn is_call_paused(pallet_name: &[u8], call_name: &[u8]) -> bool {
+			Self::paused_call_key(pallet_name, call_name)
+				.map(PausedCalls::<T>::contains_key)
+				.unwrap_or(false)
+		}
 	}
 
 	#[derive(Default)]
@@ -180,13 +533,175 @@ pub mod pallet {
 		}
 	}
 
-	impl<T: Config> Contains<T::Call> for Pallet<T> {
+	impl<T: Config> Contains<T::Call> for Pallet<T>
+	where
+		T::Call: GetCallMetadata + GetDispatchInfo,
+	{
 		fn contains(call: &T::Call) -> bool {
-			if MaintenanceMode::<T>::get() {
+			// Filtering an inherent would make the block invalid
+			if call.get_dispatch_info().class == DispatchClass::Mandatory {
+				return true;
+			}
+
+			let allowed_by_mode = if MaintenanceMode::<T>::get() {
 				T::MaintenanceCallFilter::contains(call)
 			} else {
 				T::NormalCallFilter::contains(call)
+			};
+
+			if !allowed_by_mode {
+				return false;
+			}
+
+			let CallMetadata {
+				pallet_name,
+				function_name,
+			} = call.get_call_metadata();
+			!Self::is_call_paused(pallet_name.as_bytes(), function_name.as_bytes())
+		}
+	}
+
+	impl<T: Config> DmpMessageHandler for Pallet<T> {
+		fn handle_dmp_messages(
+			iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+			max_weight: Weight,
+		) -> Weight {
+			let in_maintenance = MaintenanceMode::<T>::get();
+
+			// Nothing to hold back, execute right away
+			if !in_maintenance && Self::xcm_queue_is_empty() {
+				return T::NormalDmpHandler::handle_dmp_messages(iter, max_weight);
+			}
+
+			// Otherwise queue the new messages behind the backlog to preserve ordering
+			let mut weight = T::DbWeight::get().reads(3);
+			for (sent_at, data) in iter {
+				weight += Self::enqueue_xcm_message(data, |data| QueuedXcmMessage::Downward {
+					sent_at,
+					data,
+				});
+			}
+
+			if !in_maintenance {
+				weight += Self::service_xcm_queue(max_weight.saturating_sub(weight));
+			}
+
+			Self::bound_handler_weight(weight, max_weight)
+		}
+	}
+
+	impl<T: Config> XcmpMessageHandler for Pallet<T> {
+		fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+			iter: I,
+			max_weight: Weight,
+		) -> Weight {
+			let in_maintenance = MaintenanceMode::<T>::get();
+
+			// Nothing to hold back, execute right away
+			if !in_maintenance && Self::xcm_queue_is_empty() {
+				return T::NormalXcmpHandler::handle_xcmp_messages(iter, max_weight);
+			}
+
+			// Otherwise queue the new messages behind the backlog to preserve ordering
+			let mut weight = T::DbWeight::get().reads(3);
+			for (sender, sent_at, data) in iter {
+				weight +=
+					Self::enqueue_xcm_message(data.to_vec(), |data| QueuedXcmMessage::Horizontal {
+						sender: sender.into(),
+						sent_at,
+						data,
+					});
+			}
+
+			if !in_maintenance {
+				weight += Self::service_xcm_queue(max_weight.saturating_sub(weight));
+			}
+
+			Self::bound_handler_weight(weight, max_weight)
+		}
+	}
+
+	impl<T: Config> MaintenanceModeControl for Pallet<T> {
+		fn is_in_maintenance_mode() -> bool {
+			MaintenanceMode::<T>::get()
+		}
+
+		fn enter_maintenance_mode() {
+			if !MaintenanceMode::<T>::get() {
+				MaintenanceMode::<T>::put(true);
+				<Pallet<T>>::deposit_event(Event::EnteredMaintenanceMode);
 			}
 		}
+
+		fn resume_normal_operation() {
+			if MaintenanceMode::<T>::get() {
+				MaintenanceMode::<T>::put(false);
+				<Pallet<T>>::deposit_event(Event::NormalOperationResumed);
+			}
+		}
+	}
+
+	/// The hooks to hand to the executive in place of AllPallets. Depending on the mode, they
+	/// dispatch to either NormalExecutiveHooks or MaintenanceExecutiveHooks.
+	pub struct ExecutiveHooks<T>(PhantomData<T>);
+
+	impl<T: Config> OnInitialize<T::BlockNumber> for ExecutiveHooks<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let weight = T::DbWeight::get().read;
+			if MaintenanceMode::<T>::get() {
+				weight.saturating_add(T::MaintenanceExecutiveHooks::on_initialize(n))
+			} else {
+				weight.saturating_add(T::NormalExecutiveHooks::on_initialize(n))
+			}
+		}
+	}
+
+	impl<T: Config> OnIdle<T::BlockNumber> for ExecutiveHooks<T> {
+		fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let weight = T::DbWeight::get().read;
+			let remaining_weight = remaining_weight.saturating_sub(weight);
+			if MaintenanceMode::<T>::get() {
+				weight.saturating_add(T::MaintenanceExecutiveHooks::on_idle(n, remaining_weight))
+			} else {
+				weight.saturating_add(T::NormalExecutiveHooks::on_idle(n, remaining_weight))
+			}
+		}
+	}
+
+	impl<T: Config> OnFinalize<T::BlockNumber> for ExecutiveHooks<T> {
+		fn on_finalize(n: T::BlockNumber) {
+			if MaintenanceMode::<T>::get() {
+				T::MaintenanceExecutiveHooks::on_finalize(n)
+			} else {
+				T::NormalExecutiveHooks::on_finalize(n)
+			}
+		}
+	}
+
+	impl<T: Config> OffchainWorker<T::BlockNumber> for ExecutiveHooks<T> {
+		fn offchain_worker(n: T::BlockNumber) {
+			if MaintenanceMode::<T>::get() {
+				T::MaintenanceExecutiveHooks::offchain_worker(n)
+			} else {
+				T::NormalExecutiveHooks::offchain_worker(n)
+			}
+		}
+	}
+
+	// Storage migrations must run no matter the mode, so runtime upgrades always use the normal hooks
+	impl<T: Config> OnRuntimeUpgrade for ExecutiveHooks<T> {
+		fn on_runtime_upgrade() -> Weight {
+			T::NormalExecutiveHooks::on_runtime_upgrade()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			T::NormalExecutiveHooks::pre_upgrade()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			T::NormalExecutiveHooks::post_upgrade()
+		}
 	}
 }
@@ -17,10 +17,16 @@
 //! A minimal runtime including the maintenance-mode pallet
 use super::*;
 use crate as pallet_maintenance_mode;
+use cumulus_primitives_core::{
+	relay_chain::BlockNumber as RelayBlockNumber, DmpMessageHandler, ParaId, XcmpMessageHandler,
+};
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{Contains, Everything, GenesisBuild},
-	weights::Weight,
+	traits::{
+		Contains, Everything, GenesisBuild, Get, OffchainWorker, OnFinalize, OnIdle, OnInitialize,
+		OnRuntimeUpgrade,
+	},
+	weights::{RuntimeDbWeight, Weight},
 };
 use frame_system::EnsureRoot;
 use sp_core::H256;
@@ -29,6 +35,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
 	Perbill,
 };
+use std::cell::RefCell;
 
 //TODO use TestAccount once it is in a common place (currently it lives with democracy precompiles)
 pub type AccountId = u64;
@@ -46,6 +53,7 @@ construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		MaintenanceMode: pallet_maintenance_mode::{Pallet, Call, Storage, Event, Config},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 	}
 );
 
@@ -55,10 +63,14 @@ parameter_types! {
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
 	pub const SS58Prefix: u8 = 42;
+	pub const MaxNameLength: u32 = 32;
+	pub const MaxQueuedXcmSize: u32 = 16;
+	pub const Normal: &'static str = "normal";
+	pub const Maintenance: &'static str = "maintenance";
 }
 impl frame_system::Config for Test {
 	type BaseCallFilter = MaintenanceMode;
-	type DbWeight = ();
+	type DbWeight = MockDbWeight;
 	type Origin = Origin;
 	type Index = u64;
 	type BlockNumber = BlockNumber;
@@ -82,6 +94,33 @@ impl frame_system::Config for Test {
 	type OnSetCode = ();
 }
 
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+thread_local! {
+	pub static DB_WEIGHT: RefCell<RuntimeDbWeight> =
+		RefCell::new(RuntimeDbWeight { read: 0, write: 0 });
+}
+
+/// Set the weight of the storage accesses, which are free by default
+pub(crate) fn set_db_weight(db_weight: RuntimeDbWeight) {
+	DB_WEIGHT.with(|w| *w.borrow_mut() = db_weight);
+}
+
+pub struct MockDbWeight;
+impl Get<RuntimeDbWeight> for MockDbWeight {
+	fn get() -> RuntimeDbWeight {
+		DB_WEIGHT.with(|w| *w.borrow())
+	}
+}
+
 /// During maintenance mode we will not allow any calls.
 pub struct MaintenanceCallFilter;
 impl Contains<Call> for MaintenanceCallFilter {
@@ -90,11 +129,128 @@ impl Contains<Call> for MaintenanceCallFilter {
 	}
 }
 
+/// The maintenance mode pallet itself can never be paused.
+pub struct UnpausablePallets;
+impl Contains<Vec<u8>> for UnpausablePallets {
+	fn contains(pallet_name: &Vec<u8>) -> bool {
+		pallet_name.as_slice() == b"MaintenanceMode"
+	}
+}
+
+/// An inbound XCM message as seen by the mock handlers
+#[derive(Clone, Debug, PartialEq)]
+pub enum HandledXcmMessage {
+	Downward(RelayBlockNumber, Vec<u8>),
+	Horizontal(ParaId, RelayBlockNumber, Vec<u8>),
+}
+
+/// The weight the mock handlers consume for each message they execute
+pub const MOCK_MESSAGE_WEIGHT: Weight = 10;
+
+thread_local! {
+	pub static HANDLED_XCM_MESSAGES: RefCell<Vec<HandledXcmMessage>> = RefCell::new(Vec::new());
+}
+
+/// The messages executed by the mock handlers so far, oldest first
+pub(crate) fn handled_xcm_messages() -> Vec<HandledXcmMessage> {
+	HANDLED_XCM_MESSAGES.with(|m| m.borrow().clone())
+}
+
+/// Records every message it is handed, standing in for the DMP and XCMP queues.
+pub struct MockXcmHandler;
+impl DmpMessageHandler for MockXcmHandler {
+	fn handle_dmp_messages(
+		iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+		_max_weight: Weight,
+	) -> Weight {
+		HANDLED_XCM_MESSAGES.with(|m| {
+			let mut weight = 0;
+			for (sent_at, data) in iter {
+				m.borrow_mut()
+					.push(HandledXcmMessage::Downward(sent_at, data));
+				weight += MOCK_MESSAGE_WEIGHT;
+			}
+			weight
+		})
+	}
+}
+impl XcmpMessageHandler for MockXcmHandler {
+	fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+		iter: I,
+		_max_weight: Weight,
+	) -> Weight {
+		HANDLED_XCM_MESSAGES.with(|m| {
+			let mut weight = 0;
+			for (sender, sent_at, data) in iter {
+				m.borrow_mut().push(HandledXcmMessage::Horizontal(
+					sender,
+					sent_at,
+					data.to_vec(),
+				));
+				weight += MOCK_MESSAGE_WEIGHT;
+			}
+			weight
+		})
+	}
+}
+
+thread_local! {
+	pub static HOOKS_CALLED: RefCell<Vec<(&'static str, &'static str)>> = RefCell::new(Vec::new());
+}
+
+/// The (mode, hook) pairs called on the mock executive hooks so far, oldest first
+pub(crate) fn hooks_called() -> Vec<(&'static str, &'static str)> {
+	HOOKS_CALLED.with(|h| h.borrow().clone())
+}
+
+/// Executive hooks that record every call, tagged with the mode they were configured for.
+pub struct MockHooks<Mode>(sp_std::marker::PhantomData<Mode>);
+impl<Mode: Get<&'static str>> MockHooks<Mode> {
+	fn record(hook: &'static str) {
+		HOOKS_CALLED.with(|h| h.borrow_mut().push((Mode::get(), hook)));
+	}
+}
+impl<Mode: Get<&'static str>> OnInitialize<BlockNumber> for MockHooks<Mode> {
+	fn on_initialize(_n: BlockNumber) -> Weight {
+		Self::record("on_initialize");
+		0
+	}
+}
+impl<Mode: Get<&'static str>> OnIdle<BlockNumber> for MockHooks<Mode> {
+	fn on_idle(_n: BlockNumber, _remaining_weight: Weight) -> Weight {
+		Self::record("on_idle");
+		0
+	}
+}
+impl<Mode: Get<&'static str>> OnFinalize<BlockNumber> for MockHooks<Mode> {
+	fn on_finalize(_n: BlockNumber) {
+		Self::record("on_finalize");
+	}
+}
+impl<Mode: Get<&'static str>> OffchainWorker<BlockNumber> for MockHooks<Mode> {
+	fn offchain_worker(_n: BlockNumber) {
+		Self::record("offchain_worker");
+	}
+}
+impl<Mode: Get<&'static str>> OnRuntimeUpgrade for MockHooks<Mode> {
+	fn on_runtime_upgrade() -> Weight {
+		Self::record("on_runtime_upgrade");
+		0
+	}
+}
+
 impl Config for Test {
 	type Event = Event;
 	type NormalCallFilter = Everything;
 	type MaintenanceCallFilter = MaintenanceCallFilter;
 	type MaintenanceOrigin = EnsureRoot<AccountId>;
+	type UnpausablePallets = UnpausablePallets;
+	type MaxNameLength = MaxNameLength;
+	type NormalDmpHandler = MockXcmHandler;
+	type NormalXcmpHandler = MockXcmHandler;
+	type MaxQueuedXcmSize = MaxQueuedXcmSize;
+	type NormalExecutiveHooks = MockHooks<Normal>;
+	type MaintenanceExecutiveHooks = MockHooks<Maintenance>;
 }
 
 /// Externality builder for pallet maintenance mode's mock runtime
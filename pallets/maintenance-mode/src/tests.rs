@@ -15,9 +15,18 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Unit testing
-use crate::mock::{events, Call as OuterCall, ExtBuilder, Origin, Test};
-use crate::{Call, Error, Event};
-use frame_support::{assert_noop, assert_ok, dispatch::Dispatchable};
+use crate::mock::{
+	events, handled_xcm_messages, hooks_called, set_db_weight, Call as OuterCall, ExtBuilder,
+	HandledXcmMessage, MaintenanceMode, Origin, Test, MOCK_MESSAGE_WEIGHT,
+};
+use crate::{Call, Error, Event, ExecutiveHooks};
+use cumulus_primitives_core::{DmpMessageHandler, ParaId, XcmpMessageHandler};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::Dispatchable,
+	traits::{OffchainWorker, OnFinalize, OnIdle, OnInitialize, OnRuntimeUpgrade},
+	weights::RuntimeDbWeight,
+};
 use sp_runtime::DispatchError;
 
 #[test]
@@ -108,3 +117,414 @@ fn cannot_resume_normal_operation_while_already_operating_normally() {
 		);
 	})
 }
+
+#[test]
+fn can_pause_call() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.dispatch(Origin::root()));
+
+		assert_eq!(
+			events(),
+			vec![Event::CallPaused(b"System".to_vec(), b"remark".to_vec())]
+		);
+	})
+}
+
+#[test]
+fn cannot_remark_when_paused() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.dispatch(Origin::root()));
+
+		let call: OuterCall = frame_system::Call::remark(vec![]).into();
+		assert_noop!(call.dispatch(Origin::signed(1)), DispatchError::BadOrigin);
+	})
+}
+
+#[test]
+fn pausing_a_call_does_not_affect_other_calls() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.dispatch(Origin::root()));
+
+		let call: OuterCall = frame_system::Call::remark_with_event(vec![]).into();
+		assert_ok!(call.dispatch(Origin::signed(1)));
+	})
+}
+
+#[test]
+fn cannot_pause_call_from_wrong_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_noop!(call.dispatch(Origin::signed(1)), DispatchError::BadOrigin);
+	})
+}
+
+#[test]
+fn cannot_pause_call_when_already_paused() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.clone().dispatch(Origin::root()));
+		assert_noop!(
+			call.dispatch(Origin::root()),
+			Error::<Test>::CallAlreadyPaused
+		);
+	})
+}
+
+#[test]
+fn cannot_pause_unpausable_pallet() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(
+			b"MaintenanceMode".to_vec(),
+			b"resume_normal_operation".to_vec(),
+		)
+		.into();
+		assert_noop!(
+			call.dispatch(Origin::root()),
+			Error::<Test>::PalletCannotBePaused
+		);
+	})
+}
+
+#[test]
+fn mandatory_calls_are_never_filtered() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			let call: OuterCall = Call::pause_call(b"Timestamp".to_vec(), b"set".to_vec()).into();
+			assert_ok!(call.dispatch(Origin::root()));
+
+			let call: OuterCall = pallet_timestamp::Call::set(1).into();
+			assert_ok!(call.dispatch(Origin::none()));
+		})
+}
+
+#[test]
+fn cannot_pause_call_with_name_too_long() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), vec![b'a'; 33]).into();
+		assert_noop!(call.dispatch(Origin::root()), Error::<Test>::NameTooLong);
+	})
+}
+
+#[test]
+fn can_unpause_call() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.dispatch(Origin::root()));
+		let call: OuterCall = Call::unpause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.dispatch(Origin::root()));
+
+		let call: OuterCall = frame_system::Call::remark(vec![]).into();
+		assert_ok!(call.dispatch(Origin::signed(1)));
+
+		assert_eq!(
+			events(),
+			vec![
+				Event::CallPaused(b"System".to_vec(), b"remark".to_vec()),
+				Event::CallUnpaused(b"System".to_vec(), b"remark".to_vec()),
+			]
+		);
+	})
+}
+
+#[test]
+fn cannot_unpause_call_that_is_not_paused() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::unpause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_noop!(call.dispatch(Origin::root()), Error::<Test>::CallNotPaused);
+	})
+}
+
+#[test]
+fn paused_call_stays_paused_in_maintenance_mode() {
+	ExtBuilder::default().build().execute_with(|| {
+		let call: OuterCall = Call::pause_call(b"System".to_vec(), b"remark".to_vec()).into();
+		assert_ok!(call.dispatch(Origin::root()));
+		let call: OuterCall = Call::enter_maintenance_mode().into();
+		assert_ok!(call.dispatch(Origin::root()));
+		let call: OuterCall = Call::resume_normal_operation().into();
+		assert_ok!(call.dispatch(Origin::root()));
+
+		let call: OuterCall = frame_system::Call::remark(vec![]).into();
+		assert_noop!(call.dispatch(Origin::signed(1)), DispatchError::BadOrigin);
+	})
+}
+
+#[test]
+fn xcm_is_executed_during_normal_operation() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaintenanceMode::handle_dmp_messages(vec![(1, vec![1])].into_iter(), 1_000);
+		MaintenanceMode::handle_xcmp_messages(
+			vec![(ParaId::from(2000), 1, &[2u8][..])].into_iter(),
+			1_000,
+		);
+
+		assert_eq!(
+			handled_xcm_messages(),
+			vec![
+				HandledXcmMessage::Downward(1, vec![1]),
+				HandledXcmMessage::Horizontal(ParaId::from(2000), 1, vec![2]),
+			]
+		);
+		assert!(MaintenanceMode::xcm_queue_is_empty());
+	})
+}
+
+#[test]
+fn xcm_is_queued_during_maintenance_mode() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			MaintenanceMode::handle_dmp_messages(vec![(1, vec![1])].into_iter(), 1_000);
+			MaintenanceMode::handle_xcmp_messages(
+				vec![(ParaId::from(2000), 1, &[2u8][..])].into_iter(),
+				1_000,
+			);
+
+			assert_eq!(handled_xcm_messages(), vec![]);
+			assert_eq!(MaintenanceMode::xcm_queue_head(), 0);
+			assert_eq!(MaintenanceMode::xcm_queue_tail(), 2);
+		})
+}
+
+#[test]
+fn queued_xcm_is_not_drained_during_maintenance_mode() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			MaintenanceMode::handle_dmp_messages(vec![(1, vec![1])].into_iter(), 1_000);
+			MaintenanceMode::on_idle(1, 1_000);
+
+			assert_eq!(handled_xcm_messages(), vec![]);
+			assert!(!MaintenanceMode::xcm_queue_is_empty());
+		})
+}
+
+#[test]
+fn queued_xcm_is_drained_in_order_after_resuming() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			MaintenanceMode::handle_dmp_messages(vec![(1, vec![1])].into_iter(), 1_000);
+			MaintenanceMode::handle_xcmp_messages(
+				vec![(ParaId::from(2000), 2, &[2u8][..])].into_iter(),
+				1_000,
+			);
+			let call: OuterCall = Call::resume_normal_operation().into();
+			assert_ok!(call.dispatch(Origin::root()));
+
+			MaintenanceMode::on_idle(2, 1_000);
+
+			assert_eq!(
+				handled_xcm_messages(),
+				vec![
+					HandledXcmMessage::Downward(1, vec![1]),
+					HandledXcmMessage::Horizontal(ParaId::from(2000), 2, vec![2]),
+				]
+			);
+			assert!(MaintenanceMode::xcm_queue_is_empty());
+		})
+}
+
+#[test]
+fn queued_xcm_drain_is_weight_bounded() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			MaintenanceMode::handle_dmp_messages(
+				vec![(1, vec![1]), (1, vec![2]), (1, vec![3])].into_iter(),
+				1_000,
+			);
+			let call: OuterCall = Call::resume_normal_operation().into();
+			assert_ok!(call.dispatch(Origin::root()));
+
+			// The mock has no db weight, so each message costs exactly what the handler reports
+			MaintenanceMode::on_idle(2, 2 * MOCK_MESSAGE_WEIGHT);
+			assert_eq!(
+				handled_xcm_messages(),
+				vec![
+					HandledXcmMessage::Downward(1, vec![1]),
+					HandledXcmMessage::Downward(1, vec![2]),
+				]
+			);
+
+			MaintenanceMode::on_idle(3, 2 * MOCK_MESSAGE_WEIGHT);
+			assert_eq!(handled_xcm_messages().len(), 3);
+			assert!(MaintenanceMode::xcm_queue_is_empty());
+		})
+}
+
+#[test]
+fn xcm_queueing_weight_is_bounded_by_max_weight() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			set_db_weight(RuntimeDbWeight {
+				read: 10,
+				write: 10,
+			});
+			let data = vec![0u8; 1];
+			let messages: Vec<_> = (0..10).map(|_| (1, data.clone())).collect();
+
+			assert_eq!(
+				MaintenanceMode::handle_dmp_messages(messages.clone().into_iter(), 50),
+				50
+			);
+			assert_eq!(
+				MaintenanceMode::handle_xcmp_messages(
+					messages.iter().map(|(sent_at, data)| (
+						ParaId::from(2000),
+						*sent_at,
+						&data[..]
+					)),
+					50,
+				),
+				50
+			);
+
+			// No message is lost
+			assert_eq!(MaintenanceMode::xcm_queue_tail(), 20);
+		})
+}
+
+#[test]
+fn new_xcm_waits_behind_backlog() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			MaintenanceMode::handle_dmp_messages(vec![(1, vec![1])].into_iter(), 1_000);
+			let call: OuterCall = Call::resume_normal_operation().into();
+			assert_ok!(call.dispatch(Origin::root()));
+
+			MaintenanceMode::handle_dmp_messages(vec![(2, vec![2])].into_iter(), 1_000);
+
+			assert_eq!(
+				handled_xcm_messages(),
+				vec![
+					HandledXcmMessage::Downward(1, vec![1]),
+					HandledXcmMessage::Downward(2, vec![2]),
+				]
+			);
+			assert!(MaintenanceMode::xcm_queue_is_empty());
+		})
+}
+
+#[test]
+fn oversized_xcm_is_queued_in_chunks_and_drained_intact() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			// The mock queues at most 16 bytes per entry
+			let large: Vec<u8> = (0..40).collect();
+			MaintenanceMode::handle_dmp_messages(vec![(1, large.clone())].into_iter(), 1_000);
+			MaintenanceMode::handle_xcmp_messages(
+				vec![(ParaId::from(2000), 1, &large[..])].into_iter(),
+				1_000,
+			);
+			MaintenanceMode::handle_dmp_messages(vec![(2, vec![])].into_iter(), 1_000);
+
+			assert_eq!(handled_xcm_messages(), vec![]);
+			assert_eq!(MaintenanceMode::xcm_queue_tail(), 3);
+
+			let call: OuterCall = Call::resume_normal_operation().into();
+			assert_ok!(call.dispatch(Origin::root()));
+			MaintenanceMode::on_idle(2, 1_000);
+
+			assert_eq!(
+				handled_xcm_messages(),
+				vec![
+					HandledXcmMessage::Downward(1, large.clone()),
+					HandledXcmMessage::Horizontal(ParaId::from(2000), 1, large),
+					HandledXcmMessage::Downward(2, vec![]),
+				]
+			);
+			assert!(MaintenanceMode::xcm_queue_is_empty());
+		})
+}
+
+#[test]
+fn normal_hooks_are_called_during_normal_operation() {
+	ExtBuilder::default().build().execute_with(|| {
+		ExecutiveHooks::<Test>::on_initialize(1);
+		ExecutiveHooks::<Test>::on_idle(1, 1_000);
+		ExecutiveHooks::<Test>::on_finalize(1);
+		ExecutiveHooks::<Test>::offchain_worker(1);
+
+		assert_eq!(
+			hooks_called(),
+			vec![
+				("normal", "on_initialize"),
+				("normal", "on_idle"),
+				("normal", "on_finalize"),
+				("normal", "offchain_worker"),
+			]
+		);
+	})
+}
+
+#[test]
+fn maintenance_hooks_are_called_during_maintenance_mode() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			ExecutiveHooks::<Test>::on_initialize(1);
+			ExecutiveHooks::<Test>::on_idle(1, 1_000);
+			ExecutiveHooks::<Test>::on_finalize(1);
+			ExecutiveHooks::<Test>::offchain_worker(1);
+
+			assert_eq!(
+				hooks_called(),
+				vec![
+					("maintenance", "on_initialize"),
+					("maintenance", "on_idle"),
+					("maintenance", "on_finalize"),
+					("maintenance", "offchain_worker"),
+				]
+			);
+		})
+}
+
+#[test]
+fn normal_hooks_are_called_again_after_resuming() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			ExecutiveHooks::<Test>::on_initialize(1);
+			let call: OuterCall = Call::resume_normal_operation().into();
+			assert_ok!(call.dispatch(Origin::root()));
+			ExecutiveHooks::<Test>::on_initialize(2);
+
+			assert_eq!(
+				hooks_called(),
+				vec![
+					("maintenance", "on_initialize"),
+					("normal", "on_initialize"),
+				]
+			);
+		})
+}
+
+#[test]
+fn runtime_upgrade_always_uses_normal_hooks() {
+	ExtBuilder::default()
+		.with_maintenance_mode(true)
+		.build()
+		.execute_with(|| {
+			ExecutiveHooks::<Test>::on_runtime_upgrade();
+
+			assert_eq!(hooks_called(), vec![("normal", "on_runtime_upgrade")]);
+		})
+}
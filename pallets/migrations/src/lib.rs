@@ -25,25 +25,108 @@ mod mock;
 mod tests;
 
 use frame_support::{pallet, weights::Weight};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{Perbill, RuntimeDebug};
+use sp_std::prelude::*;
 
 pub use pallet::*;
+pub use pallet_maintenance_mode::MaintenanceModeControl;
 
 #[cfg(test)]
 #[macro_use]
 extern crate environmental;
 
+/// The outcome of a single step of a Migration
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct MigrationStepResult {
+	/// The weight consumed by this step
+	pub weight: Weight,
+	/// Whether the migration is done. If not, it is stepped again in a later block.
+	pub completed: bool,
+	/// Opaque progress cursor handed back to the next step. Ignored once the migration completed.
+	pub cursor: Vec<u8>,
+}
+
+impl MigrationStepResult {
+	/// The migration finished within this step
+	pub fn completed(weight: Weight) -> Self {
+		Self {
+			weight,
+			completed: true,
+			cursor: Vec::new(),
+		}
+	}
+
+	/// The migration made progress and must be resumed from `cursor` in a later block
+	pub fn in_progress(weight: Weight, cursor: Vec<u8>) -> Self {
+		Self {
+			weight,
+			completed: false,
+			cursor,
+		}
+	}
+}
+
+/// The progress of a single migration, as stored in MigrationState.
+///
+/// The variant indices are chosen so that the bool this storage used to hold decodes to the
+/// matching status: `false` is NotStarted and `true` is Completed.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub enum MigrationStatus {
+	/// The migration has not been stepped yet
+	#[codec(index = 0)]
+	NotStarted,
+	/// The migration has completed
+	#[codec(index = 1)]
+	Completed,
+	/// The migration has been stepped but is not done, it resumes from the given cursor
+	#[codec(index = 2)]
+	InProgress(Vec<u8>),
+}
+
+impl Default for MigrationStatus {
+	fn default() -> Self {
+		MigrationStatus::NotStarted
+	}
+}
+
 /// A Migration that must happen on-chain upon a runtime-upgrade
 pub trait Migration {
 	/// A human-readable name for this migration. Also used as storage key.
 	fn friendly_name(&self) -> &str;
 
-	/// Perform the required migration and return the weight consumed.
+	/// Perform one step of the migration and report the weight consumed and whether it is done.
 	///
-	/// Currently there is no way to migrate across blocks, so this method must (1) perform its full
-	/// migration and (2) not produce a block that has gone over-weight. Not meeting these strict
-	/// constraints will lead to a bricked chain upon a runtime upgrade because the parachain will
-	/// not be able to produce a block that the relay chain will accept.
-	fn migrate(&self, available_weight: Weight) -> Weight;
+	/// `cursor` is None on the first step, and afterwards the cursor returned by the previous step.
+	/// A migration that cannot finish within `available_weight` should return
+	/// `MigrationStepResult::in_progress` with a cursor to resume from; it is then stepped again
+	/// in `on_initialize` of the following blocks, with the chain in maintenance mode, until it
+	/// completes. Each step must still not produce a block that has gone over-weight, or the
+	/// parachain will not be able to produce a block that the relay chain will accept.
+	fn migrate(&self, available_weight: Weight, cursor: Option<Vec<u8>>) -> MigrationStepResult;
+
+	/// The friendly names of the migrations that must have completed before this one runs. They
+	/// must come before this migration in the MigrationsList, which is checked by the pallet's
+	/// integrity test. The pallet also refuses to run this migration, or to mark it as completed,
+	/// while any of them has not completed.
+	fn dependencies(&self) -> Vec<&str> {
+		Vec::new()
+	}
+
+	/// Check the state before the migration runs. Called by try-runtime for every migration that
+	/// has not completed yet. Data that post_upgrade needs can be set aside with
+	/// `frame_support::traits::OnRuntimeUpgradeHelpersExt`.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade(&self) -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	/// Check the state after the migration has completed. Called by try-runtime for every
+	/// migration pre_upgrade was called for.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(&self) -> Result<(), &'static str> {
+		Ok(())
+	}
 }
 
 #[pallet]
@@ -51,7 +134,6 @@ pub mod pallet {
 	use super::*;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
-	use sp_std::prelude::*;
 
 	/// Pallet for migrations
 	#[pallet::pallet]
@@ -64,6 +146,23 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		/// The list of migrations that will be performed
 		type MigrationsList: Get<Vec<Box<dyn Migration>>>;
+		/// Used to keep the chain in maintenance mode while migrations span multiple blocks
+		type MaintenanceMode: MaintenanceModeControl;
+		/// The origin allowed to re-run or force the state of a migration
+		type MigrationsOrigin: EnsureOrigin<Self::Origin>;
+		/// The fraction of the maximum block weight migrations may use in a single block
+		#[pallet::constant]
+		type MigrationsBudget: Get<Perbill>;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The migration is not part of the MigrationsList
+		UnknownMigration,
+		/// A migration this one depends on has not completed
+		DependencyNotCompleted,
+		/// A migration depending on this one has completed, and would have to run again too
+		DependentMigrationCompleted,
 	}
 
 	#[pallet::event]
@@ -73,6 +172,12 @@ pub mod pallet {
 		RuntimeUpgradeCompleted(Weight),
 		MigrationStarted(Vec<u8>),
 		MigrationCompleted(Vec<u8>, Weight),
+		/// A migration made progress but needs more blocks to complete \[name, weight\]
+		MigrationProgressed(Vec<u8>, Weight),
+		/// A migration will be run again from the start in the next block \[name\]
+		MigrationRescheduled(Vec<u8>),
+		/// The state of a migration was forced by governance \[name, completed\]
+		MigrationStateForced(Vec<u8>, bool),
 	}
 
 	#[pallet::hooks]
@@ -84,8 +189,7 @@ pub mod pallet {
 			log::warn!("Performing on_runtime_upgrade");
 
 			let mut weight: Weight = 0u64.into();
-			// TODO: derive a suitable value here, which is probably something < max_block
-			let available_weight: Weight = T::BlockWeights::get().max_block;
+			let available_weight = <Pallet<T>>::migrations_budget();
 
 			// start by flagging that we are not fully upgraded
 			<FullyUpgraded<T>>::put(false);
@@ -94,15 +198,261 @@ pub mod pallet {
 
 			weight += perform_runtime_upgrades::<T>(available_weight.saturating_sub(weight));
 
-			if !<FullyUpgraded<T>>::get() {
-				log::error!(
-					"migrations weren't completed in on_runtime_upgrade(), but we're not
-				configured for multi-block migrations; state is potentially inconsistent!"
+			if <FullyUpgraded<T>>::get() {
+				weight += resume_normal_operation_if_upgraded::<T>();
+			} else {
+				log::warn!(
+					"migrations weren't completed in on_runtime_upgrade(), entering maintenance mode
+					until they are completed in subsequent blocks"
 				);
+				weight += enter_maintenance_mode_until_upgraded::<T>();
 			}
 
+			// on_initialize of this same block must not step the migrations with a fresh budget
+			weight += T::DbWeight::get().writes(1);
+			<RuntimeUpgradeWeight<T>>::put(weight);
+
 			weight
 		}
+
+		/// Check that every migration only depends on migrations listed before it
+		fn integrity_test() {
+			let migrations = T::MigrationsList::get();
+			for (index, migration) in migrations.iter().enumerate() {
+				for dependency in migration.dependencies() {
+					assert!(
+						migrations[..index]
+							.iter()
+							.any(|previous| previous.friendly_name() == dependency),
+						"migration {} depends on {}, which must be listed before it",
+						migration.friendly_name(),
+						dependency,
+					);
+				}
+			}
+		}
+
+		/// Continue any migration that did not complete within on_runtime_upgrade. In the block of
+		/// a runtime upgrade, only what is left of the budget after on_runtime_upgrade is used.
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let mut weight = T::DbWeight::get().reads(2);
+			let available_weight = match <RuntimeUpgradeWeight<T>>::take() {
+				Some(upgrade_weight) => {
+					weight += T::DbWeight::get().writes(1);
+					<Pallet<T>>::migrations_budget().saturating_sub(upgrade_weight)
+				}
+				None => <Pallet<T>>::migrations_budget(),
+			};
+			if <FullyUpgraded<T>>::get() || weight >= available_weight {
+				return weight;
+			}
+
+			weight += perform_runtime_upgrades::<T>(available_weight - weight);
+
+			if <FullyUpgraded<T>>::get() {
+				weight += resume_normal_operation_if_upgraded::<T>();
+			} else {
+				// A migration scheduled by rerun_migration may not fit in a single block either
+				weight += enter_maintenance_mode_until_upgraded::<T>();
+			}
+
+			weight
+		}
+
+		/// Run the pre-upgrade checks of every migration that has not completed yet, and set
+		/// their names aside so post_upgrade checks the same migrations.
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+			let mut pending_migrations: Vec<Vec<u8>> = Vec::new();
+			for migration in &T::MigrationsList::get() {
+				let migration_name = migration.friendly_name();
+				if <MigrationState<T>>::get(migration_name.as_bytes()) == MigrationStatus::Completed
+				{
+					continue;
+				}
+
+				log::info!("running pre_upgrade checks of migration {}", migration_name);
+				migration.pre_upgrade().map_err(|e| {
+					log::error!(
+						"pre_upgrade checks of migration {} failed: {}",
+						migration_name,
+						e
+					);
+					e
+				})?;
+				pending_migrations.push(migration_name.as_bytes().into());
+			}
+
+			Self::set_temp_storage(pending_migrations, "pending_migrations");
+
+			Ok(())
+		}
+
+		/// Drive any migration left unfinished by on_runtime_upgrade to completion, as the
+		/// following blocks would, then run the post-upgrade checks of the migrations that were
+		/// pending in pre_upgrade.
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+			// A migration which doesn't complete within this many blocks is most likely stuck
+			const MAX_BLOCKS: u32 = 10_000;
+
+			// Conservatively, the budget left in the upgrade block is not used
+			<RuntimeUpgradeWeight<T>>::kill();
+
+			let mut blocks = 0u32;
+			while !<FullyUpgraded<T>>::get() {
+				if blocks >= MAX_BLOCKS {
+					return Err("migrations did not complete within the maximum number of blocks");
+				}
+				blocks += 1;
+
+				let available_weight = <Pallet<T>>::migrations_budget();
+				let weight = perform_runtime_upgrades::<T>(available_weight);
+				log::info!(
+					"migrations consumed {} out of {} available weight in block {} after the upgrade",
+					weight,
+					available_weight,
+					blocks
+				);
+			}
+			resume_normal_operation_if_upgraded::<T>();
+
+			let pending_migrations: Vec<Vec<u8>> = Self::get_temp_storage("pending_migrations")
+				.ok_or("pre_upgrade must set aside the pending migrations")?;
+			for migration in &T::MigrationsList::get() {
+				let migration_name = migration.friendly_name();
+				if !pending_migrations
+					.iter()
+					.any(|name| name.as_slice() == migration_name.as_bytes())
+				{
+					continue;
+				}
+
+				log::info!(
+					"running post_upgrade checks of migration {}",
+					migration_name
+				);
+				migration.post_upgrade().map_err(|e| {
+					log::error!(
+						"post_upgrade checks of migration {} failed: {}",
+						migration_name,
+						e
+					);
+					e
+				})?;
+			}
+
+			Ok(())
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Run a migration again from the start, eg. after it failed or only partially applied.
+		/// The migration runs in the next blocks' on_initialize, like any migration left
+		/// unfinished by a runtime upgrade.
+		///
+		/// The dependencies of the migration must have completed, and the migrations depending on
+		/// it must not have, since they would otherwise not see its new result.
+		#[pallet::weight(
+			T::DbWeight::get().reads_writes(T::MigrationsList::get().len() as Weight, 2)
+		)]
+		pub fn rerun_migration(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResultWithPostInfo {
+			T::MigrationsOrigin::ensure_origin(origin)?;
+			let migration = Self::find_migration(&name).ok_or(Error::<T>::UnknownMigration)?;
+			ensure!(
+				pending_dependency::<T>(migration.as_ref()).is_none(),
+				Error::<T>::DependencyNotCompleted
+			);
+			ensure!(
+				!Self::has_completed_dependent(&name),
+				Error::<T>::DependentMigrationCompleted
+			);
+
+			<MigrationState<T>>::insert(&name, MigrationStatus::NotStarted);
+			<FullyUpgraded<T>>::put(false);
+			Self::deposit_event(Event::MigrationRescheduled(name));
+
+			Ok(().into())
+		}
+
+		/// Mark a migration as completed, so that it won't run, or as not started, so that the
+		/// next runtime upgrade runs it again
+		///
+		/// A migration can only be marked as completed once its dependencies have, and as not
+		/// started while none of the migrations depending on it has completed.
+		#[pallet::weight(
+			T::DbWeight::get().reads_writes(T::MigrationsList::get().len() as Weight, 1)
+		)]
+		pub fn force_migration_state(
+			origin: OriginFor<T>,
+			name: Vec<u8>,
+			completed: bool,
+		) -> DispatchResultWithPostInfo {
+			T::MigrationsOrigin::ensure_origin(origin)?;
+			let migration = Self::find_migration(&name).ok_or(Error::<T>::UnknownMigration)?;
+			if completed {
+				ensure!(
+					pending_dependency::<T>(migration.as_ref()).is_none(),
+					Error::<T>::DependencyNotCompleted
+				);
+			} else {
+				ensure!(
+					!Self::has_completed_dependent(&name),
+					Error::<T>::DependentMigrationCompleted
+				);
+			}
+
+			let status = if completed {
+				MigrationStatus::Completed
+			} else {
+				MigrationStatus::NotStarted
+			};
+			<MigrationState<T>>::insert(&name, status);
+			Self::deposit_event(Event::MigrationStateForced(name, completed));
+
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn find_migration(name: &[u8]) -> Option<Box<dyn Migration>> {
+			T::MigrationsList::get()
+				.into_iter()
+				.find(|migration| migration.friendly_name().as_bytes() == name)
+		}
+
+		/// Whether any migration depending on the migration `name` has completed
+		fn has_completed_dependent(name: &[u8]) -> bool {
+			T::MigrationsList::get().iter().any(|migration| {
+				migration
+					.dependencies()
+					.iter()
+					.any(|dependency| dependency.as_bytes() == name)
+					&& <MigrationState<T>>::get(migration.friendly_name().as_bytes())
+						== MigrationStatus::Completed
+			})
+		}
+
+		/// The weight migrations may use in a single block
+		pub fn migrations_budget() -> Weight {
+			T::MigrationsBudget::get() * T::BlockWeights::get().max_block
+		}
+
+		/// The name and status of every migration in the MigrationsList, in order
+		pub fn migrations_status() -> Vec<(Vec<u8>, MigrationStatus)> {
+			T::MigrationsList::get()
+				.iter()
+				.map(|migration| {
+					let name = migration.friendly_name().as_bytes();
+					(name.to_vec(), <MigrationState<T>>::get(name))
+				})
+				.collect()
+		}
 	}
 
 	#[pallet::storage]
@@ -113,8 +463,20 @@ pub mod pallet {
 	#[pallet::storage]
 	#[pallet::getter(fn migration_state)]
 	/// MigrationState tracks the progress of a migration.
-	/// Maps name (Vec<u8>) -> whether or not migration has been completed (bool)
-	type MigrationState<T: Config> = StorageMap<_, Twox64Concat, Vec<u8>, bool, ValueQuery>;
+	/// Maps name (Vec<u8>) -> the migration's status, including its progress cursor while it is in
+	/// progress
+	type MigrationState<T: Config> =
+		StorageMap<_, Twox64Concat, Vec<u8>, MigrationStatus, ValueQuery>;
+
+	#[pallet::storage]
+	/// The weight used by on_runtime_upgrade, which on_initialize of the same block deducts from
+	/// the migrations budget
+	type RuntimeUpgradeWeight<T: Config> = StorageValue<_, Weight, OptionQuery>;
+
+	#[pallet::storage]
+	/// Whether this pallet put the chain in maintenance mode and must take it out once all
+	/// migrations have completed
+	type ResumeNormalOperationWhenUpgraded<T: Config> = StorageValue<_, bool, ValueQuery>;
 
 	#[pallet::genesis_config]
 	#[derive(Default)]
@@ -126,61 +488,114 @@ pub mod pallet {
 	impl<T: Config> GenesisBuild<T> for GenesisConfig {
 		fn build(&self) {
 			for migration_name in &self.completed_migrations {
-				<MigrationState<T>>::insert(migration_name, true);
+				<MigrationState<T>>::insert(migration_name, MigrationStatus::Completed);
 			}
+			// Nothing to migrate at genesis
+			<FullyUpgraded<T>>::put(true);
 		}
 	}
 
+	/// The first dependency of `migration` that has not completed, if any
+	fn pending_dependency<'a, T: Config>(migration: &'a dyn Migration) -> Option<&'a str> {
+		migration.dependencies().into_iter().find(|dependency| {
+			<MigrationState<T>>::get(dependency.as_bytes()) != MigrationStatus::Completed
+		})
+	}
+
 	fn perform_runtime_upgrades<T: Config>(available_weight: Weight) -> Weight {
 		let mut weight: Weight = 0u64.into();
+		let mut stepped_any = false;
 
 		for migration in &T::MigrationsList::get() {
 			let migration_name = migration.friendly_name();
 			let migration_name_as_bytes = migration_name.as_bytes();
 			log::trace!("evaluating migration {}", migration_name);
 
-			let migration_done = <MigrationState<T>>::get(migration_name_as_bytes);
-
-			if !migration_done {
-				<Pallet<T>>::deposit_event(Event::MigrationStarted(migration_name_as_bytes.into()));
-
-				// when we go overweight, leave a warning... there's nothing we can really do about
-				// this scenario other than hope that the block is actually accepted.
-				let available_for_step = if available_weight > weight {
-					available_weight - weight
-				} else {
-					log::error!(
-						"previous migration went overweight;
-						ignoring and providing migration {} 0 weight.",
-						migration_name,
-					);
-
-					0u64.into()
-				};
+			let status = <MigrationState<T>>::get(migration_name_as_bytes);
+			if status == MigrationStatus::Completed {
+				continue;
+			}
 
-				log::trace!(
-					"performing migration {}, available weight: {}",
+			// Once the budget is spent, the remaining migrations wait for the next block rather
+			// than being stepped with no weight at all. The first migration stepped is always
+			// given the whole budget, so that migrations make progress in every block.
+			if stepped_any && weight >= available_weight {
+				log::warn!(
+					"migration budget exhausted, deferring migration {} to the next block",
+					migration_name
+				);
+				return weight;
+			}
+			// The MigrationsList order normally guarantees this, unless a dependency is missing
+			// from the list or listed after this migration. The migrations wait rather than run
+			// this one too early.
+			weight += T::DbWeight::get().reads(migration.dependencies().len() as Weight);
+			if let Some(dependency) = pending_dependency::<T>(migration.as_ref()) {
+				log::error!(
+					"migration {} depends on {}, which has not completed; deferring migrations",
+					migration_name,
+					dependency
+				);
+				return weight;
+			}
+			stepped_any = true;
+
+			let cursor = match status {
+				MigrationStatus::InProgress(cursor) => Some(cursor),
+				_ => {
+					<Pallet<T>>::deposit_event(Event::MigrationStarted(
+						migration_name_as_bytes.into(),
+					));
+					None
+				}
+			};
+
+			let available_for_step = available_weight.saturating_sub(weight);
+
+			log::trace!(
+				"performing migration {}, available weight: {}",
+				migration_name,
+				available_for_step
+			);
+
+			let step = migration.migrate(available_for_step, cursor);
+			let consumed_weight = step.weight;
+			weight += consumed_weight;
+			log::info!(
+				"migration {} consumed {} out of {} available weight",
+				migration_name,
+				consumed_weight,
+				available_for_step
+			);
+			if weight > available_weight {
+				log::error!(
+					"Migration {} consumed more weight than it was given! ({} > {})",
 					migration_name,
+					consumed_weight,
 					available_for_step
 				);
+			}
 
-				let consumed_weight = migration.migrate(available_for_step);
-				<Pallet<T>>::deposit_event(Event::MigrationCompleted(
+			if !step.completed {
+				// Later migrations may rely on this one, so they all wait until it is done
+				<MigrationState<T>>::insert(
+					migration_name_as_bytes,
+					MigrationStatus::InProgress(step.cursor),
+				);
+				<Pallet<T>>::deposit_event(Event::MigrationProgressed(
 					migration_name_as_bytes.into(),
 					consumed_weight,
 				));
-				<MigrationState<T>>::insert(migration_name_as_bytes, true);
+				weight += T::DbWeight::get().writes(1);
 
-				weight += consumed_weight;
-				if weight > available_weight {
-					log::error!(
-						"Migration {} consumed more weight than it was given! ({} > {})",
-						migration_name,
-						consumed_weight,
-						available_for_step
-					);
-				}
+				return weight;
 			}
+
+			<Pallet<T>>::deposit_event(Event::MigrationCompleted(
+				migration_name_as_bytes.into(),
+				consumed_weight,
+			));
+			<MigrationState<T>>::insert(migration_name_as_bytes, MigrationStatus::Completed);
 		}
 
 		<FullyUpgraded<T>>::put(true);
@@ -189,4 +604,29 @@ pub mod pallet {
 
 		weight
 	}
+
+	/// Keep the chain in maintenance mode until the migrations complete. Nothing is done if the
+	/// chain already is in maintenance mode, or if this pallet put it there and governance has
+	/// since resumed normal operation.
+	fn enter_maintenance_mode_until_upgraded<T: Config>() -> Weight {
+		if <ResumeNormalOperationWhenUpgraded<T>>::get()
+			|| T::MaintenanceMode::is_in_maintenance_mode()
+		{
+			return T::DbWeight::get().reads(2);
+		}
+
+		T::MaintenanceMode::enter_maintenance_mode();
+		<ResumeNormalOperationWhenUpgraded<T>>::put(true);
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+
+	/// Take the chain out of maintenance mode if the migrations put it there
+	fn resume_normal_operation_if_upgraded<T: Config>() -> Weight {
+		if <ResumeNormalOperationWhenUpgraded<T>>::take() {
+			T::MaintenanceMode::resume_normal_operation();
+			T::DbWeight::get().reads_writes(1, 2)
+		} else {
+			T::DbWeight::get().reads(1)
+		}
+	}
 }
@@ -24,18 +24,24 @@ use frame_support::{
 	traits::GenesisBuild,
 	weights::{constants::RocksDbWeight, Weight},
 };
+use frame_system::EnsureRoot;
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Block as TestBlock, Header, TestXt},
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
 	Perbill,
 };
+use std::cell::RefCell;
 
 pub type AccountId = u64;
 pub type BlockNumber = u64;
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>;
+// Extrinsics the executive can apply, so that blocks can be initialized as on a real chain
+type UncheckedExtrinsic = TestXt<Call, frame_system::CheckWeight<Test>>;
+type Block = TestBlock<UncheckedExtrinsic>;
+
+pub type Executive =
+	frame_executive::Executive<Test, Block, frame_system::ChainContext<Test>, Test, AllPallets>;
 
 // Configure a mock runtime to test the pallet.
 construct_runtime!(
@@ -45,7 +51,7 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Migrations: pallet_migrations::{Pallet, Storage, Config, Event<T>},
+		Migrations: pallet_migrations::{Pallet, Call, Storage, Config, Event<T>},
 	}
 );
 
@@ -55,6 +61,7 @@ parameter_types! {
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
 	pub const SS58Prefix: u8 = 42;
+	pub const MigrationsBudget: Perbill = Perbill::from_percent(50);
 }
 impl frame_system::Config for Test {
 	type BaseCallFilter = ();
@@ -92,15 +99,21 @@ impl frame_system::Config for Test {
 /// A pair of callbacks provided to register_callback() will map directly to a single instance of
 /// Migration (done by the MockMigration glue below). Treat each pair of callbacks as though it were
 /// a custom implementation of the Migration trait just as a normal Pallet would.
+///
+/// Migrations spanning multiple blocks should be registered with register_multi_block_callback(),
+/// whose migrate callback receives the cursor and reports its own progress.
 pub struct MockMigrationManager<'test> {
 	name_fn_callbacks: Vec<Box<dyn 'test + FnMut() -> &'static str>>,
-	migrate_fn_callbacks: Vec<Box<dyn 'test + FnMut(Weight) -> Weight>>,
+	dependencies: Vec<Vec<&'static str>>,
+	migrate_fn_callbacks:
+		Vec<Box<dyn 'test + FnMut(Weight, Option<Vec<u8>>) -> MigrationStepResult>>,
 }
 
 impl Default for MockMigrationManager<'_> {
 	fn default() -> Self {
 		Self {
 			name_fn_callbacks: Default::default(),
+			dependencies: Default::default(),
 			migrate_fn_callbacks: Default::default(),
 		}
 	}
@@ -111,17 +124,46 @@ impl<'test> MockMigrationManager<'test> {
 	where
 		FN: 'test + FnMut() -> &'static str,
 		FM: 'test + FnMut(Weight) -> Weight,
+	{
+		let mut migrate_fn = migrate_fn;
+		self.register_multi_block_callback(name_fn, move |available_weight, _| {
+			MigrationStepResult::completed(migrate_fn(available_weight))
+		});
+	}
+
+	pub fn register_multi_block_callback<FN, FM>(&mut self, name_fn: FN, migrate_fn: FM)
+	where
+		FN: 'test + FnMut() -> &'static str,
+		FM: 'test + FnMut(Weight, Option<Vec<u8>>) -> MigrationStepResult,
 	{
 		self.name_fn_callbacks.push(Box::new(name_fn));
+		self.dependencies.push(Vec::new());
 		self.migrate_fn_callbacks.push(Box::new(migrate_fn));
 	}
 
+	/// Declare the dependencies of the migration registered last
+	pub fn set_dependencies(&mut self, dependencies: Vec<&'static str>) {
+		*self
+			.dependencies
+			.last_mut()
+			.expect("a migration must be registered first") = dependencies;
+	}
+
 	pub(crate) fn invoke_name_fn(&mut self, index: usize) -> &'static str {
 		self.name_fn_callbacks[index]()
 	}
 
-	pub(crate) fn invoke_migrate_fn(&mut self, index: usize, available_weight: Weight) -> Weight {
-		self.migrate_fn_callbacks[index](available_weight)
+	pub(crate) fn dependencies(&self, index: usize) -> Vec<&'static str> {
+		self.dependencies[index].clone()
+	}
+
+	pub(crate) fn invoke_migrate_fn(
+		&mut self,
+		index: usize,
+		available_weight: Weight,
+		cursor: Option<Vec<u8>>,
+	) -> MigrationStepResult {
+		self.migrate_fn_callbacks[index](available_weight, cursor)
 	}
 
 	fn generate_migrations_list(&self) -> Vec<Box<dyn Migration>> {
@@ -170,10 +212,17 @@ impl Migration for MockMigration {
 		});
 		result
 	}
-	fn migrate(&self, available_weight: Weight) -> Weight {
-		let mut result: Weight = 0u64.into();
+	fn dependencies(&self) -> Vec<&str> {
+		let mut result = Vec::new();
 		MOCK_MIGRATIONS_LIST::with(|mgr: &mut MockMigrationManager| {
-			result = mgr.invoke_migrate_fn(self.index, available_weight);
+			result = mgr.dependencies(self.index);
+		});
+		result
+	}
+	fn migrate(&self, available_weight: Weight, cursor: Option<Vec<u8>>) -> MigrationStepResult {
+		let mut result = MigrationStepResult::completed(0u64.into());
+		MOCK_MIGRATIONS_LIST::with(|mgr: &mut MockMigrationManager| {
+			result = mgr.invoke_migrate_fn(self.index, available_weight, cursor);
 		});
 		result
 	}
@@ -192,9 +241,35 @@ impl Get<Vec<Box<dyn Migration>>> for MockMigrations {
 	}
 }
 
+thread_local! {
+	static MAINTENANCE_MODE: RefCell<bool> = RefCell::new(false);
+}
+
+/// Stands in for the maintenance-mode pallet by tracking the mode in a thread local
+pub struct MockMaintenanceMode;
+impl MockMaintenanceMode {
+	pub fn set(in_maintenance_mode: bool) {
+		MAINTENANCE_MODE.with(|m| *m.borrow_mut() = in_maintenance_mode);
+	}
+}
+impl MaintenanceModeControl for MockMaintenanceMode {
+	fn is_in_maintenance_mode() -> bool {
+		MAINTENANCE_MODE.with(|m| *m.borrow())
+	}
+	fn enter_maintenance_mode() {
+		Self::set(true);
+	}
+	fn resume_normal_operation() {
+		Self::set(false);
+	}
+}
+
 impl Config for Test {
 	type Event = Event;
 	type MigrationsList = MockMigrations;
+	type MaintenanceMode = MockMaintenanceMode;
+	type MigrationsOrigin = EnsureRoot<AccountId>;
+	type MigrationsBudget = MigrationsBudget;
 }
 
 /// Externality builder for pallet migration's mock runtime
@@ -244,6 +319,18 @@ pub(crate) fn events() -> Vec<pallet_migrations::Event<Test>> {
 		.collect::<Vec<_>>()
 }
 
+/// Initialize a block the way the runtime's executive does: if the runtime was upgraded, the
+/// on_runtime_upgrade hooks run first and on_initialize follows in the same block.
+pub(crate) fn initialize_block(block_number: u64) {
+	Executive::initialize_block(&Header::new(
+		block_number,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Default::default(),
+	));
+}
+
 pub(crate) fn roll_to(block_number: u64, invoke_on_runtime_upgrade_first: bool) {
 	if invoke_on_runtime_upgrade_first {
 		Migrations::on_runtime_upgrade();
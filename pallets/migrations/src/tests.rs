@@ -15,12 +15,20 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Unit testing
-use crate::mock::{events, ExtBuilder, Migrations, MockMigrationManager, System};
-use crate::Event;
+use crate::mock::{
+	events, ExtBuilder, Migrations, MigrationsBudget, MockMaintenanceMode, MockMigrationManager,
+	Origin, System, Test,
+};
+use crate::{Error, Event, MaintenanceModeControl, MigrationStatus, MigrationStepResult};
 use frame_support::{
-	traits::OnRuntimeUpgrade,
+	assert_noop, assert_ok,
+	storage::migration::put_storage_value,
+	traits::{Get, IntegrityTest, OnRuntimeUpgrade},
 	weights::{constants::RocksDbWeight, Weight},
+	StorageHasher, Twox64Concat,
 };
+use parity_scale_codec::Encode;
+use sp_runtime::DispatchError;
 use std::sync::{Arc, Mutex};
 
 #[test]
@@ -199,23 +207,20 @@ fn migration_should_only_be_invoked_once() {
 }
 
 #[test]
-fn on_runtime_upgrade_charges_minimum_two_db_writes() {
+fn on_runtime_upgrade_charges_minimum_three_db_writes() {
 	ExtBuilder::default().build().execute_with(|| {
 		let weight = Migrations::on_runtime_upgrade();
-		assert_eq!(weight, RocksDbWeight::get().writes(2));
+		// the extra read checks whether maintenance mode has to be left
+		assert_eq!(weight, RocksDbWeight::get().reads_writes(1, 3));
 	})
 }
 
 #[test]
-fn overweight_migrations_tolerated() {
-	// pallet-migrations currently tolerates a migration going over-weight. not only does it
-	// tolerate it, but it continues on to the next migration even if it's already overweight.
-	//
-	// The logic behind this is that we would rather go over-weight and risk a block taking too long
-	// (which *might* be "catastrophic") than outright prevent migrations from proceeding (which is
-	// certainly "catastrophic").
-	//
-	// maybe_catastrophic > certainly_catastrophic
+fn overweight_migrations_defer_remaining_migrations() {
+	// pallet-migrations tolerates a migration going over-weight, since we would rather go
+	// over-weight and risk a block taking too long than outright prevent migrations from
+	// proceeding. The migrations that no longer fit in the budget are deferred to the following
+	// blocks rather than being run with no weight at all.
 
 	let num_migration1_calls = Arc::new(Mutex::new(0u32));
 	let num_migration2_calls = Arc::new(Mutex::new(0u32));
@@ -227,12 +232,11 @@ fn overweight_migrations_tolerated() {
 			let num_migration2_calls = Arc::clone(&num_migration2_calls);
 			let num_migration3_calls = Arc::clone(&num_migration3_calls);
 
+			// each of these is larger than the whole migrations budget
 			mgr.register_callback(
 				move || "migration1",
 				move |_| -> Weight {
 					*num_migration1_calls.lock().unwrap() += 1;
-					// TODO: this is brittle because it assumes it is larger than the value used at
-					// the top of process_runtime_upgrades()
 					1_000_000_000_000u64.into()
 				},
 			);
@@ -257,6 +261,24 @@ fn overweight_migrations_tolerated() {
 			ExtBuilder::default().build().execute_with(|| {
 				Migrations::on_runtime_upgrade();
 
+				assert_eq!(*num_migration1_calls.lock().unwrap(), 1);
+				assert_eq!(*num_migration2_calls.lock().unwrap(), 0);
+				assert_eq!(*num_migration3_calls.lock().unwrap(), 0);
+				assert_eq!(Migrations::is_fully_upgraded(), false);
+				assert_eq!(
+					Migrations::migration_state(b"migration2".to_vec()),
+					MigrationStatus::NotStarted
+				);
+
+				// on_initialize of the upgrade block finds the budget spent
+				crate::mock::roll_to(2, false);
+				assert_eq!(*num_migration2_calls.lock().unwrap(), 0);
+
+				crate::mock::roll_to(3, false);
+				assert_eq!(*num_migration2_calls.lock().unwrap(), 1);
+				assert_eq!(*num_migration3_calls.lock().unwrap(), 0);
+
+				crate::mock::roll_to(4, false);
 				assert_eq!(*num_migration1_calls.lock().unwrap(), 1);
 				assert_eq!(*num_migration2_calls.lock().unwrap(), 1);
 				assert_eq!(*num_migration3_calls.lock().unwrap(), 1);
@@ -265,3 +287,560 @@ fn overweight_migrations_tolerated() {
 		},
 	);
 }
+
+#[test]
+fn migrations_share_the_budget() {
+	let migration_calls = Arc::new(Mutex::new(Vec::new()));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			for name in ["migration1", "migration2", "migration3"].iter().copied() {
+				let migration_calls = Arc::clone(&migration_calls);
+				mgr.register_callback(
+					move || name,
+					move |available_weight| -> Weight {
+						migration_calls
+							.lock()
+							.unwrap()
+							.push((name, available_weight));
+						// together the first two exhaust the budget
+						Migrations::migrations_budget() / 2
+					},
+				);
+			}
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				let budget = Migrations::migrations_budget();
+				assert_eq!(
+					budget,
+					MigrationsBudget::get()
+						* <Test as frame_system::Config>::BlockWeights::get().max_block
+				);
+
+				// the first migration is given all that's left after flagging the upgrade
+				let upgrade_weight = RocksDbWeight::get().writes(1);
+				Migrations::on_runtime_upgrade();
+				assert_eq!(
+					*migration_calls.lock().unwrap(),
+					vec![
+						("migration1", budget - upgrade_weight),
+						("migration2", budget / 2 - upgrade_weight),
+					]
+				);
+
+				// nor is anything left for on_initialize of the upgrade block
+				crate::mock::roll_to(2, false);
+				assert_eq!(migration_calls.lock().unwrap().len(), 2);
+
+				crate::mock::roll_to(3, false);
+				assert_eq!(migration_calls.lock().unwrap().len(), 3);
+				assert_eq!(Migrations::is_fully_upgraded(), true);
+			});
+		},
+	);
+}
+
+#[test]
+fn integrity_test_accepts_dependencies_listed_before() {
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			mgr.register_callback(move || "migration1", move |_| -> Weight { 0u64.into() });
+			mgr.register_callback(move || "migration2", move |_| -> Weight { 0u64.into() });
+			mgr.set_dependencies(vec!["migration1"]);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::integrity_test();
+			});
+		},
+	);
+}
+
+#[test]
+#[should_panic(expected = "migration migration1 depends on migration2")]
+fn integrity_test_rejects_dependencies_listed_after() {
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			mgr.register_callback(move || "migration1", move |_| -> Weight { 0u64.into() });
+			mgr.set_dependencies(vec!["migration2"]);
+			mgr.register_callback(move || "migration2", move |_| -> Weight { 0u64.into() });
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::integrity_test();
+			});
+		},
+	);
+}
+
+#[test]
+#[should_panic(expected = "migration migration1 depends on unknown")]
+fn integrity_test_rejects_unknown_dependencies() {
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			mgr.register_callback(move || "migration1", move |_| -> Weight { 0u64.into() });
+			mgr.set_dependencies(vec!["unknown"]);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::integrity_test();
+			});
+		},
+	);
+}
+
+#[test]
+fn migration_does_not_run_before_its_dependencies() {
+	let num_migrate_calls = Arc::new(Mutex::new(0u32));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			// listed in the wrong order, which the integrity test would have caught
+			for name in ["migration2", "migration1"].iter().copied() {
+				let num_migrate_calls = Arc::clone(&num_migrate_calls);
+				mgr.register_callback(
+					move || name,
+					move |_| -> Weight {
+						*num_migrate_calls.lock().unwrap() += 1;
+						1u32.into()
+					},
+				);
+				if name == "migration2" {
+					mgr.set_dependencies(vec!["migration1"]);
+				}
+			}
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				crate::mock::roll_to(3, true);
+
+				// the migrations wait rather than run migration2 first
+				assert_eq!(*num_migrate_calls.lock().unwrap(), 0);
+				assert_eq!(Migrations::is_fully_upgraded(), false);
+				assert_eq!(
+					Migrations::migration_state(b"migration2".to_vec()),
+					MigrationStatus::NotStarted
+				);
+				assert!(MockMaintenanceMode::is_in_maintenance_mode());
+			});
+		},
+	);
+}
+
+#[test]
+fn governance_cannot_break_dependencies() {
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			mgr.register_callback(move || "migration1", move |_| -> Weight { 1u32.into() });
+			mgr.register_callback(move || "migration2", move |_| -> Weight { 1u32.into() });
+			mgr.set_dependencies(vec!["migration1"]);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				// migration2 cannot be completed before migration1
+				assert_noop!(
+					Migrations::force_migration_state(Origin::root(), b"migration2".to_vec(), true),
+					Error::<Test>::DependencyNotCompleted
+				);
+				assert_noop!(
+					Migrations::rerun_migration(Origin::root(), b"migration2".to_vec()),
+					Error::<Test>::DependencyNotCompleted
+				);
+
+				crate::mock::roll_until_upgraded(true);
+
+				// nor can migration1 be reset while migration2 has completed
+				assert_noop!(
+					Migrations::force_migration_state(
+						Origin::root(),
+						b"migration1".to_vec(),
+						false
+					),
+					Error::<Test>::DependentMigrationCompleted
+				);
+				assert_noop!(
+					Migrations::rerun_migration(Origin::root(), b"migration1".to_vec()),
+					Error::<Test>::DependentMigrationCompleted
+				);
+
+				// unless migration2 is reset first
+				assert_ok!(Migrations::force_migration_state(
+					Origin::root(),
+					b"migration2".to_vec(),
+					false
+				));
+				assert_ok!(Migrations::rerun_migration(
+					Origin::root(),
+					b"migration1".to_vec()
+				));
+			});
+		},
+	);
+}
+
+/// Registers a migration that needs `num_steps` steps, using the step count as its cursor
+fn register_multi_block_migration<'test>(
+	mgr: &mut MockMigrationManager<'test>,
+	name: &'static str,
+	num_steps: u8,
+	cursors_seen: Arc<Mutex<Vec<Option<Vec<u8>>>>>,
+) {
+	mgr.register_multi_block_callback(
+		move || name,
+		move |_, cursor| -> MigrationStepResult {
+			cursors_seen.lock().unwrap().push(cursor.clone());
+			let step = cursor.map(|c| c[0]).unwrap_or(0) + 1;
+			if step == num_steps {
+				MigrationStepResult::completed(1u32.into())
+			} else {
+				MigrationStepResult::in_progress(1u32.into(), vec![step])
+			}
+		},
+	);
+}
+
+#[test]
+fn multi_block_migration_continues_in_on_initialize() {
+	let cursors_seen = Arc::new(Mutex::new(Vec::new()));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			register_multi_block_migration(mgr, "migration1", 3, Arc::clone(&cursors_seen));
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::on_runtime_upgrade();
+
+				assert_eq!(Migrations::is_fully_upgraded(), false);
+				assert_eq!(
+					Migrations::migration_state(b"migration1".to_vec()),
+					MigrationStatus::InProgress(vec![1])
+				);
+				assert!(MockMaintenanceMode::is_in_maintenance_mode());
+
+				crate::mock::roll_until_upgraded(false);
+
+				// two more steps were needed, one per block
+				assert_eq!(System::block_number(), 3);
+				assert_eq!(
+					*cursors_seen.lock().unwrap(),
+					vec![None, Some(vec![1]), Some(vec![2])]
+				);
+				assert_eq!(
+					Migrations::migration_state(b"migration1".to_vec()),
+					MigrationStatus::Completed
+				);
+				assert!(!MockMaintenanceMode::is_in_maintenance_mode());
+
+				let expected = vec![
+					Event::RuntimeUpgradeStarted(),
+					Event::MigrationStarted("migration1".into()),
+					Event::MigrationProgressed("migration1".into(), 1u32.into()),
+					Event::MigrationProgressed("migration1".into(), 1u32.into()),
+					Event::MigrationCompleted("migration1".into(), 1u32.into()),
+					Event::RuntimeUpgradeCompleted(100000001u32.into()),
+				];
+				assert_eq!(events(), expected);
+
+				// nothing left to do in later blocks
+				crate::mock::roll_to(5, false);
+				assert_eq!(cursors_seen.lock().unwrap().len(), 3);
+				assert_eq!(events(), expected);
+			});
+		},
+	);
+}
+
+#[test]
+fn upgrade_block_budget_is_shared_with_on_initialize() {
+	let available_weights = Arc::new(Mutex::new(Vec::new()));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			let available_weights = Arc::clone(&available_weights);
+			mgr.register_multi_block_callback(
+				move || "migration1",
+				move |available_weight, cursor| -> MigrationStepResult {
+					available_weights.lock().unwrap().push(available_weight);
+					// uses half of what it is given, over three steps
+					let step = cursor.map(|c| c[0]).unwrap_or(0) + 1;
+					if step == 3 {
+						MigrationStepResult::completed(available_weight / 2)
+					} else {
+						MigrationStepResult::in_progress(available_weight / 2, vec![step])
+					}
+				},
+			);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				let budget = Migrations::migrations_budget();
+
+				// the executive runs on_runtime_upgrade then on_initialize in the upgrade block
+				crate::mock::initialize_block(2);
+				let steps = available_weights.lock().unwrap().clone();
+				assert_eq!(steps.len(), 2);
+				assert_eq!(steps[0], budget - RocksDbWeight::get().writes(1));
+				// on_initialize only gets what on_runtime_upgrade left
+				assert!(steps[0] / 2 + steps[1] <= budget);
+
+				// the following block starts with a full budget again
+				crate::mock::initialize_block(3);
+				let steps = available_weights.lock().unwrap().clone();
+				assert_eq!(steps.len(), 3);
+				assert_eq!(steps[2], budget - RocksDbWeight::get().reads(2));
+				assert_eq!(Migrations::is_fully_upgraded(), true);
+			});
+		},
+	);
+}
+
+#[test]
+fn later_migrations_wait_for_unfinished_migration() {
+	let cursors_seen = Arc::new(Mutex::new(Vec::new()));
+	let migration2_block = Arc::new(Mutex::new(None));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			register_multi_block_migration(mgr, "migration1", 2, Arc::clone(&cursors_seen));
+
+			let migration2_block = Arc::clone(&migration2_block);
+			mgr.register_callback(
+				move || "migration2",
+				move |_| -> Weight {
+					*migration2_block.lock().unwrap() = Some(System::block_number());
+					1u32.into()
+				},
+			);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::on_runtime_upgrade();
+				assert_eq!(*migration2_block.lock().unwrap(), None);
+
+				crate::mock::roll_until_upgraded(false);
+				assert_eq!(*migration2_block.lock().unwrap(), Some(2));
+				assert_eq!(System::block_number(), 2);
+			});
+		},
+	);
+}
+
+#[test]
+fn maintenance_mode_entered_by_governance_is_left_alone() {
+	let cursors_seen = Arc::new(Mutex::new(Vec::new()));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			register_multi_block_migration(mgr, "migration1", 2, Arc::clone(&cursors_seen));
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				MockMaintenanceMode::set(true);
+
+				crate::mock::roll_until_upgraded(true);
+
+				assert!(MockMaintenanceMode::is_in_maintenance_mode());
+			});
+		},
+	);
+}
+
+#[test]
+fn legacy_migration_state_is_decoded() {
+	ExtBuilder::default().build().execute_with(|| {
+		let put_legacy_state = |name: &[u8], done: bool| {
+			put_storage_value(
+				b"Migrations",
+				b"MigrationState",
+				&Twox64Concat::hash(&name.to_vec().encode()),
+				done,
+			);
+		};
+		put_legacy_state(b"done", true);
+		put_legacy_state(b"not done", false);
+
+		assert_eq!(
+			Migrations::migration_state(b"done".to_vec()),
+			MigrationStatus::Completed
+		);
+		assert_eq!(
+			Migrations::migration_state(b"not done".to_vec()),
+			MigrationStatus::NotStarted
+		);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_runtime_completes_multi_block_migrations() {
+	let cursors_seen = Arc::new(Mutex::new(Vec::new()));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			register_multi_block_migration(mgr, "migration1", 3, Arc::clone(&cursors_seen));
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::pre_upgrade().expect("pre_upgrade checks pass");
+				Migrations::on_runtime_upgrade();
+				Migrations::post_upgrade().expect("post_upgrade checks pass");
+
+				// every step ran without any block being produced
+				assert_eq!(System::block_number(), 1);
+				assert_eq!(cursors_seen.lock().unwrap().len(), 3);
+				assert_eq!(Migrations::is_fully_upgraded(), true);
+				assert!(!MockMaintenanceMode::is_in_maintenance_mode());
+			});
+		},
+	);
+}
+
+#[test]
+fn rerun_migration_runs_it_again_in_next_block() {
+	let num_migrate_calls = Arc::new(Mutex::new(0u32));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			let num_migrate_calls = Arc::clone(&num_migrate_calls);
+			mgr.register_callback(
+				move || "migration1",
+				move |_| -> Weight {
+					*num_migrate_calls.lock().unwrap() += 1;
+					1u32.into()
+				},
+			);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				crate::mock::roll_until_upgraded(true);
+				assert_eq!(*num_migrate_calls.lock().unwrap(), 1);
+
+				assert_ok!(Migrations::rerun_migration(
+					Origin::root(),
+					b"migration1".to_vec()
+				));
+				assert_eq!(Migrations::is_fully_upgraded(), false);
+				assert_eq!(
+					Migrations::migration_state(b"migration1".to_vec()),
+					MigrationStatus::NotStarted
+				);
+				assert_eq!(
+					events().last(),
+					Some(&Event::MigrationRescheduled("migration1".into()))
+				);
+
+				crate::mock::roll_to(2, false);
+				assert_eq!(*num_migrate_calls.lock().unwrap(), 2);
+				assert_eq!(Migrations::is_fully_upgraded(), true);
+				assert_eq!(
+					Migrations::migration_state(b"migration1".to_vec()),
+					MigrationStatus::Completed
+				);
+			});
+		},
+	);
+}
+
+#[test]
+fn rerun_migration_requires_migrations_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Migrations::rerun_migration(Origin::signed(1), b"migration1".to_vec()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Migrations::force_migration_state(Origin::signed(1), b"migration1".to_vec(), true),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn unknown_migration_is_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Migrations::rerun_migration(Origin::root(), b"unknown".to_vec()),
+			Error::<Test>::UnknownMigration
+		);
+		assert_noop!(
+			Migrations::force_migration_state(Origin::root(), b"unknown".to_vec(), true),
+			Error::<Test>::UnknownMigration
+		);
+	});
+}
+
+#[test]
+fn force_migration_state_works() {
+	let num_migrate_calls = Arc::new(Mutex::new(0u32));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			let num_migrate_calls = Arc::clone(&num_migrate_calls);
+			mgr.register_callback(
+				move || "migration1",
+				move |_| -> Weight {
+					*num_migrate_calls.lock().unwrap() += 1;
+					1u32.into()
+				},
+			);
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				// a migration forced as completed doesn't run on upgrade
+				assert_ok!(Migrations::force_migration_state(
+					Origin::root(),
+					b"migration1".to_vec(),
+					true
+				));
+				assert_eq!(
+					events(),
+					vec![Event::MigrationStateForced("migration1".into(), true)]
+				);
+				crate::mock::roll_until_upgraded(true);
+				assert_eq!(*num_migrate_calls.lock().unwrap(), 0);
+
+				// while one forced as not started runs on the next upgrade
+				assert_ok!(Migrations::force_migration_state(
+					Origin::root(),
+					b"migration1".to_vec(),
+					false
+				));
+				assert_eq!(
+					Migrations::migration_state(b"migration1".to_vec()),
+					MigrationStatus::NotStarted
+				);
+				crate::mock::roll_until_upgraded(true);
+				assert_eq!(*num_migrate_calls.lock().unwrap(), 1);
+			});
+		},
+	);
+}
+
+#[test]
+fn migrations_status_lists_every_migration() {
+	let cursors_seen = Arc::new(Mutex::new(Vec::new()));
+
+	crate::mock::execute_with_mock_migrations(
+		&mut |mgr: &mut MockMigrationManager| {
+			mgr.register_callback(move || "migration1", move |_| -> Weight { 1u32.into() });
+			register_multi_block_migration(mgr, "migration2", 2, Arc::clone(&cursors_seen));
+			mgr.register_callback(move || "migration3", move |_| -> Weight { 1u32.into() });
+		},
+		&mut || {
+			ExtBuilder::default().build().execute_with(|| {
+				Migrations::on_runtime_upgrade();
+
+				assert_eq!(
+					Migrations::migrations_status(),
+					vec![
+						(b"migration1".to_vec(), MigrationStatus::Completed),
+						(b"migration2".to_vec(), MigrationStatus::InProgress(vec![1])),
+						(b"migration3".to_vec(), MigrationStatus::NotStarted),
+					]
+				);
+			});
+		},
+	);
+}
@@ -0,0 +1,381 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet providing randomness from the relay chain BABE VRF output.
+//!
+//! The author of each relay chain block produces a VRF output, which BABE stores in its
+//! `AuthorVrfRandomness` storage item. This pallet reads it from the relay state proof that comes
+//! with the parachain inherent data. An optional inherent carries the proof, which is checked
+//! against the storage root of the relay parent, so the collators can't bias the randomness.
+//! They can at most withhold it. The inherent is only created in the blocks targeted by requests,
+//! and when the collator included `AuthorVrfRandomness` in the relay state proof.
+//!
+//! Randomness is requested with a deposit and a delay in blocks. Once the target block
+//! (the request block plus the delay) is reached, the requester fulfils the request with the
+//! relay randomness recorded in the target block, which returns the deposit. Each request gets a
+//! different random word, derived from that randomness, the request id and a salt chosen by the
+//! requester. If no relay randomness was recorded in the target block, the request can never be
+//! fulfilled and anyone can expire it after that block, which returns the deposit.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::pallet;
+
+pub use pallet::*;
+
+#[pallet]
+pub mod pallet {
+	use cumulus_primitives_parachain_inherent::{
+		ParachainInherentData, INHERENT_IDENTIFIER as PARACHAIN_INHERENT_IDENTIFIER,
+	};
+	use frame_support::inherent::{
+		InherentData, InherentIdentifier, MakeFatalError, ProvideInherent,
+	};
+	use frame_support::pallet_prelude::*;
+	use frame_support::traits::{Currency, ReservableCurrency};
+	use frame_system::pallet_prelude::*;
+	use parity_scale_codec::Encode;
+	use sp_core::H256;
+	use sp_runtime::traits::{BlakeTwo256, Saturating};
+	use sp_std::vec::Vec;
+	use sp_trie::StorageProof;
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub type RequestId = u64;
+
+	/// A pending request for randomness.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Request<AccountId, Balance, BlockNumber> {
+		/// The account that made the request, which is the only one able to fulfil it
+		pub requester: AccountId,
+		/// The deposit reserved from the requester
+		pub deposit: Balance,
+		/// The salt mixed in the random word of the request
+		pub salt: H256,
+		/// The block whose relay randomness fulfils the request
+		pub fulfil_block: BlockNumber,
+	}
+
+	/// The relay randomness recorded in a block, kept while requests need it.
+	#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+	pub struct RandomnessResult {
+		/// The relay randomness, unset until the inherent of the block records it
+		pub randomness: Option<H256>,
+		/// The number of pending requests fulfilled with this randomness
+		pub request_count: u32,
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// Configuration trait of this pallet.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Overarching event type
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Currency in which the deposit of the requests is reserved
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The deposit reserved for each request until it is fulfilled
+		#[pallet::constant]
+		type Deposit: Get<BalanceOf<Self>>;
+		/// The minimum delay in blocks between a request and its fulfilment
+		#[pallet::constant]
+		type MinBlockDelay: Get<Self::BlockNumber>;
+		/// The maximum delay in blocks between a request and its fulfilment
+		#[pallet::constant]
+		type MaxBlockDelay: Get<Self::BlockNumber>;
+		/// The storage root of the relay parent, against which the relay state proof is checked.
+		/// It must be trusted, such as the one set by `cumulus_pallet_parachain_system`.
+		type RelayStorageRoot: Get<Option<H256>>;
+	}
+
+	/// An error that can occur while executing the randomness pallet's logic.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The requester cannot afford the deposit
+		CannotAffordDeposit,
+		/// The delay is not between the minimum and the maximum delay
+		InvalidBlockDelay,
+		/// There is no request with this id
+		RequestNotFound,
+		/// The request belongs to another account
+		NotYourRequest,
+		/// The target block of the request has not been reached yet
+		RequestNotReady,
+		/// No relay randomness was recorded in the target block of the request
+		RandomnessNotAvailable,
+		/// The relay randomness could not be read from the relay state proof
+		InvalidRelayStateProof,
+		/// The request can still be fulfilled
+		RequestNotExpired,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Randomness was requested, to be fulfilled from the given block.
+		/// [request_id, requester, fulfil_block]
+		RandomnessRequested(RequestId, T::AccountId, T::BlockNumber),
+		/// A request was fulfilled with the given random word. [request_id, random_word]
+		RequestFulfilled(RequestId, H256),
+		/// The relay randomness was recorded for the requests of this block. [randomness]
+		RelayRandomnessRecorded(H256),
+		/// A request expired without relay randomness, and its deposit was returned. [request_id]
+		RequestExpired(RequestId),
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn request_count)]
+	/// The number of requests ever made, which is also the id of the next request
+	pub type RequestCount<T: Config> = StorageValue<_, RequestId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn requests)]
+	/// The pending requests
+	pub type Requests<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		RequestId,
+		Request<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn randomness_results)]
+	/// The relay randomness of the blocks targeted by pending requests
+	pub type RandomnessResults<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, RandomnessResult, OptionQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Record the relay randomness for the requests targeting this block.
+		///
+		/// This inherent carries the relay state proof, which is checked against the storage root
+		/// of the relay parent.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Mandatory))]
+		pub fn set_relay_randomness(
+			origin: OriginFor<T>,
+			relay_state_proof: StorageProof,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let relay_storage_root =
+				T::RelayStorageRoot::get().ok_or(Error::<T>::InvalidRelayStateProof)?;
+			let randomness = Self::read_relay_randomness(relay_storage_root, relay_state_proof)
+				.ok_or(Error::<T>::InvalidRelayStateProof)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			RandomnessResults::<T>::mutate_exists(now, |result| {
+				if let Some(result) = result {
+					result.randomness = Some(randomness);
+				}
+			});
+
+			Self::deposit_event(Event::RelayRandomnessRecorded(randomness));
+
+			Ok(())
+		}
+
+		/// Request randomness, to be fulfilled with the relay randomness of the block `delay`
+		/// blocks from now. The deposit is reserved until the request is fulfilled.
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 4))]
+		pub fn request_randomness(
+			origin: OriginFor<T>,
+			salt: H256,
+			delay: T::BlockNumber,
+		) -> DispatchResult {
+			let requester = ensure_signed(origin)?;
+
+			ensure!(
+				delay >= T::MinBlockDelay::get() && delay <= T::MaxBlockDelay::get(),
+				Error::<T>::InvalidBlockDelay
+			);
+
+			let deposit = T::Deposit::get();
+			T::Currency::reserve(&requester, deposit)
+				.map_err(|_| Error::<T>::CannotAffordDeposit)?;
+
+			let fulfil_block = frame_system::Pallet::<T>::block_number().saturating_add(delay);
+			let request_id = RequestCount::<T>::get();
+
+			Requests::<T>::insert(
+				request_id,
+				Request {
+					requester: requester.clone(),
+					deposit,
+					salt,
+					fulfil_block,
+				},
+			);
+			RandomnessResults::<T>::mutate(fulfil_block, |result| {
+				result.get_or_insert_with(Default::default).request_count += 1;
+			});
+			RequestCount::<T>::put(request_id.saturating_add(1));
+
+			Self::deposit_event(Event::RandomnessRequested(
+				request_id,
+				requester,
+				fulfil_block,
+			));
+
+			Ok(())
+		}
+
+		/// Fulfil a request of the caller, which returns its deposit. The random word is given in
+		/// the `RequestFulfilled` event and by `random_word`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+		pub fn fulfil_request(origin: OriginFor<T>, request_id: RequestId) -> DispatchResult {
+			let requester = ensure_signed(origin)?;
+
+			let request = Requests::<T>::get(request_id).ok_or(Error::<T>::RequestNotFound)?;
+			ensure!(request.requester == requester, Error::<T>::NotYourRequest);
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= request.fulfil_block,
+				Error::<T>::RequestNotReady
+			);
+
+			let random_word =
+				Self::random_word(request_id).ok_or(Error::<T>::RandomnessNotAvailable)?;
+
+			Self::remove_request(request_id, request);
+
+			Self::deposit_event(Event::RequestFulfilled(request_id, random_word));
+
+			Ok(())
+		}
+
+		/// Expire a request whose target block has passed without relay randomness being recorded,
+		/// which returns its deposit to the requester. Any account can expire a request, as it can
+		/// never be fulfilled.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+		pub fn expire_request(origin: OriginFor<T>, request_id: RequestId) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let request = Requests::<T>::get(request_id).ok_or(Error::<T>::RequestNotFound)?;
+			// The inherent recording the relay randomness comes first in the target block, so it
+			// can't be recorded anymore once that block has passed.
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > request.fulfil_block
+					&& Self::random_word(request_id).is_none(),
+				Error::<T>::RequestNotExpired
+			);
+
+			Self::remove_request(request_id, request);
+
+			Self::deposit_event(Event::RequestExpired(request_id));
+
+			Ok(())
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = MakeFatalError<()>;
+		// The relay state proof is taken from the parachain inherent data.
+		const INHERENT_IDENTIFIER: InherentIdentifier = PARACHAIN_INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			// The proof is only needed by the requests targeting this block.
+			let now = frame_system::Pallet::<T>::block_number();
+			if !RandomnessResults::<T>::contains_key(now) {
+				return None;
+			}
+
+			let data: ParachainInherentData = data
+				.get_data(&PARACHAIN_INHERENT_IDENTIFIER)
+				.ok()
+				.flatten()?;
+
+			// Only include the proof if the relay randomness can be read from it.
+			Self::read_relay_randomness(
+				data.validation_data.relay_parent_storage_root,
+				data.relay_chain_state.clone(),
+			)?;
+
+			Some(Call::set_relay_randomness(data.relay_chain_state))
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			matches!(call, Call::set_relay_randomness(..))
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Remove a fulfilled or expired request, returning its deposit. The relay randomness of
+		/// its target block is removed with the last request needing it.
+		fn remove_request(
+			request_id: RequestId,
+			request: Request<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		) {
+			Requests::<T>::remove(request_id);
+			RandomnessResults::<T>::mutate_exists(request.fulfil_block, |result| {
+				if let Some(inner) = result {
+					inner.request_count = inner.request_count.saturating_sub(1);
+					if inner.request_count == 0 {
+						*result = None;
+					}
+				}
+			});
+			T::Currency::unreserve(&request.requester, request.deposit);
+		}
+
+		/// The random word of a request, if the relay randomness of its target block is known.
+		pub fn random_word(request_id: RequestId) -> Option<H256> {
+			let request = Requests::<T>::get(request_id)?;
+			let randomness = RandomnessResults::<T>::get(request.fulfil_block)?.randomness?;
+
+			Some(H256(sp_io::hashing::blake2_256(
+				&(randomness, request_id, request.salt).encode(),
+			)))
+		}
+
+		/// The relay chain storage key of BABE's `AuthorVrfRandomness`.
+		pub fn relay_randomness_key() -> Vec<u8> {
+			[
+				sp_io::hashing::twox_128(b"Babe"),
+				sp_io::hashing::twox_128(b"AuthorVrfRandomness"),
+			]
+			.concat()
+		}
+
+		/// Read the VRF output of the relay parent author from the relay state proof.
+		/// Returns `None` if the proof is invalid, or if the relay parent has no VRF output.
+		pub fn read_relay_randomness(
+			relay_storage_root: H256,
+			relay_state_proof: StorageProof,
+		) -> Option<H256> {
+			let db = relay_state_proof.into_memory_db::<BlakeTwo256>();
+			let encoded = sp_trie::read_trie_value::<sp_trie::Layout<BlakeTwo256>, _>(
+				&db,
+				&relay_storage_root,
+				&Self::relay_randomness_key(),
+			)
+			.ok()??;
+
+			let randomness: Option<[u8; 32]> = Decode::decode(&mut &encoded[..]).ok()?;
+
+			randomness.map(H256)
+		}
+	}
+}
@@ -0,0 +1,217 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A minimal runtime including the randomness pallet, with a mocked relay state
+use crate as pallet_randomness;
+use cumulus_primitives_core::PersistedValidationData;
+use cumulus_primitives_parachain_inherent::{ParachainInherentData, INHERENT_IDENTIFIER};
+use frame_support::{
+	construct_runtime,
+	inherent::{InherentData, ProvideInherent},
+	parameter_types,
+	traits::{Everything, Get, UnfilteredDispatchable},
+};
+use frame_system::RawOrigin;
+use parity_scale_codec::Encode;
+use sp_core::H256;
+use sp_io;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use sp_trie::StorageProof;
+use std::cell::RefCell;
+
+pub type AccountId = u64;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Randomness: pallet_randomness::{Pallet, Call, Storage, Event<T>, Inherent},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u128 = 1;
+}
+impl pallet_balances::Config for Test {
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 4];
+	type MaxLocks = ();
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+thread_local! {
+	static RELAY_STORAGE_ROOT: RefCell<Option<H256>> = RefCell::new(None);
+}
+
+/// The storage root of the mocked relay state, as set by `mock_relay_state`.
+pub struct MockRelayStorageRoot;
+impl Get<Option<H256>> for MockRelayStorageRoot {
+	fn get() -> Option<H256> {
+		RELAY_STORAGE_ROOT.with(|root| *root.borrow())
+	}
+}
+
+parameter_types! {
+	pub const Deposit: Balance = 100;
+	pub const MinBlockDelay: BlockNumber = 2;
+	pub const MaxBlockDelay: BlockNumber = 10;
+}
+impl pallet_randomness::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type Deposit = Deposit;
+	type MinBlockDelay = MinBlockDelay;
+	type MaxBlockDelay = MaxBlockDelay;
+	type RelayStorageRoot = MockRelayStorageRoot;
+}
+
+/// Externality builder for pallet randomness's mock runtime
+/// Allows configuring balances
+pub(crate) struct ExtBuilder {
+	/// Accounts endowed with balances
+	balances: Vec<(AccountId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> ExtBuilder {
+		ExtBuilder { balances: vec![] }
+	}
+}
+
+impl ExtBuilder {
+	pub(crate) fn with_balances(mut self, balances: Vec<(AccountId, Balance)>) -> Self {
+		self.balances = balances;
+		self
+	}
+
+	pub(crate) fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.expect("Frame system builds valid default genesis config");
+
+		pallet_balances::GenesisConfig::<Test> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.expect("Pallet balances storage can be assimilated");
+
+		RELAY_STORAGE_ROOT.with(|root| *root.borrow_mut() = None);
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+pub(crate) fn last_event() -> Event {
+	System::events().pop().expect("Event expected").event
+}
+
+/// Mock the relay state of the relay parent, where BABE's `AuthorVrfRandomness` is `randomness`.
+/// Its storage root becomes the trusted relay parent storage root, and the parachain inherent data
+/// holding its proof is returned.
+pub(crate) fn mock_relay_state(randomness: Option<[u8; 32]>) -> InherentData {
+	let key = Randomness::relay_randomness_key();
+
+	let mut backend = sp_state_machine::new_in_mem::<BlakeTwo256>();
+	backend.insert(vec![(None, vec![(key.clone(), Some(randomness.encode()))])]);
+	let relay_parent_storage_root = *backend.root();
+	let relay_chain_state: StorageProof =
+		sp_state_machine::prove_read(backend, vec![key]).expect("prove read");
+
+	RELAY_STORAGE_ROOT.with(|root| *root.borrow_mut() = Some(relay_parent_storage_root));
+
+	let mut inherent_data = InherentData::default();
+	inherent_data
+		.put_data(
+			INHERENT_IDENTIFIER,
+			&ParachainInherentData {
+				validation_data: PersistedValidationData {
+					relay_parent_storage_root,
+					..Default::default()
+				},
+				relay_chain_state,
+				downward_messages: Default::default(),
+				horizontal_messages: Default::default(),
+			},
+		)
+		.expect("failed to put parachain inherent data");
+	inherent_data
+}
+
+/// Move to the next block, including the randomness inherent if it is created for the given
+/// relay randomness.
+pub(crate) fn next_block_with_relay_randomness(randomness: Option<[u8; 32]>) {
+	System::set_block_number(System::block_number() + 1);
+
+	if let Some(call) = Randomness::create_inherent(&mock_relay_state(randomness)) {
+		call.dispatch_bypass_filter(RawOrigin::None.into())
+			.expect("dispatch succeeded");
+	}
+}
+
+/// Move to block `n`, with the same relay randomness in each block.
+pub(crate) fn roll_to_with_relay_randomness(n: BlockNumber, randomness: Option<[u8; 32]>) {
+	while System::block_number() < n {
+		next_block_with_relay_randomness(randomness);
+	}
+}
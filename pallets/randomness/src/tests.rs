@@ -0,0 +1,325 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit testing
+use crate::mock::{
+	last_event, mock_relay_state, next_block_with_relay_randomness, roll_to_with_relay_randomness,
+	Balances, Event as MetaEvent, ExtBuilder, Origin, Randomness, System, Test,
+};
+use crate::{Error, Event, RandomnessResult, Request};
+use frame_support::{assert_noop, assert_ok, inherent::ProvideInherent};
+use parity_scale_codec::Encode;
+use sp_core::H256;
+
+const RELAY_RANDOMNESS: [u8; 32] = [7; 32];
+
+fn salt() -> H256 {
+	H256::repeat_byte(0x42)
+}
+
+#[test]
+fn request_randomness_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+
+			assert_eq!(Balances::reserved_balance(&1), 100);
+			assert_eq!(Randomness::request_count(), 1);
+			assert_eq!(
+				Randomness::requests(0),
+				Some(Request {
+					requester: 1,
+					deposit: 100,
+					salt: salt(),
+					fulfil_block: 3,
+				})
+			);
+			assert_eq!(
+				Randomness::randomness_results(3),
+				Some(RandomnessResult {
+					randomness: None,
+					request_count: 1,
+				})
+			);
+			assert_eq!(
+				last_event(),
+				MetaEvent::Randomness(Event::RandomnessRequested(0, 1, 3))
+			);
+		})
+}
+
+#[test]
+fn request_randomness_rejects_invalid_delay() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Randomness::request_randomness(Origin::signed(1), salt(), 1),
+				Error::<Test>::InvalidBlockDelay
+			);
+			assert_noop!(
+				Randomness::request_randomness(Origin::signed(1), salt(), 11),
+				Error::<Test>::InvalidBlockDelay
+			);
+		})
+}
+
+#[test]
+fn request_randomness_fails_without_deposit() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 10)])
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Randomness::request_randomness(Origin::signed(1), salt(), 2),
+				Error::<Test>::CannotAffordDeposit
+			);
+		})
+}
+
+#[test]
+fn inherent_is_only_created_for_targeted_blocks() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(
+				Randomness::create_inherent(&mock_relay_state(Some(RELAY_RANDOMNESS))).is_none()
+			);
+
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			System::set_block_number(3);
+
+			assert!(
+				Randomness::create_inherent(&mock_relay_state(Some(RELAY_RANDOMNESS))).is_some()
+			);
+		})
+}
+
+#[test]
+fn inherent_is_not_created_without_relay_randomness() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			System::set_block_number(3);
+
+			// The relay parent has no VRF output.
+			assert!(Randomness::create_inherent(&mock_relay_state(None)).is_none());
+		})
+}
+
+#[test]
+fn relay_randomness_is_recorded_in_the_targeted_block() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+
+			next_block_with_relay_randomness(Some([1; 32]));
+			assert_eq!(Randomness::randomness_results(3).unwrap().randomness, None);
+
+			next_block_with_relay_randomness(Some(RELAY_RANDOMNESS));
+			assert_eq!(
+				Randomness::randomness_results(3).unwrap().randomness,
+				Some(H256(RELAY_RANDOMNESS))
+			);
+			assert_eq!(
+				last_event(),
+				MetaEvent::Randomness(Event::RelayRandomnessRecorded(H256(RELAY_RANDOMNESS)))
+			);
+		})
+}
+
+#[test]
+fn set_relay_randomness_rejects_proof_of_another_relay_state() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			System::set_block_number(3);
+
+			let call = Randomness::create_inherent(&mock_relay_state(Some(RELAY_RANDOMNESS)))
+				.expect("inherent is created");
+			// The trusted storage root is now the one of another relay state.
+			mock_relay_state(Some([1; 32]));
+
+			let proof = match call {
+				crate::Call::set_relay_randomness(proof) => proof,
+				_ => unreachable!(),
+			};
+			assert_noop!(
+				Randomness::set_relay_randomness(Origin::none(), proof),
+				Error::<Test>::InvalidRelayStateProof
+			);
+		})
+}
+
+#[test]
+fn fulfil_request_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			roll_to_with_relay_randomness(3, Some(RELAY_RANDOMNESS));
+
+			let random_word = H256(sp_io::hashing::blake2_256(
+				&(H256(RELAY_RANDOMNESS), 0u64, salt()).encode(),
+			));
+			assert_eq!(Randomness::random_word(0), Some(random_word));
+
+			assert_ok!(Randomness::fulfil_request(Origin::signed(1), 0));
+
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Randomness::requests(0), None);
+			assert_eq!(Randomness::randomness_results(3), None);
+			assert_eq!(
+				last_event(),
+				MetaEvent::Randomness(Event::RequestFulfilled(0, random_word))
+			);
+		})
+}
+
+#[test]
+fn requests_of_the_same_block_get_different_words() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			roll_to_with_relay_randomness(3, Some(RELAY_RANDOMNESS));
+
+			assert_ne!(Randomness::random_word(0), Randomness::random_word(1));
+
+			// The randomness is kept until the last request is fulfilled.
+			assert_ok!(Randomness::fulfil_request(Origin::signed(1), 0));
+			assert_eq!(Randomness::randomness_results(3).unwrap().request_count, 1);
+			assert_ok!(Randomness::fulfil_request(Origin::signed(1), 1));
+			assert_eq!(Randomness::randomness_results(3), None);
+		})
+}
+
+#[test]
+fn fulfil_request_fails_before_the_target_block() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			roll_to_with_relay_randomness(2, Some(RELAY_RANDOMNESS));
+
+			assert_noop!(
+				Randomness::fulfil_request(Origin::signed(1), 0),
+				Error::<Test>::RequestNotReady
+			);
+		})
+}
+
+#[test]
+fn fulfil_request_fails_for_another_account() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			roll_to_with_relay_randomness(3, Some(RELAY_RANDOMNESS));
+
+			assert_noop!(
+				Randomness::fulfil_request(Origin::signed(2), 0),
+				Error::<Test>::NotYourRequest
+			);
+			assert_noop!(
+				Randomness::fulfil_request(Origin::signed(1), 1),
+				Error::<Test>::RequestNotFound
+			);
+		})
+}
+
+#[test]
+fn fulfil_request_fails_without_relay_randomness() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			roll_to_with_relay_randomness(3, None);
+
+			assert_noop!(
+				Randomness::fulfil_request(Origin::signed(1), 0),
+				Error::<Test>::RandomnessNotAvailable
+			);
+		})
+}
+
+#[test]
+fn expire_request_works_without_relay_randomness() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			roll_to_with_relay_randomness(4, None);
+
+			// Any account can expire the request, whose deposit returns to the requester.
+			assert_ok!(Randomness::expire_request(Origin::signed(2), 0));
+
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Randomness::requests(0), None);
+			assert_eq!(Randomness::randomness_results(3), None);
+			assert_eq!(
+				last_event(),
+				MetaEvent::Randomness(Event::RequestExpired(0))
+			);
+		})
+}
+
+#[test]
+fn expire_request_fails_while_the_request_can_be_fulfilled() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 2));
+			assert_ok!(Randomness::request_randomness(Origin::signed(1), salt(), 3));
+
+			// The relay randomness could still be recorded in the target block.
+			roll_to_with_relay_randomness(3, None);
+			assert_noop!(
+				Randomness::expire_request(Origin::signed(1), 0),
+				Error::<Test>::RequestNotExpired
+			);
+
+			// The relay randomness was recorded in the target block.
+			roll_to_with_relay_randomness(5, Some(RELAY_RANDOMNESS));
+			assert_noop!(
+				Randomness::expire_request(Origin::signed(1), 1),
+				Error::<Test>::RequestNotExpired
+			);
+
+			assert_noop!(
+				Randomness::expire_request(Origin::signed(1), 2),
+				Error::<Test>::RequestNotFound
+			);
+		})
+}
@@ -0,0 +1,232 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Xcm Transactor Pallet
+//!
+//! This pallet allows local accounts to dispatch calls on the relay chain through XCM
+//! `Transact`, from a derivative account of the sovereign account of this parachain.
+//!
+//! Derivative accounts are the accounts `utility.as_derivative` dispatches from, and are
+//! identified by a `u16` index. Each index is registered to a local account, which is the only
+//! one allowed to transact through it.
+//!
+//! This pallet has one storage item: IndexToAccount, which holds the local account owning each
+//! derivative index.
+//!
+//! This pallet has three extrinsics: register, which assigns a derivative index to an account,
+//! deregister, which frees it, and transact_through_derivative, which sends the call wrapped in
+//! `utility.as_derivative` to the relay chain. The XCM fees are paid with the relay chain token,
+//! which is withdrawn from the local account of the caller and then from the sovereign account on
+//! the relay chain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::pallet;
+pub use pallet::*;
+#[cfg(test)]
+pub mod mock;
+#[cfg(test)]
+pub mod tests;
+
+#[pallet]
+pub mod pallet {
+
+	use frame_support::{pallet_prelude::*, transactional, weights::Weight};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{AtLeast32BitUnsigned, Convert};
+	use sp_std::prelude::*;
+	use xcm::v1::{
+		AssetId, Fungibility, MultiAsset, MultiLocation, Order, OriginKind, SendXcm, Xcm,
+	};
+	use xcm_executor::traits::TransactAsset;
+	use xcm_primitives::relay_calls::{encode_as_derivative, UtilityCallIndices};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The units in which the XCM fees are given.
+		type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy + Into<u128>;
+
+		/// Convert a local account into a MultiLocation, to withdraw the fees from it.
+		type AccountIdToMultiLocation: Convert<Self::AccountId, MultiLocation>;
+
+		/// The transactor used to withdraw the relay chain token paying for the fees.
+		type AssetTransactor: TransactAsset;
+
+		/// How to send the XCM message to the relay chain.
+		type XcmSender: SendXcm;
+
+		/// The weight bought on the relay chain in addition to the weight of the call, to pay
+		/// for the execution of the XCM message itself.
+		type BaseXcmWeight: Get<Weight>;
+
+		/// The indices of the utility pallet of the relay chain and of `as_derivative`.
+		type RelayUtilityCallIndices: Get<UtilityCallIndices>;
+
+		/// Origin that is allowed to register and deregister derivative indices.
+		type DerivativeAddressRegistrationOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The maximum length of the encoded relay chain call sent by
+		/// `transact_through_derivative`.
+		type MaxCallLength: Get<u32>;
+
+		/// The weight of each byte of the relay chain call, which is copied into the XCM message
+		/// and the event.
+		type WeightPerCallByte: Get<Weight>;
+	}
+
+	/// An error that can occur while executing the xcm transactor pallet's logic.
+	#[pallet::error]
+	pub enum Error<T> {
+		IndexAlreadyClaimed,
+		UnclaimedIndex,
+		NotOwner,
+		UnableToWithdrawAsset,
+		ErrorSending,
+		CallTooLong,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A call was sent to the relay chain. [account, destination, call, index]
+		TransactedDerivative(T::AccountId, MultiLocation, Vec<u8>, u16),
+		/// A derivative index was registered to an account. [account, index]
+		RegisteredDerivative(T::AccountId, u16),
+		/// A derivative index was deregistered. [index]
+		DeRegisteredDerivative(u16),
+	}
+
+	/// Stores the local account owning each derivative index
+	#[pallet::storage]
+	#[pallet::getter(fn index_to_account)]
+	pub type IndexToAccount<T: Config> = StorageMap<_, Blake2_128Concat, u16, T::AccountId>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a derivative index for an account
+		#[pallet::weight(0)]
+		pub fn register(origin: OriginFor<T>, who: T::AccountId, index: u16) -> DispatchResult {
+			T::DerivativeAddressRegistrationOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				IndexToAccount::<T>::get(&index).is_none(),
+				Error::<T>::IndexAlreadyClaimed
+			);
+
+			IndexToAccount::<T>::insert(&index, who.clone());
+
+			Self::deposit_event(Event::RegisteredDerivative(who, index));
+			Ok(())
+		}
+
+		/// Deregister a derivative index, which can then be registered to another account
+		#[pallet::weight(0)]
+		pub fn deregister(origin: OriginFor<T>, index: u16) -> DispatchResult {
+			T::DerivativeAddressRegistrationOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				IndexToAccount::<T>::get(&index).is_some(),
+				Error::<T>::UnclaimedIndex
+			);
+
+			IndexToAccount::<T>::remove(&index);
+
+			Self::deposit_event(Event::DeRegisteredDerivative(index));
+			Ok(())
+		}
+
+		/// Dispatch an encoded relay chain call from the derivative account `index`, which must
+		/// be owned by the sender. `fee_amount` of the relay chain token is withdrawn from the
+		/// sender to buy `dest_weight` on the relay chain, which must cover the call.
+		#[pallet::weight(Pallet::<T>::transact_through_derivative_weight(inner_call.len()))]
+		#[transactional]
+		pub fn transact_through_derivative(
+			origin: OriginFor<T>,
+			index: u16,
+			fee_amount: T::Balance,
+			dest_weight: Weight,
+			inner_call: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(
+				inner_call.len() <= T::MaxCallLength::get() as usize,
+				Error::<T>::CallTooLong
+			);
+
+			let account = IndexToAccount::<T>::get(&index).ok_or(Error::<T>::UnclaimedIndex)?;
+			ensure!(account == who, Error::<T>::NotOwner);
+
+			let call = encode_as_derivative(&T::RelayUtilityCallIndices::get(), index, inner_call);
+
+			// Burn the local representation of the relay chain token, which is then withdrawn
+			// from the sovereign account on the relay chain to pay for the execution.
+			let fee = MultiAsset {
+				id: AssetId::Concrete(MultiLocation::parent()),
+				fun: Fungibility::Fungible(fee_amount.into()),
+			};
+			T::AssetTransactor::withdraw_asset(&fee, &T::AccountIdToMultiLocation::convert(who))
+				.map_err(|_| Error::<T>::UnableToWithdrawAsset)?;
+
+			let dest = MultiLocation::parent();
+			let message = Self::transact_message(fee_amount.into(), dest_weight, call.clone());
+			T::XcmSender::send_xcm(dest.clone(), message).map_err(|_| Error::<T>::ErrorSending)?;
+
+			Self::deposit_event(Event::TransactedDerivative(account, dest, call, index));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The weight of `transact_through_derivative` for a relay chain call of `call_length`
+		/// bytes: the index lookup, the withdrawal of the fees (balance and total issuance) and
+		/// the upward message queue, plus the copies of the call.
+		pub fn transact_through_derivative_weight(call_length: usize) -> Weight {
+			T::DbWeight::get()
+				.reads_writes(4, 3)
+				.saturating_add(T::WeightPerCallByte::get().saturating_mul(call_length as Weight))
+		}
+
+		/// The message withdrawing `fee_amount` from the sovereign account of this parachain to buy
+		/// `dest_weight`, and dispatching `call` from it.
+		pub fn transact_message(fee_amount: u128, dest_weight: Weight, call: Vec<u8>) -> Xcm<()> {
+			let fees = MultiAsset {
+				id: AssetId::Concrete(MultiLocation::here()),
+				fun: Fungibility::Fungible(fee_amount),
+			};
+
+			Xcm::WithdrawAsset {
+				assets: fees.clone().into(),
+				effects: vec![Order::BuyExecution {
+					fees,
+					weight: dest_weight,
+					debt: T::BaseXcmWeight::get(),
+					halt_on_error: true,
+					instructions: vec![Xcm::Transact {
+						origin_type: OriginKind::SovereignAccount,
+						require_weight_at_most: dest_weight,
+						call: call.into(),
+					}],
+				}],
+			}
+		}
+	}
+}
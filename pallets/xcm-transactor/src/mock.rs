@@ -0,0 +1,191 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate as pallet_xcm_transactor;
+
+use frame_support::{construct_runtime, parameter_types, weights::Weight};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+};
+use std::cell::RefCell;
+use xcm::v1::{
+	Error as XcmError, Junction, Junctions, MultiAsset, MultiLocation, NetworkId,
+	Result as XcmResult, SendXcm, Xcm,
+};
+use xcm_executor::{traits::TransactAsset, Assets};
+use xcm_primitives::relay_calls::UtilityCallIndices;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		XcmTransactor: pallet_xcm_transactor::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+/// An account without relay chain tokens, from which withdrawing the fees fails.
+pub const POOR_ACCOUNT: u64 = 3;
+
+pub struct AccountIdToMultiLocation;
+impl Convert<u64, MultiLocation> for AccountIdToMultiLocation {
+	fn convert(account: u64) -> MultiLocation {
+		Junction::AccountIndex64 {
+			network: NetworkId::Any,
+			index: account,
+		}
+		.into()
+	}
+}
+
+pub struct DummyAssetTransactor;
+impl TransactAsset for DummyAssetTransactor {
+	fn withdraw_asset(_what: &MultiAsset, who: &MultiLocation) -> Result<Assets, XcmError> {
+		if who == &AccountIdToMultiLocation::convert(POOR_ACCOUNT) {
+			return Err(XcmError::NotWithdrawable);
+		}
+
+		Ok(Assets::default())
+	}
+}
+
+thread_local! {
+	pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+}
+
+/// The messages sent through `TestSendXcm`.
+pub(crate) fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+	SENT_XCM.with(|q| (*q.borrow()).clone())
+}
+
+/// Sender that only records the messages sent to the relay chain.
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	fn send_xcm(dest: MultiLocation, msg: Xcm<()>) -> XcmResult {
+		if dest.interior != Junctions::Here || dest.parents != 1 {
+			return Err(XcmError::CannotReachDestination(dest, msg));
+		}
+
+		SENT_XCM.with(|q| q.borrow_mut().push((dest, msg)));
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const BaseXcmWeight: Weight = 1000;
+	pub const RelayUtilityCallIndices: UtilityCallIndices = UtilityCallIndices {
+		pallet: 16,
+		as_derivative: 1,
+	};
+	pub const MaxCallLength: u32 = 4;
+	pub const WeightPerCallByte: Weight = 10;
+}
+
+impl Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AccountIdToMultiLocation = AccountIdToMultiLocation;
+	type AssetTransactor = DummyAssetTransactor;
+	type XcmSender = TestSendXcm;
+	type BaseXcmWeight = BaseXcmWeight;
+	type RelayUtilityCallIndices = RelayUtilityCallIndices;
+	type DerivativeAddressRegistrationOrigin = EnsureRoot<u64>;
+	type MaxCallLength = MaxCallLength;
+	type WeightPerCallByte = WeightPerCallByte;
+}
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub(crate) fn events() -> Vec<super::Event<Test>> {
+	System::events()
+		.into_iter()
+		.map(|r| r.event)
+		.filter_map(|e| {
+			if let Event::XcmTransactor(inner) = e {
+				Some(inner)
+			} else {
+				None
+			}
+		})
+		.collect::<Vec<_>>()
+}
+
+pub fn expect_events(e: Vec<super::Event<Test>>) {
+	assert_eq!(events(), e);
+}
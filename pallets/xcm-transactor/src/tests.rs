@@ -0,0 +1,163 @@
+// Tests for XcmTransactor Pallet
+use crate::*;
+use mock::*;
+
+use frame_support::{assert_noop, assert_ok, weights::GetDispatchInfo};
+use sp_runtime::DispatchError;
+use xcm::v1::MultiLocation;
+
+#[test]
+fn registering_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		assert_eq!(XcmTransactor::index_to_account(&1).unwrap(), 1u64);
+		expect_events(vec![crate::Event::RegisteredDerivative(1u64, 1)])
+	});
+}
+
+#[test]
+fn test_register_address() {
+	new_test_ext().execute_with(|| {
+		// Only root can register
+		assert_noop!(
+			XcmTransactor::register(Origin::signed(1u64), 1u64, 1),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		// An index cannot be claimed twice
+		assert_noop!(
+			XcmTransactor::register(Origin::root(), 2u64, 1),
+			Error::<Test>::IndexAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn test_deregister_address() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmTransactor::deregister(Origin::root(), 1),
+			Error::<Test>::UnclaimedIndex
+		);
+
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+		assert_noop!(
+			XcmTransactor::deregister(Origin::signed(1u64), 1),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(XcmTransactor::deregister(Origin::root(), 1));
+
+		assert!(XcmTransactor::index_to_account(&1).is_none());
+		// The index can then be registered again
+		assert_ok!(XcmTransactor::register(Origin::root(), 2u64, 1));
+
+		expect_events(vec![
+			crate::Event::RegisteredDerivative(1u64, 1),
+			crate::Event::DeRegisteredDerivative(1),
+			crate::Event::RegisteredDerivative(2u64, 1),
+		])
+	});
+}
+
+#[test]
+fn test_transact_through_derivative_errors() {
+	new_test_ext().execute_with(|| {
+		// The index is not registered
+		assert_noop!(
+			XcmTransactor::transact_through_derivative(
+				Origin::signed(1u64),
+				1,
+				100u64,
+				100u64,
+				vec![1u8]
+			),
+			Error::<Test>::UnclaimedIndex
+		);
+
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+		assert_ok!(XcmTransactor::register(Origin::root(), POOR_ACCOUNT, 3));
+
+		// The index is registered to another account
+		assert_noop!(
+			XcmTransactor::transact_through_derivative(
+				Origin::signed(2u64),
+				1,
+				100u64,
+				100u64,
+				vec![1u8]
+			),
+			Error::<Test>::NotOwner
+		);
+
+		// The fees cannot be withdrawn
+		assert_noop!(
+			XcmTransactor::transact_through_derivative(
+				Origin::signed(POOR_ACCOUNT),
+				3,
+				100u64,
+				100u64,
+				vec![1u8]
+			),
+			Error::<Test>::UnableToWithdrawAsset
+		);
+
+		// The call is longer than `MaxCallLength`
+		assert_noop!(
+			XcmTransactor::transact_through_derivative(
+				Origin::signed(1u64),
+				1,
+				100u64,
+				100u64,
+				vec![1u8; 5]
+			),
+			Error::<Test>::CallTooLong
+		);
+
+		assert!(sent_xcm().is_empty());
+	});
+}
+
+#[test]
+fn test_transact_through_derivative_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		assert_ok!(XcmTransactor::transact_through_derivative(
+			Origin::signed(1u64),
+			1,
+			100u64,
+			100u64,
+			vec![1u8]
+		));
+
+		// utility.as_derivative(1, call)
+		let expected_call = vec![16u8, 1u8, 1u8, 0u8, 1u8];
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				MultiLocation::parent(),
+				XcmTransactor::transact_message(100, 100, expected_call.clone())
+			)]
+		);
+
+		expect_events(vec![
+			crate::Event::RegisteredDerivative(1u64, 1),
+			crate::Event::TransactedDerivative(1u64, MultiLocation::parent(), expected_call, 1),
+		])
+	});
+}
+
+#[test]
+fn transact_through_derivative_weight_depends_on_call_length() {
+	let weight = |inner_call: Vec<u8>| {
+		Call::<Test>::transact_through_derivative(1, 100u64, 100u64, inner_call)
+			.get_dispatch_info()
+			.weight
+	};
+
+	assert_eq!(weight(vec![1u8]), 10);
+	assert_eq!(weight(vec![1u8; 4]), 40);
+}
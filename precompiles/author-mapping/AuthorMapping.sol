@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Manage the association between author ids and accounts. Author ids are given as their 32
+/// bytes public key.
+/// This file is generated from the Rust implementation of the precompile.
+interface AuthorMapping {
+    /// Associate `author_id` with the caller, reserving the security deposit.
+    /// Selector: ef8b6cd8
+    function addAssociation(bytes32 author_id) external;
+
+    /// Replace `old_author_id`, associated with the caller, by `new_author_id`. No new security
+    /// deposit is required.
+    /// Selector: 25a39da5
+    function updateAssociation(bytes32 old_author_id, bytes32 new_author_id) external;
+
+    /// Clear the association of `author_id` with the caller, unreserving the security deposit.
+    /// Selector: 448b54d6
+    function clearAssociation(bytes32 author_id) external;
+
+    /// The account associated with `author_id`, or the zero address if there is none.
+    /// Selector: 0b61a887
+    function accountOf(bytes32 author_id) external view returns (address);
+}
@@ -0,0 +1,135 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to associate the author ids used at the consensus layer (nimbus ids) with
+//! accounts through pallet-author-mapping.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Decode;
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::GetCallMetadata,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{error, precompile, Address, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_core::{H160, H256};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// A precompile to wrap the functionality from pallet-author-mapping.
+/// It must be installed with `precompile_utils::check_not_delegated`, as the associations of the
+/// caller are managed, reserving its deposit.
+pub struct AuthorMappingWrapper<Runtime>(PhantomData<Runtime>);
+
+/// Manage the association between author ids and accounts. Author ids are given as their 32
+/// bytes public key.
+#[precompile(interface = "AuthorMapping")]
+impl<Runtime> AuthorMappingWrapper<Runtime>
+where
+	Runtime: pallet_author_mapping::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::AccountId: Into<H160>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_author_mapping::Call<Runtime>>,
+{
+	/// Associate `author_id` with the caller, reserving the security deposit.
+	#[precompile::public("addAssociation(bytes32)")]
+	fn add_association(handle: &mut PrecompileHandle, author_id: H256) -> EvmResult {
+		let author_id = Self::decode_author_id(author_id)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_author_mapping::Call::<Runtime>::add_association(author_id);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Replace `old_author_id`, associated with the caller, by `new_author_id`. No new security
+	/// deposit is required.
+	#[precompile::public("updateAssociation(bytes32,bytes32)")]
+	fn update_association(
+		handle: &mut PrecompileHandle,
+		old_author_id: H256,
+		new_author_id: H256,
+	) -> EvmResult {
+		let old_author_id = Self::decode_author_id(old_author_id)?;
+		let new_author_id = Self::decode_author_id(new_author_id)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_author_mapping::Call::<Runtime>::update_association(
+			old_author_id,
+			new_author_id,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Clear the association of `author_id` with the caller, unreserving the security deposit.
+	#[precompile::public("clearAssociation(bytes32)")]
+	fn clear_association(handle: &mut PrecompileHandle, author_id: H256) -> EvmResult {
+		let author_id = Self::decode_author_id(author_id)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_author_mapping::Call::<Runtime>::clear_association(author_id);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// The account associated with `author_id`, or the zero address if there is none.
+	#[precompile::public("accountOf(bytes32)")]
+	#[precompile::view]
+	fn account_of(handle: &mut PrecompileHandle, author_id: H256) -> EvmResult<Address> {
+		let author_id = Self::decode_author_id(author_id)?;
+
+		let account =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_author_mapping::Pallet::<Runtime>::account_id_of(&author_id))
+			})?;
+
+		Ok(Address(account.map(Into::into).unwrap_or_default()))
+	}
+
+	/// Decode an author id from its 32 bytes public key.
+	fn decode_author_id(author_id: H256) -> EvmResult<Runtime::AuthorId> {
+		Runtime::AuthorId::decode(&mut author_id.as_bytes())
+			.map_err(|_| error("failed decoding author id"))
+	}
+}
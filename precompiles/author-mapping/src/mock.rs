@@ -0,0 +1,62 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities.
+
+use super::*;
+
+use frame_support::{construct_runtime, parameter_types};
+use nimbus_primitives::NimbusId;
+
+pub use precompile_utils::testing::{Account, TestPrecompiles, PRECOMPILE_ADDRESS};
+
+pub type AccountId = Account;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles<Runtime>);
+
+parameter_types! {
+	pub const DepositAmount: Balance = 100;
+}
+
+impl pallet_author_mapping::Config for Runtime {
+	type Event = Event;
+	type AuthorId = NimbusId;
+	type DepositCurrency = Balances;
+	type DepositAmount = DepositAmount;
+	type WeightInfo = ();
+}
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		AuthorMapping: pallet_author_mapping::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+pub type Precompiles<R> = TestPrecompiles<AuthorMappingWrapper<R>>;
+
+pub(crate) type ExtBuilder = precompile_utils::testing::ExtBuilder<Runtime>;
@@ -0,0 +1,265 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::Context;
+use pallet_evm::PrecompileSet;
+use precompile_utils::{
+	testing::{check_solidity_interface, PrecompileTesterExt},
+	EvmDataWriter,
+};
+use sp_core::U256;
+
+fn author_id(byte: u8) -> H256 {
+	H256::repeat_byte(byte)
+}
+
+fn add_association_input(author_id: H256) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::AddAssociation as u32)
+		.write(author_id)
+		.build()
+}
+
+fn account_of_input(author_id: H256) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::AccountOf as u32)
+		.write(author_id)
+		.build()
+}
+
+fn add_association(who: Account, author_id: H256) {
+	Precompiles::<Runtime>::prepare_test(
+		who,
+		Account::Precompile,
+		add_association_input(author_id),
+	)
+	.execute_stops();
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::AddAssociation as u32, 0xef8b6cd8);
+	assert_eq!(Action::UpdateAssociation as u32, 0x25a39da5);
+	assert_eq!(Action::ClearAssociation as u32, 0x448b54d6);
+	assert_eq!(Action::AccountOf as u32, 0x0b61a887);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "AuthorMapping.sol");
+}
+
+#[test]
+fn account_of_is_zero_without_association() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			account_of_input(author_id(1)),
+		)
+		.execute_returns_encoded(Address(H160::zero()));
+	});
+}
+
+#[test]
+fn add_association_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			add_association(Account::Alice, author_id(1));
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 100);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				account_of_input(author_id(1)),
+			)
+			.execute_returns_encoded(Address(Account::Alice.into()));
+		});
+}
+
+#[test]
+fn add_association_fails_if_already_associated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000), (Account::Bob, 1000)])
+		.build()
+		.execute_with(|| {
+			add_association(Account::Alice, author_id(1));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				add_association_input(author_id(1)),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call AuthorMapping::add_association failed")
+			});
+		});
+}
+
+#[test]
+fn add_association_fails_without_security_deposit() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 99)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				add_association_input(author_id(1)),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call AuthorMapping::add_association failed")
+			});
+		});
+}
+
+#[test]
+fn update_association_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			add_association(Account::Alice, author_id(1));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::UpdateAssociation as u32)
+					.write(author_id(1))
+					.write(author_id(2))
+					.build(),
+			)
+			.execute_stops();
+
+			// No new security deposit is taken.
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 100);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				account_of_input(author_id(1)),
+			)
+			.execute_returns_encoded(Address(H160::zero()));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				account_of_input(author_id(2)),
+			)
+			.execute_returns_encoded(Address(Account::Alice.into()));
+		});
+}
+
+#[test]
+fn update_association_fails_for_another_account() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			add_association(Account::Alice, author_id(1));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::UpdateAssociation as u32)
+					.write(author_id(1))
+					.write(author_id(2))
+					.build(),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call AuthorMapping::update_association failed")
+			});
+		});
+}
+
+#[test]
+fn clear_association_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			add_association(Account::Alice, author_id(1));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::ClearAssociation as u32)
+					.write(author_id(1))
+					.build(),
+			)
+			.execute_stops();
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				account_of_input(author_id(1)),
+			)
+			.execute_returns_encoded(Address(H160::zero()));
+		});
+}
+
+#[test]
+fn clear_association_fails_without_association() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::ClearAssociation as u32)
+					.write(author_id(1))
+					.build(),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call AuthorMapping::clear_association failed")
+			});
+		});
+}
+
+#[test]
+fn author_mapping_cannot_be_delegated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context is the one of the calling contract (Charlie), while
+			// the caller is the account which called it (Alice).
+			let context = Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&add_association_input(author_id(1)),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+		});
+}
@@ -0,0 +1,50 @@
+pragma solidity ^0.8.0;
+
+/**
+ * @title ERC20 permit extension (EIP-2612)
+ * @dev see https://eips.ethereum.org/EIPS/eip-2612
+ * @dev The domain of the permits is built from the chain id and the address of the precompile:
+ * keccak256(abi.encode(keccak256("EIP712Domain(uint256 chainId,address verifyingContract)"),
+ * chainId, verifyingContract))
+ */
+interface IERC20Permit {
+  /**
+   * @dev Approve `spender` to spend `value` tokens of `owner`, given the signature of `owner`.
+   * The signature is the EIP-712 signature of
+   * Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)
+   * where nonce is the current nonce of `owner`, which is incremented when the permit is used.
+   * Selector: d505accf
+   * @param owner address The address which owns the funds.
+   * @param spender address The address which will spend the funds.
+   * @param value uint256 The amount of tokens to be spent.
+   * @param deadline uint256 The timestamp, in seconds, after which the permit is expired.
+   * @param v uint8 The recovery id of the signature.
+   * @param r bytes32 The r value of the signature.
+   * @param s bytes32 The s value of the signature.
+   */
+  function permit(
+    address owner,
+    address spender,
+    uint256 value,
+    uint256 deadline,
+    uint8 v,
+    bytes32 r,
+    bytes32 s
+  ) external;
+
+  /**
+   * @dev Returns the current nonce of `owner`, which must be included in its next permit.
+   * Selector: 7ecebe00
+   * @param owner address The address which owns the funds.
+   * @return The current nonce of `owner`.
+   */
+  function nonces(address owner) external view returns (uint256);
+
+  /**
+   * @dev Returns the EIP-712 domain separator of the permits.
+   * Selector: 3644e515
+   * @return The domain separator.
+   */
+  // solhint-disable-next-line func-name-mixedcase
+  function DOMAIN_SEPARATOR() external view returns (bytes32);
+}
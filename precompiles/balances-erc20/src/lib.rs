@@ -17,14 +17,13 @@
 //! Precompile to interact with pallet_balances instances using the ERC20 interface standard.
 
 #![cfg_attr(not(feature = "std"), no_std)]
-#![cfg_attr(test, feature(assert_matches))]
 
 use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
 	sp_runtime::traits::{CheckedSub, StaticLookup},
-	storage::types::StorageDoubleMap,
-	traits::StorageInstance,
+	storage::types::{StorageDoubleMap, StorageMap, ValueQuery},
+	traits::{Get, GetCallMetadata, StorageInstance},
 	Blake2_128Concat,
 };
 use pallet_balances::pallet::{
@@ -33,14 +32,16 @@ use pallet_balances::pallet::{
 };
 use pallet_evm::{AddressMapping, Precompile};
 use precompile_utils::{
-	error, keccak256, Address, EvmDataReader, EvmDataWriter, EvmResult, Gasometer, LogsBuilder,
-	RuntimeHelper,
+	check_function_modifier, error, keccak256, Address, EvmDataReader, EvmDataWriter, EvmResult,
+	Gasometer, LogsBuilder, RuntimeHelper,
 };
-use sp_core::{H160, U256};
+use sp_core::{H160, H256, U256};
+use sp_io::hashing::keccak_256;
 use sp_std::{
 	convert::{TryFrom, TryInto},
 	marker::PhantomData,
 	vec,
+	vec::Vec,
 };
 
 #[cfg(test)]
@@ -54,19 +55,41 @@ pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address
 /// Solidity selector of the Approval log, which is the Keccak of the Log signature.
 pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
 
-/// Associates pallet Instance to a prefix used for the Approves storage.
+/// EIP-712 type hash of the domain of the permits, which is the Keccak of the domain type.
+pub const PERMIT_DOMAIN_TYPEHASH: [u8; 32] =
+	keccak256!("EIP712Domain(uint256 chainId,address verifyingContract)");
+
+/// EIP-712 type hash of the permits, which is the Keccak of the Permit type.
+pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
+	"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+);
+
+/// Gas cost of recovering the signer of a permit, which is the cost of the ECRecover precompile.
+pub const PERMIT_ECRECOVER_GAS_COST: u64 = 3000;
+
+/// Half the order of the secp256k1 curve. Signatures whose `s` is above it are rejected, as for
+/// Ethereum transactions (EIP-2), since `(r, n - s)` is another valid signature of the digest.
+pub const SECP256K1N_HALF: [u8; 32] = [
+	0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+	0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Associates pallet Instance to the prefixes used for the Approves and Nonces storages.
 /// This trait is implemented for () and the 16 substrate Instance.
 pub trait InstanceToPrefix {
 	/// Prefix used for the Approves storage.
 	type ApprovesPrefix: StorageInstance;
+
+	/// Prefix used for the Nonces storage.
+	type NoncesPrefix: StorageInstance;
 }
 
 // We use a macro to implement the trait for () and the 16 substrate Instance.
 macro_rules! impl_prefix {
-	($prefix:ident, $instance:ty, $name:literal) => {
-		pub struct $prefix;
+	($approves_prefix:ident, $nonces_prefix:ident, $instance:ty, $name:literal) => {
+		pub struct $approves_prefix;
 
-		impl StorageInstance for $prefix {
+		impl StorageInstance for $approves_prefix {
 			const STORAGE_PREFIX: &'static str = "Approves";
 
 			fn pallet_prefix() -> &'static str {
@@ -74,29 +97,120 @@ macro_rules! impl_prefix {
 			}
 		}
 
+		pub struct $nonces_prefix;
+
+		impl StorageInstance for $nonces_prefix {
+			const STORAGE_PREFIX: &'static str = "Nonces";
+
+			fn pallet_prefix() -> &'static str {
+				$name
+			}
+		}
+
 		impl InstanceToPrefix for $instance {
-			type ApprovesPrefix = $prefix;
+			type ApprovesPrefix = $approves_prefix;
+			type NoncesPrefix = $nonces_prefix;
 		}
 	};
 }
 
-impl_prefix!(ApprovesPrefix0, (), "Erc20Instance0Balances");
-impl_prefix!(ApprovesPrefix1, Instance1, "Erc20Instance1Balances");
-impl_prefix!(ApprovesPrefix2, Instance2, "Erc20Instance2Balances");
-impl_prefix!(ApprovesPrefix3, Instance3, "Erc20Instance3Balances");
-impl_prefix!(ApprovesPrefix4, Instance4, "Erc20Instance4Balances");
-impl_prefix!(ApprovesPrefix5, Instance5, "Erc20Instance5Balances");
-impl_prefix!(ApprovesPrefix6, Instance6, "Erc20Instance6Balances");
-impl_prefix!(ApprovesPrefix7, Instance7, "Erc20Instance7Balances");
-impl_prefix!(ApprovesPrefix8, Instance8, "Erc20Instance8Balances");
-impl_prefix!(ApprovesPrefix9, Instance9, "Erc20Instance9Balances");
-impl_prefix!(ApprovesPrefix10, Instance10, "Erc20Instance10Balances");
-impl_prefix!(ApprovesPrefix11, Instance11, "Erc20Instance11Balances");
-impl_prefix!(ApprovesPrefix12, Instance12, "Erc20Instance12Balances");
-impl_prefix!(ApprovesPrefix13, Instance13, "Erc20Instance13Balances");
-impl_prefix!(ApprovesPrefix14, Instance14, "Erc20Instance14Balances");
-impl_prefix!(ApprovesPrefix15, Instance15, "Erc20Instance15Balances");
-impl_prefix!(ApprovesPrefix16, Instance16, "Erc20Instance16Balances");
+impl_prefix!(ApprovesPrefix0, NoncesPrefix0, (), "Erc20Instance0Balances");
+impl_prefix!(
+	ApprovesPrefix1,
+	NoncesPrefix1,
+	Instance1,
+	"Erc20Instance1Balances"
+);
+impl_prefix!(
+	ApprovesPrefix2,
+	NoncesPrefix2,
+	Instance2,
+	"Erc20Instance2Balances"
+);
+impl_prefix!(
+	ApprovesPrefix3,
+	NoncesPrefix3,
+	Instance3,
+	"Erc20Instance3Balances"
+);
+impl_prefix!(
+	ApprovesPrefix4,
+	NoncesPrefix4,
+	Instance4,
+	"Erc20Instance4Balances"
+);
+impl_prefix!(
+	ApprovesPrefix5,
+	NoncesPrefix5,
+	Instance5,
+	"Erc20Instance5Balances"
+);
+impl_prefix!(
+	ApprovesPrefix6,
+	NoncesPrefix6,
+	Instance6,
+	"Erc20Instance6Balances"
+);
+impl_prefix!(
+	ApprovesPrefix7,
+	NoncesPrefix7,
+	Instance7,
+	"Erc20Instance7Balances"
+);
+impl_prefix!(
+	ApprovesPrefix8,
+	NoncesPrefix8,
+	Instance8,
+	"Erc20Instance8Balances"
+);
+impl_prefix!(
+	ApprovesPrefix9,
+	NoncesPrefix9,
+	Instance9,
+	"Erc20Instance9Balances"
+);
+impl_prefix!(
+	ApprovesPrefix10,
+	NoncesPrefix10,
+	Instance10,
+	"Erc20Instance10Balances"
+);
+impl_prefix!(
+	ApprovesPrefix11,
+	NoncesPrefix11,
+	Instance11,
+	"Erc20Instance11Balances"
+);
+impl_prefix!(
+	ApprovesPrefix12,
+	NoncesPrefix12,
+	Instance12,
+	"Erc20Instance12Balances"
+);
+impl_prefix!(
+	ApprovesPrefix13,
+	NoncesPrefix13,
+	Instance13,
+	"Erc20Instance13Balances"
+);
+impl_prefix!(
+	ApprovesPrefix14,
+	NoncesPrefix14,
+	Instance14,
+	"Erc20Instance14Balances"
+);
+impl_prefix!(
+	ApprovesPrefix15,
+	NoncesPrefix15,
+	Instance15,
+	"Erc20Instance15Balances"
+);
+impl_prefix!(
+	ApprovesPrefix16,
+	NoncesPrefix16,
+	Instance16,
+	"Erc20Instance16Balances"
+);
 
 /// Alias for the Balance type for the provided Runtime and Instance.
 pub type BalanceOf<Runtime, Instance = ()> =
@@ -114,20 +228,39 @@ pub type ApprovesStorage<Runtime, Instance> = StorageDoubleMap<
 	BalanceOf<Runtime, Instance>,
 >;
 
+/// Storage type used to store the nonces of the permits (EIP-2612), which are incremented each
+/// time a permit of the owner is used.
+/// (Owner => Nonce)
+pub type NoncesStorage<Runtime, Instance> = StorageMap<
+	<Instance as InstanceToPrefix>::NoncesPrefix,
+	Blake2_128Concat,
+	<Runtime as frame_system::Config>::AccountId,
+	U256,
+	ValueQuery,
+>;
+
 #[precompile_utils::generate_function_selector]
 #[derive(Debug, PartialEq, num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
 pub enum Action {
+	#[view]
 	TotalSupply = "totalSupply()",
+	#[view]
 	BalanceOf = "balanceOf(address)",
+	#[view]
 	Allowance = "allowance(address,address)",
 	Transfer = "transfer(address,uint256)",
 	Approve = "approve(address,uint256)",
 	TransferFrom = "transferFrom(address,address,uint256)",
+	Permit = "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+	#[view]
+	Nonces = "nonces(address)",
+	#[view]
+	DomainSeparator = "DOMAIN_SEPARATOR()",
 }
 
-/// Precompile exposing a pallet_balance as an ERC20.
+/// Precompile exposing a pallet_balance as an ERC20, with EIP-2612 permits.
 /// Multiple precompiles can support instances of pallet_balance.
-/// The precompile uses an additional storage to store approvals.
+/// The precompile uses additional storages to store approvals and the nonces of the permits.
 pub struct Erc20BalancesPrecompile<Runtime, Instance: 'static = ()>(
 	PhantomData<(Runtime, Instance)>,
 );
@@ -135,26 +268,56 @@ pub struct Erc20BalancesPrecompile<Runtime, Instance: 'static = ()>(
 impl<Runtime, Instance> Precompile for Erc20BalancesPrecompile<Runtime, Instance>
 where
 	Instance: InstanceToPrefix + 'static,
-	Runtime: pallet_balances::Config<Instance> + pallet_evm::Config,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime: pallet_balances::Config<Instance> + pallet_evm::Config + pallet_timestamp::Config,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	Runtime::Call: From<pallet_balances::Call<Runtime, Instance>>,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
 {
 	fn execute(
 		input: &[u8], //Reminder this is big-endian
 		target_gas: Option<u64>,
 		context: &Context,
+	) -> Result<PrecompileOutput, ExitError> {
+		// The EVM version currently used doesn't provide `is_static` to precompiles.
+		Self::execute_with_static(input, target_gas, context, false)
+	}
+}
+
+impl<Runtime, Instance> Erc20BalancesPrecompile<Runtime, Instance>
+where
+	Instance: InstanceToPrefix + 'static,
+	Runtime: pallet_balances::Config<Instance> + pallet_evm::Config + pallet_timestamp::Config,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	Runtime::Call: From<pallet_balances::Call<Runtime, Instance>>,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
+{
+	/// Execute the precompile, `is_static` being whether it is called in a static context
+	/// (STATICCALL).
+	pub fn execute_with_static(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
 	) -> Result<PrecompileOutput, ExitError> {
 		let mut input = EvmDataReader::new(input);
+		let selector = input.read_selector()?;
 
-		match &input.read_selector()? {
+		check_function_modifier(context, is_static, selector.modifier())?;
+
+		match selector {
 			Action::TotalSupply => Self::total_supply(input, target_gas),
 			Action::BalanceOf => Self::balance_of(input, target_gas),
 			Action::Allowance => Self::allowance(input, target_gas),
 			Action::Approve => Self::approve(input, target_gas, context),
 			Action::Transfer => Self::transfer(input, target_gas, context),
 			Action::TransferFrom => Self::transfer_from(input, target_gas, context),
+			Action::Permit => Self::permit(input, target_gas, context),
+			Action::Nonces => Self::nonces(input, target_gas),
+			Action::DomainSeparator => Self::domain_separator(input, target_gas, context),
 		}
 	}
 }
@@ -162,21 +325,25 @@ where
 impl<Runtime, Instance> Erc20BalancesPrecompile<Runtime, Instance>
 where
 	Instance: InstanceToPrefix + 'static,
-	Runtime: pallet_balances::Config<Instance> + pallet_evm::Config,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime: pallet_balances::Config<Instance> + pallet_evm::Config + pallet_timestamp::Config,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	Runtime::Call: From<pallet_balances::Call<Runtime, Instance>>,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
 {
 	fn total_supply(input: EvmDataReader, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
 		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 
 		// Parse input.
 		input.expect_arguments(0)?;
 
 		// Fetch info.
-		let amount: U256 = pallet_balances::Pallet::<Runtime, Instance>::total_issuance().into();
+		let amount: U256 =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| pallet_balances::Pallet::<Runtime, Instance>::total_issuance())
+			})?
+			.into();
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -192,7 +359,6 @@ where
 		target_gas: Option<u64>,
 	) -> EvmResult<PrecompileOutput> {
 		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 
 		// Read input.
 		input.expect_arguments(1)?;
@@ -202,7 +368,10 @@ where
 		// Fetch info.
 		let amount: U256 = {
 			let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
-			pallet_balances::Pallet::<Runtime, Instance>::usable_balance(&owner).into()
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| pallet_balances::Pallet::<Runtime, Instance>::usable_balance(&owner))
+			})?
+			.into()
 		};
 
 		// Build output.
@@ -216,7 +385,6 @@ where
 
 	fn allowance(mut input: EvmDataReader, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
 		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 
 		// Read input.
 		input.expect_arguments(2)?;
@@ -229,9 +397,11 @@ where
 			let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
 			let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
 
-			ApprovesStorage::<Runtime, Instance>::get(owner, spender)
-				.unwrap_or_default()
-				.into()
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| ApprovesStorage::<Runtime, Instance>::get(owner, spender))
+			})?
+			.unwrap_or_default()
+			.into()
 		};
 
 		// Build output.
@@ -402,6 +572,187 @@ where
 		})
 	}
 
+	fn permit(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+		gasometer.record_cost(PERMIT_ECRECOVER_GAS_COST)?;
+		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		gasometer.record_cost(2 * RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+		gasometer.record_log_costs_manual(3, 32)?;
+
+		// Parse input.
+		input.expect_arguments(7)?;
+
+		let owner: H160 = input.read::<Address>()?.into();
+		let spender: H160 = input.read::<Address>()?.into();
+		let amount: U256 = input.read()?;
+		let deadline: U256 = input.read()?;
+		let v: u8 = input.read()?;
+		let r: H256 = input.read()?;
+		let s: H256 = input.read()?;
+
+		// The deadline is in seconds, like the timestamp of Ethereum blocks.
+		let now: U256 = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| pallet_timestamp::Pallet::<Runtime>::get())
+		})?
+		.into();
+		if deadline < now / 1000 {
+			return Err(error("permit expired"));
+		}
+
+		{
+			let owner_account: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+			let spender_account: Runtime::AccountId =
+				Runtime::AddressMapping::into_account_id(spender);
+			let amount = Self::u256_to_amount(amount)?;
+
+			let nonce = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| NoncesStorage::<Runtime, Instance>::get(owner_account.clone()))
+			})?;
+			// The chain id of the domain separator is read from storage by the runtime.
+			let digest = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| {
+					Self::permit_digest(context.address, owner, spender, amount, nonce, deadline)
+				})
+			})?;
+
+			if Self::recover_signer(digest, v, r, s) != Some(owner) {
+				return Err(error("invalid permit"));
+			}
+
+			NoncesStorage::<Runtime, Instance>::insert(owner_account.clone(), nonce + U256::one());
+			ApprovesStorage::<Runtime, Instance>::insert(owner_account, spender_account, amount);
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Stopped,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: LogsBuilder::new(context.address)
+				.log3(
+					SELECTOR_LOG_APPROVAL,
+					owner,
+					spender,
+					EvmDataWriter::new().write(amount).build(),
+				)
+				.build(),
+		})
+	}
+
+	fn nonces(mut input: EvmDataReader, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Read input.
+		input.expect_arguments(1)?;
+
+		let owner: H160 = input.read::<Address>()?.into();
+
+		// Fetch info.
+		let nonce: U256 = {
+			let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| NoncesStorage::<Runtime, Instance>::get(owner))
+			})?
+		};
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: EvmDataWriter::new().write(nonce).build(),
+			logs: vec![],
+		})
+	}
+
+	fn domain_separator(
+		input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Parse input.
+		input.expect_arguments(0)?;
+
+		// The chain id is read from storage by the runtime.
+		let domain_separator =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| Self::compute_domain_separator(context.address))
+			})?;
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: EvmDataWriter::new().write(domain_separator).build(),
+			logs: vec![],
+		})
+	}
+
+	/// EIP-712 domain separator of the permits, built from the chain id and the address of the
+	/// precompile.
+	pub fn compute_domain_separator(address: H160) -> H256 {
+		let encoded = EvmDataWriter::new()
+			.write(H256::from(PERMIT_DOMAIN_TYPEHASH))
+			.write(U256::from(Runtime::ChainId::get()))
+			.write(Address(address))
+			.build();
+
+		keccak_256(&encoded).into()
+	}
+
+	/// EIP-712 digest signed by the owner to permit `spender` to spend `value`.
+	pub fn permit_digest(
+		address: H160,
+		owner: H160,
+		spender: H160,
+		value: BalanceOf<Runtime, Instance>,
+		nonce: U256,
+		deadline: U256,
+	) -> [u8; 32] {
+		let permit_hash = keccak_256(
+			&EvmDataWriter::new()
+				.write(H256::from(PERMIT_TYPEHASH))
+				.write(Address(owner))
+				.write(Address(spender))
+				.write::<U256>(value.into())
+				.write(nonce)
+				.write(deadline)
+				.build(),
+		);
+
+		let mut pre_digest = Vec::with_capacity(66);
+		pre_digest.extend_from_slice(b"\x19\x01");
+		pre_digest.extend_from_slice(Self::compute_domain_separator(address).as_bytes());
+		pre_digest.extend_from_slice(&permit_hash);
+
+		keccak_256(&pre_digest)
+	}
+
+	/// Address of the signer of `digest`, or `None` if the signature is invalid or malleable.
+	fn recover_signer(digest: [u8; 32], v: u8, r: H256, s: H256) -> Option<H160> {
+		if v != 27 && v != 28 {
+			return None;
+		}
+
+		if s > H256(SECP256K1N_HALF) {
+			return None;
+		}
+
+		let mut signature = [0u8; 65];
+		signature[0..32].copy_from_slice(r.as_bytes());
+		signature[32..64].copy_from_slice(s.as_bytes());
+		signature[64] = v - 27;
+
+		let public_key = sp_io::crypto::secp256k1_ecdsa_recover(&signature, &digest).ok()?;
+
+		Some(H160::from(H256::from(keccak_256(&public_key))))
+	}
+
 	fn u256_to_amount(value: U256) -> EvmResult<BalanceOf<Runtime, Instance>> {
 		value
 			.try_into()
@@ -163,6 +163,10 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const ChainId: u64 = 1281;
+}
+
 impl pallet_evm::Config for Runtime {
 	type FeeCalculator = ();
 	type GasWeightMapping = ();
@@ -173,7 +177,7 @@ impl pallet_evm::Config for Runtime {
 	type Event = Event;
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type Precompiles = Precompiles<Self>;
-	type ChainId = ();
+	type ChainId = ChainId;
 	type OnChargeTransaction = ();
 	type BlockGasLimit = ();
 	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
@@ -190,6 +194,7 @@ construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 	}
 );
 
@@ -199,11 +204,13 @@ impl<R> PrecompileSet for Precompiles<R>
 where
 	R: pallet_balances::Config,
 	R: pallet_evm::Config,
+	R: pallet_timestamp::Config,
 	R::AccountId: From<H160>,
-	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	R::Call: From<pallet_balances::Call<R>>,
 	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
 	BalanceOf<R>: TryFrom<U256> + Into<U256>,
+	<R as pallet_timestamp::Config>::Moment: Into<U256>,
 {
 	fn execute(
 		address: H160,
@@ -224,39 +231,7 @@ fn hash(a: u64) -> H160 {
 	H160::from_low_u64_be(a)
 }
 
-pub(crate) struct ExtBuilder {
-	// endowed accounts with balances
-	balances: Vec<(AccountId, Balance)>,
-}
-
-impl Default for ExtBuilder {
-	fn default() -> ExtBuilder {
-		ExtBuilder { balances: vec![] }
-	}
-}
-
-impl ExtBuilder {
-	pub(crate) fn with_balances(mut self, balances: Vec<(AccountId, Balance)>) -> Self {
-		self.balances = balances;
-		self
-	}
-
-	pub(crate) fn build(self) -> sp_io::TestExternalities {
-		let mut t = frame_system::GenesisConfig::default()
-			.build_storage::<Runtime>()
-			.expect("Frame system builds valid default genesis config");
-
-		pallet_balances::GenesisConfig::<Runtime> {
-			balances: self.balances,
-		}
-		.assimilate_storage(&mut t)
-		.expect("Pallet balances storage can be assimilated");
-
-		let mut ext = sp_io::TestExternalities::new(t);
-		ext.execute_with(|| System::set_block_number(1));
-		ext
-	}
-}
+pub(crate) type ExtBuilder = precompile_utils::testing::ExtBuilder<Runtime>;
 
 pub(crate) fn roll_to(n: u64) {
 	while System::block_number() < n {
@@ -14,55 +14,35 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::assert_matches::assert_matches;
-
 use crate::mock::*;
 use crate::*;
 
 use pallet_evm::PrecompileSet;
-use precompile_utils::{error, EvmDataWriter, LogsBuilder};
+use precompile_utils::{error, testing::PrecompileTesterExt, EvmDataWriter, LogsBuilder};
 use sha3::{Digest, Keccak256};
 
 #[test]
 fn selector_less_than_four_bytes() {
 	ExtBuilder::default().build().execute_with(|| {
 		// This selector is only three bytes long when four are required.
-		let bogus_selector = vec![1u8, 2u8, 3u8];
-
-		assert_eq!(
-			Precompiles::<Runtime>::execute(
-				Account::Precompile.into(),
-				&bogus_selector,
-				None,
-				&evm::Context {
-					address: Account::Precompile.into(),
-					caller: Account::Alice.into(),
-					apparent_value: From::from(0),
-				},
-			),
-			Some(Err(error("tried to parse selector out of bounds")))
-		);
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			vec![1u8, 2u8, 3u8],
+		)
+		.execute_error(error("tried to parse selector out of bounds"));
 	});
 }
 
 #[test]
 fn no_selector_exists_but_length_is_right() {
 	ExtBuilder::default().build().execute_with(|| {
-		let bogus_selector = vec![1u8, 2u8, 3u8, 4u8];
-
-		assert_eq!(
-			Precompiles::<Runtime>::execute(
-				Account::Precompile.into(),
-				&bogus_selector,
-				None,
-				&evm::Context {
-					address: Account::Precompile.into(),
-					caller: Account::Alice.into(),
-					apparent_value: From::from(0),
-				},
-			),
-			Some(Err(error("unknown selector")))
-		);
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			vec![1u8, 2u8, 3u8, 4u8],
+		)
+		.execute_error(error("unknown selector"));
 	});
 }
 
@@ -92,26 +72,14 @@ fn get_total_supply() {
 		.with_balances(vec![(Account::Alice, 1000), (Account::Bob, 2500)])
 		.build()
 		.execute_with(|| {
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::TotalSupply)
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(U256::from(3500u64)).build(),
-					cost: Default::default(),
-					logs: Default::default(),
-				}))
-			);
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::TotalSupply).build(),
+			)
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns_encoded(U256::from(3500u64));
 		});
 }
 
@@ -121,27 +89,16 @@ fn get_balances_known_user() {
 		.with_balances(vec![(Account::Alice, 1000)])
 		.build()
 		.execute_with(|| {
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::BalanceOf)
-						.write(Address(Account::Alice.into()))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(U256::from(1000u64)).build(),
-					cost: Default::default(),
-					logs: Default::default(),
-				}))
-			);
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::BalanceOf)
+					.write(Address(Account::Alice.into()))
+					.build(),
+			)
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns_encoded(U256::from(1000u64));
 		});
 }
 
@@ -151,27 +108,16 @@ fn get_balances_unknown_user() {
 		.with_balances(vec![(Account::Alice, 1000)])
 		.build()
 		.execute_with(|| {
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::BalanceOf)
-						.write(Address(Account::Bob.into()))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(U256::from(0u64)).build(),
-					cost: Default::default(),
-					logs: Default::default(),
-				}))
-			);
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::BalanceOf)
+					.write(Address(Account::Bob.into()))
+					.build(),
+			)
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns_encoded(U256::from(0u64));
 		});
 }
 
@@ -181,35 +127,26 @@ fn approve() {
 		.with_balances(vec![(Account::Alice, 1000)])
 		.build()
 		.execute_with(|| {
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::Approve)
-						.write(Address(Account::Bob.into()))
-						.write(U256::from(500))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(true).build(),
-					cost: 1756u64,
-					logs: LogsBuilder::new(Account::Precompile.into())
-						.log3(
-							SELECTOR_LOG_APPROVAL,
-							Account::Alice,
-							Account::Bob,
-							EvmDataWriter::new().write(U256::from(500)).build(),
-						)
-						.build(),
-				}))
-			);
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Approve)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(500))
+					.build(),
+			)
+			.expect_cost(1756)
+			.expect_log(
+				LogsBuilder::new(Account::Precompile.into())
+					.log3(
+						SELECTOR_LOG_APPROVAL,
+						Account::Alice,
+						Account::Bob,
+						EvmDataWriter::new().write(U256::from(500)).build(),
+					)
+					.build(),
+			)
+			.execute_returns_encoded(true);
 		});
 }
 
@@ -265,28 +202,17 @@ fn check_allowance_not_existing() {
 		.with_balances(vec![(Account::Alice, 1000)])
 		.build()
 		.execute_with(|| {
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::Allowance)
-						.write(Address(Account::Alice.into()))
-						.write(Address(Account::Bob.into()))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(U256::from(0u64)).build(),
-					cost: 0u64,
-					logs: Default::default(),
-				}))
-			);
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Allowance)
+					.write(Address(Account::Alice.into()))
+					.write(Address(Account::Bob.into()))
+					.build(),
+			)
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns_encoded(U256::from(0u64));
 		});
 }
 
@@ -296,110 +222,153 @@ fn transfer() {
 		.with_balances(vec![(Account::Alice, 1000)])
 		.build()
 		.execute_with(|| {
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::Transfer)
-						.write(Address(Account::Bob.into()))
-						.write(U256::from(400))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(true).build(),
-					cost: 197230756u64, // 1 weight => 1 gas in mock
-					logs: LogsBuilder::new(Account::Precompile.into())
-						.log3(
-							SELECTOR_LOG_TRANSFER,
-							Account::Alice,
-							Account::Bob,
-							EvmDataWriter::new().write(U256::from(400)).build(),
-						)
-						.build(),
-				}))
-			);
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Transfer)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(400))
+					.build(),
+			)
+			.expect_cost(197230756) // 1 weight => 1 gas in mock
+			.expect_log(
+				LogsBuilder::new(Account::Precompile.into())
+					.log3(
+						SELECTOR_LOG_TRANSFER,
+						Account::Alice,
+						Account::Bob,
+						EvmDataWriter::new().write(U256::from(400)).build(),
+					)
+					.build(),
+			)
+			.execute_returns_encoded(true);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::BalanceOf)
+					.write(Address(Account::Alice.into()))
+					.build(),
+			)
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns_encoded(U256::from(600));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::BalanceOf)
+					.write(Address(Account::Bob.into()))
+					.build(),
+			)
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns_encoded(U256::from(400));
+		});
+}
 
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::BalanceOf)
-						.write(Address(Account::Alice.into()))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(U256::from(600)).build(),
-					cost: Default::default(),
-					logs: Default::default(),
-				}))
-			);
+#[test]
+fn transfer_not_enough_funds() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Transfer)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(1400))
+					.build(),
+			)
+			.execute_reverts(|reason| {
+				reason == "Dispatched call Balances::transfer failed: InsufficientBalance"
+			});
+		});
+}
 
-			assert_eq!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::BalanceOf)
-						.write(Address(Account::Bob.into()))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Returned,
-					output: EvmDataWriter::new().write(U256::from(400)).build(),
-					cost: Default::default(),
-					logs: Default::default(),
-				}))
-			);
+#[test]
+fn transfer_is_not_payable() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Transfer)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(400))
+					.build(),
+			)
+			.with_value(1)
+			.execute_reverts_with_output(EvmDataWriter::revert_reason("function is not payable"));
 		});
 }
 
 #[test]
-fn transfer_not_enough_funds() {
+fn balance_of_is_not_payable() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::BalanceOf)
+				.write(Address(Account::Alice.into()))
+				.build(),
+		)
+		.with_value(1)
+		.execute_reverts_with_output(EvmDataWriter::revert_reason("function is not payable"));
+	});
+}
+
+#[test]
+fn transfer_and_approve_fail_in_static_context() {
 	ExtBuilder::default()
 		.with_balances(vec![(Account::Alice, 1000)])
 		.build()
 		.execute_with(|| {
-			assert_matches!(
-				Precompiles::<Runtime>::execute(
-					Account::Precompile.into(),
-					&EvmDataWriter::new()
-						.write_selector(Action::Transfer)
-						.write(Address(Account::Bob.into()))
-						.write(U256::from(1400))
-						.build(),
-					None,
-					&evm::Context {
-						address: Account::Precompile.into(),
-						caller: Account::Alice.into(),
-						apparent_value: From::from(0),
-					},
-				),
-				Some(Err(ExitError::Other(str)))
-					if str.contains("Dispatched call failed with error: DispatchErrorWithPostInfo")
-					&& str.contains("InsufficientBalance")
+			let context = Context {
+				address: Account::Precompile.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			for action in vec![Action::Transfer, Action::Approve] {
+				let input = EvmDataWriter::new_with_selector(action)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(400))
+					.build();
+
+				assert_eq!(
+					Erc20BalancesPrecompile::<Runtime>::execute_with_static(
+						&input, None, &context, true
+					)
+					.map(|_| ()),
+					Err(error("can't call non-static function in static context"))
+				);
+			}
+
+			let input = EvmDataWriter::new_with_selector(Action::BalanceOf)
+				.write(Address(Account::Alice.into()))
+				.build();
+			let output = Erc20BalancesPrecompile::<Runtime>::execute_with_static(
+				&input, None, &context, true,
+			)
+			.expect("view functions can be called in a static context");
+			assert_eq!(
+				output.output,
+				EvmDataWriter::new().write(U256::from(1000)).build()
 			);
 		});
 }
 
+#[test]
+fn no_precompile_at_other_addresses() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Bob, vec![]).execute_none();
+	});
+}
+
 #[test]
 fn transfer_from() {
 	ExtBuilder::default()
@@ -646,3 +615,294 @@ fn transfer_from_self() {
 			);
 		});
 }
+
+const PERMIT_OWNER_SECRET_KEY: [u8; 32] = [0x42; 32];
+
+/// Address of the owner of the permits, which is an `Account::Bogus` in the mock.
+fn permit_owner() -> H160 {
+	let secret_key = libsecp256k1::SecretKey::parse(&PERMIT_OWNER_SECRET_KEY).unwrap();
+	let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key).serialize();
+	H160::from_slice(&Keccak256::digest(&public_key[1..])[12..])
+}
+
+/// Sign the permit digest with `secret_key`, returning `(v, r, s)`.
+fn sign_permit(
+	secret_key: [u8; 32],
+	spender: H160,
+	value: u128,
+	nonce: U256,
+	deadline: U256,
+) -> (u8, H256, H256) {
+	let digest = Erc20BalancesPrecompile::<Runtime>::permit_digest(
+		Account::Precompile.into(),
+		permit_owner(),
+		spender,
+		value,
+		nonce,
+		deadline,
+	);
+
+	let (signature, recovery_id) = libsecp256k1::sign(
+		&libsecp256k1::Message::parse(&digest),
+		&libsecp256k1::SecretKey::parse(&secret_key).unwrap(),
+	);
+	let signature = signature.serialize();
+
+	(
+		recovery_id.serialize() + 27,
+		H256::from_slice(&signature[0..32]),
+		H256::from_slice(&signature[32..64]),
+	)
+}
+
+fn permit_input(
+	spender: H160,
+	value: u128,
+	deadline: U256,
+	(v, r, s): (u8, H256, H256),
+) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::Permit)
+		.write(Address(permit_owner()))
+		.write(Address(spender))
+		.write(U256::from(value))
+		.write(deadline)
+		.write(v)
+		.write(r)
+		.write(s)
+		.build()
+}
+
+#[test]
+fn permit_selectors() {
+	assert_eq!(Action::Permit as u32, 0xd505accf);
+	assert_eq!(Action::Nonces as u32, 0x7ecebe00);
+	assert_eq!(Action::DomainSeparator as u32, 0x3644e515);
+
+	assert_eq!(
+		crate::PERMIT_DOMAIN_TYPEHASH,
+		&Keccak256::digest(b"EIP712Domain(uint256 chainId,address verifyingContract)")[..]
+	);
+
+	assert_eq!(
+		crate::PERMIT_TYPEHASH,
+		&Keccak256::digest(
+			b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+		)[..]
+	);
+}
+
+#[test]
+fn domain_separator() {
+	ExtBuilder::default().build().execute_with(|| {
+		// keccak256(abi.encode(PERMIT_DOMAIN_TYPEHASH, chainId, verifyingContract))
+		let mut encoded = Vec::from(crate::PERMIT_DOMAIN_TYPEHASH);
+		encoded.extend_from_slice(H256::from_low_u64_be(1281).as_bytes());
+		encoded.extend_from_slice(H256::from(Account::Precompile).as_bytes());
+		let expected = H256::from_slice(&Keccak256::digest(&encoded)[..]);
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::DomainSeparator).build(),
+		)
+		.expect_cost(0)
+		.expect_no_logs()
+		.execute_returns_encoded(expected);
+	});
+}
+
+#[test]
+fn permit_valid() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Bogus, 1000)])
+		.build()
+		.execute_with(|| {
+			let signature = sign_permit(
+				PERMIT_OWNER_SECRET_KEY,
+				Account::Bob.into(),
+				500,
+				U256::zero(),
+				U256::MAX,
+			);
+
+			// Anyone can submit the permit.
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, U256::MAX, signature),
+			)
+			.expect_log(
+				LogsBuilder::new(Account::Precompile.into())
+					.log3(
+						SELECTOR_LOG_APPROVAL,
+						permit_owner(),
+						Account::Bob,
+						EvmDataWriter::new().write(U256::from(500)).build(),
+					)
+					.build(),
+			)
+			.execute_stops();
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Allowance)
+					.write(Address(permit_owner()))
+					.write(Address(Account::Bob.into()))
+					.build(),
+			)
+			.execute_returns_encoded(U256::from(500));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Nonces)
+					.write(Address(permit_owner()))
+					.build(),
+			)
+			.execute_returns_encoded(U256::one());
+		});
+}
+
+#[test]
+fn permit_cannot_be_replayed() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Bogus, 1000)])
+		.build()
+		.execute_with(|| {
+			let signature = sign_permit(
+				PERMIT_OWNER_SECRET_KEY,
+				Account::Bob.into(),
+				500,
+				U256::zero(),
+				U256::MAX,
+			);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, U256::MAX, signature),
+			)
+			.execute_stops();
+
+			// The nonce of the owner has been incremented.
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, U256::MAX, signature),
+			)
+			.execute_reverts(|output| output == "invalid permit");
+		});
+}
+
+#[test]
+fn permit_invalid_signature() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Bogus, 1000)])
+		.build()
+		.execute_with(|| {
+			// Signed by another key than the one of the owner.
+			let signature = sign_permit(
+				[0x43; 32],
+				Account::Bob.into(),
+				500,
+				U256::zero(),
+				U256::MAX,
+			);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, U256::MAX, signature),
+			)
+			.execute_reverts(|output| output == "invalid permit");
+
+			// Signed for another value.
+			let signature = sign_permit(
+				PERMIT_OWNER_SECRET_KEY,
+				Account::Bob.into(),
+				500,
+				U256::zero(),
+				U256::MAX,
+			);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 1000, U256::MAX, signature),
+			)
+			.execute_reverts(|output| output == "invalid permit");
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Nonces)
+					.write(Address(permit_owner()))
+					.build(),
+			)
+			.execute_returns_encoded(U256::zero());
+		});
+}
+
+#[test]
+fn permit_rejects_high_s_signature() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Bogus, 1000)])
+		.build()
+		.execute_with(|| {
+			let (v, r, s) = sign_permit(
+				PERMIT_OWNER_SECRET_KEY,
+				Account::Bob.into(),
+				500,
+				U256::zero(),
+				U256::MAX,
+			);
+
+			// `(r, n - s)` with the other recovery id is also a signature of the owner.
+			let n = U256::from_big_endian(&crate::SECP256K1N_HALF) * 2 + 1;
+			let mut high_s = [0u8; 32];
+			(n - U256::from_big_endian(s.as_bytes())).to_big_endian(&mut high_s);
+			let signature = (55 - v, r, H256(high_s));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, U256::MAX, signature),
+			)
+			.execute_reverts(|output| output == "invalid permit");
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, U256::MAX, (v, r, s)),
+			)
+			.execute_stops();
+		});
+}
+
+#[test]
+fn permit_expired() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Bogus, 1000)])
+		.build()
+		.execute_with(|| {
+			// The timestamp is in milliseconds while the deadline is in seconds.
+			pallet_timestamp::Pallet::<Runtime>::set_timestamp(10_000_000);
+			let deadline = U256::from(9_999);
+
+			let signature = sign_permit(
+				PERMIT_OWNER_SECRET_KEY,
+				Account::Bob.into(),
+				500,
+				U256::zero(),
+				deadline,
+			);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				permit_input(Account::Bob.into(), 500, deadline, signature),
+			)
+			.execute_reverts(|output| output == "permit expired");
+		});
+}
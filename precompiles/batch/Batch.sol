@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Perform multiple calls in a single transaction, on behalf of the caller.
+/// Each subcall `i` is a call to `to[i]` with `value[i]`, `call_data[i]` and `gas_limit[i]`,
+/// missing values defaulting to 0, empty call data and all the remaining gas.
+/// Emits `SubcallSucceeded(uint256 index)` or `SubcallFailed(uint256 index)` for each subcall.
+/// This file is generated from the Rust implementation of the precompile.
+interface Batch {
+    /// Perform the subcalls, ignoring the ones that fail.
+    /// Selector: 79df4b9c
+    function batchSome(address[] memory to, uint256[] memory value, bytes[] memory call_data, uint64[] memory gas_limit) external;
+
+    /// Perform the subcalls until one of them fails, keeping the effects of the previous ones.
+    /// Selector: cf0491c7
+    function batchSomeUntilFailure(address[] memory to, uint256[] memory value, bytes[] memory call_data, uint64[] memory gas_limit) external;
+
+    /// Perform the subcalls, failing and reverting all their effects if one of them fails.
+    /// Selector: 96e292b8
+    function batchAll(address[] memory to, uint256[] memory value, bytes[] memory call_data, uint64[] memory gas_limit) external;
+}
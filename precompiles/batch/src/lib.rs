@@ -0,0 +1,233 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to perform multiple calls in a single transaction, on behalf of the caller.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use evm::ExitReason;
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use pallet_evm::{AddressMapping, Runner};
+use precompile_utils::{
+	error, keccak256, precompile, Address, Bytes, EvmDataWriter, EvmResult, LogsBuilder,
+	PrecompileHandle,
+};
+use sp_core::{H160, U256};
+use sp_std::{convert::TryInto, marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Solidity selector of the SubcallSucceeded log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SUBCALL_SUCCEEDED: [u8; 32] = keccak256!("SubcallSucceeded(uint256)");
+
+/// Solidity selector of the SubcallFailed log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SUBCALL_FAILED: [u8; 32] = keccak256!("SubcallFailed(uint256)");
+
+/// Behavior of a batch when one of its subcalls fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+	/// Continue with the next subcalls.
+	BatchSome,
+	/// Stop without executing the next subcalls, keeping the effects of the previous ones.
+	BatchSomeUntilFailure,
+	/// Fail, reverting the effects of the previous subcalls.
+	BatchAll,
+}
+
+/// A precompile to perform multiple calls in a single transaction.
+/// It must be installed with `precompile_utils::check_not_delegated`, as subcalls are made on
+/// behalf of the caller.
+///
+/// Precompiles cannot perform nested calls in the EVM version currently used, so each subcall is
+/// executed as a new call of the runner of `pallet_evm`, whose effects are written to the storage
+/// as soon as it succeeds. `batchAll` executes its subcalls in a storage transaction, which is
+/// rolled back when one of them fails. Each subcall being executed like a transaction, its origin
+/// (`tx.origin`) is the caller of the batch, even if it is a contract.
+pub struct BatchPrecompile<Runtime>(PhantomData<Runtime>);
+
+/// Perform multiple calls in a single transaction, on behalf of the caller.
+/// Each subcall `i` is a call to `to[i]` with `value[i]`, `call_data[i]` and `gas_limit[i]`,
+/// missing values defaulting to 0, empty call data and all the remaining gas.
+/// Emits `SubcallSucceeded(uint256 index)` or `SubcallFailed(uint256 index)` for each subcall.
+#[precompile(interface = "Batch")]
+impl<Runtime> BatchPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	/// Perform the subcalls, ignoring the ones that fail.
+	#[precompile::public("batchSome(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some(
+		handle: &mut PrecompileHandle,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(handle, Mode::BatchSome, to, value, call_data, gas_limit)
+	}
+
+	/// Perform the subcalls until one of them fails, keeping the effects of the previous ones.
+	#[precompile::public("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some_until_failure(
+		handle: &mut PrecompileHandle,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(
+			handle,
+			Mode::BatchSomeUntilFailure,
+			to,
+			value,
+			call_data,
+			gas_limit,
+		)
+	}
+
+	/// Perform the subcalls, failing and reverting all their effects if one of them fails.
+	#[precompile::public("batchAll(address[],uint256[],bytes[],uint64[])")]
+	fn batch_all(
+		handle: &mut PrecompileHandle,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		with_transaction(|| {
+			match Self::batch(handle, Mode::BatchAll, to, value, call_data, gas_limit) {
+				Ok(()) => TransactionOutcome::Commit(Ok(())),
+				Err(e) => TransactionOutcome::Rollback(Err(e)),
+			}
+		})
+	}
+
+	fn batch(
+		handle: &mut PrecompileHandle,
+		mode: Mode,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		if value.len() > to.len() {
+			return Err(error("value array is too long"));
+		}
+		if call_data.len() > to.len() {
+			return Err(error("call data array is too long"));
+		}
+		if gas_limit.len() > to.len() {
+			return Err(error("gas limit array is too long"));
+		}
+
+		let caller = handle.context.caller;
+		let batch_address = handle.context.address;
+
+		for (i, Address(to)) in to.into_iter().enumerate() {
+			if to == batch_address {
+				return Err(error("batch precompile cannot call itself"));
+			}
+
+			let value = value.get(i).copied().unwrap_or_else(U256::zero);
+			let call_data = call_data.get(i).cloned().unwrap_or_default();
+
+			let remaining_gas = handle.gasometer.remaining_gas()?;
+			let gas_limit = match (gas_limit.get(i).copied().unwrap_or(0), remaining_gas) {
+				(0, remaining) => remaining.unwrap_or(u64::MAX),
+				(limit, Some(remaining)) if limit > remaining => {
+					return Err(error(alloc::format!("not enough gas for subcall {}", i)))
+				}
+				(limit, _) => limit,
+			};
+
+			let succeeded = Self::subcall(handle, caller, to, value, call_data, gas_limit)?;
+
+			let selector = if succeeded {
+				SELECTOR_LOG_SUBCALL_SUCCEEDED
+			} else {
+				SELECTOR_LOG_SUBCALL_FAILED
+			};
+			let logs = LogsBuilder::new(batch_address)
+				.log1(selector, EvmDataWriter::new().write(U256::from(i)).build())
+				.build();
+			handle.record_logs(logs)?;
+
+			if !succeeded {
+				match mode {
+					Mode::BatchSome => (),
+					Mode::BatchSomeUntilFailure => break,
+					Mode::BatchAll => return Err(error(alloc::format!("subcall {} failed", i))),
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Perform a subcall on behalf of `caller`, charging its cost and forwarding its logs.
+	/// Returns whether the subcall succeeded. If it failed its effects are reverted.
+	fn subcall(
+		handle: &mut PrecompileHandle,
+		caller: H160,
+		to: H160,
+		value: U256,
+		call_data: Bytes,
+		gas_limit: u64,
+	) -> EvmResult<bool> {
+		// The subcall is executed as a transaction, which increments the nonce of the caller.
+		// It is restored as the caller didn't send another transaction.
+		let caller_account = Runtime::AddressMapping::into_account_id(caller);
+		let nonce = frame_system::Pallet::<Runtime>::account_nonce(&caller_account);
+
+		let result = Runtime::Runner::call(
+			caller,
+			to,
+			call_data.into(),
+			value,
+			gas_limit,
+			None,
+			None,
+			Runtime::config(),
+		);
+
+		frame_system::Account::<Runtime>::mutate(&caller_account, |account| account.nonce = nonce);
+
+		let info = match result {
+			Ok(info) => info,
+			// The subcall could not be executed (for example the caller cannot pay the value).
+			Err(_) => return Ok(false),
+		};
+
+		let used_gas: u64 = info
+			.used_gas
+			.try_into()
+			.map_err(|_| error("used gas overflow"))?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		match info.exit_reason {
+			ExitReason::Succeed(_) => {
+				handle.add_logs(info.logs);
+				Ok(true)
+			}
+			_ => Ok(false),
+		}
+	}
+}
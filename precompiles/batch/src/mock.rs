@@ -0,0 +1,56 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities.
+
+use super::*;
+
+use frame_support::construct_runtime;
+
+pub use precompile_utils::testing::{Account, TestPrecompiles, PRECOMPILE_ADDRESS};
+
+pub type AccountId = Account;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles<Runtime>);
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+pub type Precompiles<R> = TestPrecompiles<BatchPrecompile<R>>;
+
+pub(crate) type ExtBuilder = precompile_utils::testing::ExtBuilder<Runtime>;
+
+/// Code of a contract which always reverts (`PUSH1 0 PUSH1 0 REVERT`).
+pub(crate) const REVERT_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+/// Code of a contract which stores 1 at its storage slot 0, then reverts
+/// (`PUSH1 1 PUSH1 0 SSTORE PUSH1 0 PUSH1 0 REVERT`).
+pub(crate) const STORE_AND_REVERT_CODE: [u8; 10] =
+	[0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0xfd];
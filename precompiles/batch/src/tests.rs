@@ -0,0 +1,385 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::Context;
+use pallet_evm::PrecompileSet;
+use precompile_utils::{
+	error,
+	testing::{check_solidity_interface, PrecompileTesterExt},
+	EvmDataWriter,
+};
+use sp_core::H256;
+
+fn batch_input(action: Action, subcalls: Vec<(Account, u128, Vec<u8>)>) -> Vec<u8> {
+	let to: Vec<_> = subcalls
+		.iter()
+		.map(|(to, _, _)| Address(to.clone().into()))
+		.collect();
+	let value: Vec<_> = subcalls
+		.iter()
+		.map(|(_, value, _)| U256::from(*value))
+		.collect();
+	let call_data: Vec<_> = subcalls
+		.into_iter()
+		.map(|(_, _, call_data)| Bytes(call_data))
+		.collect();
+
+	EvmDataWriter::new_with_selector(action as u32)
+		.write(to)
+		.write(value)
+		.write(call_data)
+		.write(Vec::<u64>::new())
+		.build()
+}
+
+fn subcall_log(selector: [u8; 32], index: usize) -> Vec<pallet_evm::Log> {
+	LogsBuilder::new(Account::Precompile.into())
+		.log1(
+			selector,
+			EvmDataWriter::new().write(U256::from(index)).build(),
+		)
+		.build()
+}
+
+fn install_reverting_contract(account: Account) {
+	pallet_evm::AccountCodes::<Runtime>::insert(H160::from(account), REVERT_CODE.to_vec());
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::BatchSome as u32, 0x79df4b9c);
+	assert_eq!(Action::BatchSomeUntilFailure as u32, 0xcf0491c7);
+	assert_eq!(Action::BatchAll as u32, 0x96e292b8);
+
+	assert_eq!(
+		SELECTOR_LOG_SUBCALL_SUCCEEDED,
+		sp_core::hashing::keccak_256(b"SubcallSucceeded(uint256)")
+	);
+	assert_eq!(
+		SELECTOR_LOG_SUBCALL_FAILED,
+		sp_core::hashing::keccak_256(b"SubcallFailed(uint256)")
+	);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "Batch.sol");
+}
+
+#[test]
+fn batch_some_transfers() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchSome,
+					vec![(Account::Bob, 100, vec![]), (Account::Charlie, 200, vec![])],
+				),
+			)
+			.expect_cost(2 * (21_000 + 375 + 375 + 8 * 32))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 1))
+			.execute_stops();
+
+			assert_eq!(Balances::free_balance(&Account::Alice), 700);
+			assert_eq!(Balances::free_balance(&Account::Bob), 100);
+			assert_eq!(Balances::free_balance(&Account::Charlie), 200);
+
+			// Subcalls don't increment the nonce of the caller.
+			assert_eq!(System::account_nonce(&Account::Alice), 0);
+		});
+}
+
+#[test]
+fn batch_some_continues_after_failure() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			install_reverting_contract(Account::Charlie);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchSome,
+					vec![(Account::Charlie, 200, vec![]), (Account::Bob, 100, vec![])],
+				),
+			)
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 0))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 1))
+			.execute_stops();
+
+			assert_eq!(Balances::free_balance(&Account::Alice), 900);
+			assert_eq!(Balances::free_balance(&Account::Bob), 100);
+			assert_eq!(Balances::free_balance(&Account::Charlie), 0);
+		});
+}
+
+#[test]
+fn batch_some_until_failure_stops_at_failure() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			install_reverting_contract(Account::Charlie);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchSomeUntilFailure,
+					vec![
+						(Account::Bob, 100, vec![]),
+						(Account::Charlie, 200, vec![]),
+						(Account::Bob, 100, vec![]),
+					],
+				),
+			)
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 1))
+			.execute_stops();
+
+			assert_eq!(Balances::free_balance(&Account::Alice), 900);
+			assert_eq!(Balances::free_balance(&Account::Bob), 100);
+		});
+}
+
+#[test]
+fn batch_some_fails_subcall_without_funds() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchSome,
+					vec![(Account::Bob, 2000, vec![]), (Account::Bob, 100, vec![])],
+				),
+			)
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 0))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 1))
+			.execute_stops();
+
+			assert_eq!(Balances::free_balance(&Account::Bob), 100);
+		});
+}
+
+#[test]
+fn failed_subcall_effects_do_not_persist() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// Writes 1 to its storage slot 0, then reverts.
+			pallet_evm::AccountCodes::<Runtime>::insert(
+				H160::from(Account::Charlie),
+				STORE_AND_REVERT_CODE.to_vec(),
+			);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchSome,
+					vec![(Account::Bob, 100, vec![]), (Account::Charlie, 200, vec![])],
+				),
+			)
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 1))
+			.execute_stops();
+
+			assert_eq!(Balances::free_balance(&Account::Alice), 900);
+			assert_eq!(Balances::free_balance(&Account::Bob), 100);
+			assert_eq!(Balances::free_balance(&Account::Charlie), 0);
+			assert_eq!(
+				pallet_evm::AccountStorages::<Runtime>::get(
+					H160::from(Account::Charlie),
+					H256::zero()
+				),
+				H256::zero()
+			);
+			assert_eq!(System::account_nonce(&Account::Alice), 0);
+		});
+}
+
+#[test]
+fn batch_all_transfers() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchAll,
+					vec![(Account::Bob, 100, vec![]), (Account::Charlie, 200, vec![])],
+				),
+			)
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0))
+			.expect_log(subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 1))
+			.execute_stops();
+
+			assert_eq!(Balances::free_balance(&Account::Alice), 700);
+			assert_eq!(Balances::free_balance(&Account::Bob), 100);
+			assert_eq!(Balances::free_balance(&Account::Charlie), 200);
+		});
+}
+
+#[test]
+fn batch_all_reverts_previous_subcalls_on_failure() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			install_reverting_contract(Account::Charlie);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(
+					Action::BatchAll,
+					vec![
+						(Account::Bob, 100, vec![]),
+						(Account::Charlie, 200, vec![]),
+						(Account::Bob, 100, vec![]),
+					],
+				),
+			)
+			.execute_reverts(|reason| reason == "subcall 1 failed");
+
+			// The transfer of the first subcall is rolled back.
+			assert_eq!(Balances::free_balance(&Account::Alice), 1000);
+			assert_eq!(Balances::free_balance(&Account::Bob), 0);
+			assert_eq!(System::account_nonce(&Account::Alice), 0);
+		});
+}
+
+#[test]
+fn batch_rejects_too_long_arrays() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::BatchSome as u32)
+				.write(vec![Address(Account::Bob.into())])
+				.write(vec![U256::zero(), U256::zero()])
+				.write(Vec::<Bytes>::new())
+				.write(Vec::<u64>::new())
+				.build(),
+		)
+		.execute_reverts(|reason| reason == "value array is too long");
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::BatchSome as u32)
+				.write(vec![Address(Account::Bob.into())])
+				.write(Vec::<U256>::new())
+				.write(Vec::<Bytes>::new())
+				.write(vec![0u64, 0u64])
+				.build(),
+		)
+		.execute_reverts(|reason| reason == "gas limit array is too long");
+	});
+}
+
+#[test]
+fn batch_rejects_not_enough_gas() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::BatchSome as u32)
+					.write(vec![Address(Account::Bob.into())])
+					.write(Vec::<U256>::new())
+					.write(Vec::<Bytes>::new())
+					.write(vec![100_000u64])
+					.build(),
+			)
+			.with_target_gas(Some(50_000))
+			.execute_reverts(|reason| reason == "not enough gas for subcall 0");
+		});
+}
+
+#[test]
+fn batch_cannot_call_itself() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			batch_input(Action::BatchSome, vec![(Account::Precompile, 0, vec![])]),
+		)
+		.execute_reverts(|reason| reason == "batch precompile cannot call itself");
+	});
+}
+
+#[test]
+fn batch_is_not_payable() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				batch_input(Action::BatchSome, vec![(Account::Bob, 100, vec![])]),
+			)
+			.with_value(1)
+			.execute_reverts(|reason| reason == "function is not payable");
+		});
+}
+
+#[test]
+fn batch_cannot_be_delegated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context is the one of the calling contract (Charlie), while
+			// the caller is the account which called it (Alice).
+			let context = Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&batch_input(Action::BatchSome, vec![(Account::Bob, 100, vec![])]),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert_eq!(Balances::free_balance(&Account::Bob), 0);
+		});
+}
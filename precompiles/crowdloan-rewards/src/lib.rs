@@ -21,11 +21,12 @@
 use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
-	traits::Currency,
+	traits::{Currency, GetCallMetadata},
 };
 use pallet_evm::{AddressMapping, Precompile};
 use precompile_utils::{
-	error, Address, EvmDataReader, EvmDataWriter, EvmResult, Gasometer, RuntimeHelper,
+	check_function_modifier, error, Address, EvmDataReader, EvmDataWriter, EvmResult, Gasometer,
+	RuntimeHelper,
 };
 
 use sp_core::{H160, U256};
@@ -48,7 +49,9 @@ pub type BalanceOf<Runtime> =
 #[precompile_utils::generate_function_selector]
 #[derive(Debug, PartialEq, num_enum::TryFromPrimitive)]
 enum Action {
+	#[view]
 	IsContributor = "is_contributor(address)",
+	#[view]
 	RewardInfo = "reward_info(address)",
 	Claim = "claim()",
 	UpdateRewardAddress = "update_reward_address(address)",
@@ -61,7 +64,7 @@ impl<Runtime> Precompile for CrowdloanRewardsWrapper<Runtime>
 where
 	Runtime: pallet_crowdloan_rewards::Config + pallet_evm::Config,
 	BalanceOf<Runtime>: TryFrom<U256> + Debug,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<pallet_crowdloan_rewards::Call<Runtime>>,
 {
@@ -69,10 +72,34 @@ where
 		input: &[u8], //Reminder this is big-endian
 		target_gas: Option<u64>,
 		context: &Context,
+	) -> Result<PrecompileOutput, ExitError> {
+		// The EVM version currently used doesn't provide `is_static` to precompiles.
+		Self::execute_with_static(input, target_gas, context, false)
+	}
+}
+
+impl<Runtime> CrowdloanRewardsWrapper<Runtime>
+where
+	Runtime: pallet_crowdloan_rewards::Config + pallet_evm::Config,
+	BalanceOf<Runtime>: TryFrom<U256> + Debug,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_crowdloan_rewards::Call<Runtime>>,
+{
+	/// Execute the precompile, `is_static` being whether it is called in a static context
+	/// (STATICCALL).
+	pub fn execute_with_static(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
 	) -> Result<PrecompileOutput, ExitError> {
 		let mut input = EvmDataReader::new(input);
+		let selector = input.read_selector()?;
+
+		check_function_modifier(context, is_static, selector.modifier())?;
 
-		match &input.read_selector()? {
+		match selector {
 			// Check for accessor methods first. These return results immediately
 			Action::IsContributor => Self::is_contributor(input, target_gas),
 			Action::RewardInfo => Self::reward_info(input, target_gas),
@@ -86,7 +113,7 @@ impl<Runtime> CrowdloanRewardsWrapper<Runtime>
 where
 	Runtime: pallet_crowdloan_rewards::Config + pallet_evm::Config + frame_system::Config,
 	BalanceOf<Runtime>: TryFrom<U256> + TryInto<u128> + Debug,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<pallet_crowdloan_rewards::Call<Runtime>>,
 {
@@ -96,7 +123,6 @@ where
 		target_gas: Option<u64>,
 	) -> EvmResult<PrecompileOutput> {
 		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?; // accounts_payable
 
 		// Bound check
 		input.expect_arguments(1)?;
@@ -114,7 +140,11 @@ where
 
 		// fetch data from pallet
 		let is_contributor: bool =
-			pallet_crowdloan_rewards::Pallet::<Runtime>::accounts_payable(account).is_some();
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads
+					.read(|| pallet_crowdloan_rewards::Pallet::<Runtime>::accounts_payable(account))
+			})?
+			.is_some();
 
 		log::trace!(target: "crowldoan-rewards-precompile", "Result from pallet is {:?}", is_contributor);
 
@@ -131,7 +161,6 @@ where
 		target_gas: Option<u64>,
 	) -> EvmResult<PrecompileOutput> {
 		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?; // accounts_payable
 
 		// Bound check
 		input.expect_arguments(1)?;
@@ -148,7 +177,9 @@ where
 		);
 
 		// fetch data from pallet
-		let reward_info = pallet_crowdloan_rewards::Pallet::<Runtime>::accounts_payable(account);
+		let reward_info = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| pallet_crowdloan_rewards::Pallet::<Runtime>::accounts_payable(account))
+		})?;
 
 		let (total, claimed): (U256, U256) = if let Some(reward_info) = reward_info {
 			let total_reward: u128 = reward_info
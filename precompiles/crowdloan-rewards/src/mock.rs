@@ -16,7 +16,6 @@
 
 //! Test utilities
 use super::*;
-use codec::{Decode, Encode, MaxEncodedLen};
 use cumulus_primitives_core::{
 	relay_chain::BlockNumber as RelayChainBlockNumber, PersistedValidationData,
 };
@@ -27,28 +26,26 @@ use frame_support::{
 	dispatch::UnfilteredDispatchable,
 	inherent::{InherentData, ProvideInherent},
 	parameter_types,
-	traits::{Everything, GenesisBuild, OnFinalize, OnInitialize},
+	traits::{GenesisBuild, OnFinalize, OnInitialize},
 };
 use frame_system::RawOrigin;
-use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot, PrecompileSet};
-use serde::{Deserialize, Serialize};
-use sp_core::H256;
 use sp_io;
-use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
-	Perbill,
-};
-pub type AccountId = TestAccount;
+use sp_runtime::Perbill;
+
+pub use precompile_utils::testing::{Account, TestPrecompiles};
+
+pub type AccountId = Account;
 pub type Balance = u128;
 pub type BlockNumber = u64;
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles);
 
 // Configure a mock runtime to test the pallet.
 construct_runtime!(
-	pub enum Test where
+	pub enum Runtime where
 		Block = Block,
 		NodeBlock = Block,
 		UncheckedExtrinsic = UncheckedExtrinsic,
@@ -62,62 +59,18 @@ construct_runtime!(
 	}
 );
 
-// FRom https://github.com/PureStake/moonbeam/pull/518. Merge to common once is merged
-#[derive(
-	Eq,
-	PartialEq,
-	Ord,
-	PartialOrd,
-	Clone,
-	Copy,
-	Encode,
-	Decode,
-	Debug,
-	MaxEncodedLen,
-	Serialize,
-	Deserialize,
-	derive_more::Display,
-)]
-pub enum TestAccount {
-	Alice,
-	Bob,
-	Charlie,
-	Bogus,
-}
-
-impl Default for TestAccount {
-	fn default() -> Self {
-		Self::Bogus
-	}
-}
-
-impl AddressMapping<TestAccount> for TestAccount {
-	fn into_account_id(h160_account: H160) -> TestAccount {
-		match h160_account {
-			a if a == H160::repeat_byte(0xAA) => Self::Alice,
-			a if a == H160::repeat_byte(0xBB) => Self::Bob,
-			a if a == H160::repeat_byte(0xCC) => Self::Charlie,
-			_ => Self::Bogus,
-		}
-	}
+/// The crowdloan precompile is available at address one in the mock runtime.
+pub fn precompile_address() -> H160 {
+	Account::Precompile.into()
 }
 
-impl From<TestAccount> for H160 {
-	fn from(value: TestAccount) -> H160 {
-		match value {
-			TestAccount::Alice => H160::repeat_byte(0xAA),
-			TestAccount::Bob => H160::repeat_byte(0xBB),
-			TestAccount::Charlie => H160::repeat_byte(0xCC),
-			TestAccount::Bogus => Default::default(),
-		}
-	}
-}
+pub type Precompiles = TestPrecompiles<CrowdloanRewardsWrapper<Runtime>>;
 
 parameter_types! {
 	pub ParachainId: cumulus_primitives_core::ParaId = 100.into();
 }
 
-impl cumulus_pallet_parachain_system::Config for Test {
+impl cumulus_pallet_parachain_system::Config for Runtime {
 	type SelfParaId = ParachainId;
 	type Event = Event;
 	type OnValidationData = ();
@@ -128,110 +81,6 @@ impl cumulus_pallet_parachain_system::Config for Test {
 	type ReservedDmpWeight = ();
 }
 
-parameter_types! {
-	pub const BlockHashCount: u64 = 250;
-	pub const SS58Prefix: u8 = 42;
-}
-impl frame_system::Config for Test {
-	type BaseCallFilter = Everything;
-	type DbWeight = ();
-	type Origin = Origin;
-	type Index = u64;
-	type BlockNumber = BlockNumber;
-	type Call = Call;
-	type Hash = H256;
-	type Hashing = BlakeTwo256;
-	type AccountId = TestAccount;
-	type Lookup = IdentityLookup<Self::AccountId>;
-	type Header = Header;
-	type Event = Event;
-	type BlockHashCount = BlockHashCount;
-	type Version = ();
-	type PalletInfo = PalletInfo;
-	type AccountData = pallet_balances::AccountData<Balance>;
-	type OnNewAccount = ();
-	type OnKilledAccount = ();
-	type SystemWeightInfo = ();
-	type BlockWeights = ();
-	type BlockLength = ();
-	type SS58Prefix = SS58Prefix;
-	type OnSetCode = cumulus_pallet_parachain_system::ParachainSetCode<Self>;
-}
-parameter_types! {
-	pub const ExistentialDeposit: u128 = 0;
-}
-impl pallet_balances::Config for Test {
-	type MaxReserves = ();
-	type ReserveIdentifier = ();
-	type MaxLocks = ();
-	type Balance = Balance;
-	type Event = Event;
-	type DustRemoval = ();
-	type ExistentialDeposit = ExistentialDeposit;
-	type AccountStore = System;
-	type WeightInfo = ();
-}
-
-/// The crowdloan precompile is available at address one in the mock runtime.
-pub fn precompile_address() -> H160 {
-	H160::from_low_u64_be(1)
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct TestPrecompiles<R>(PhantomData<R>);
-
-impl<R> PrecompileSet for TestPrecompiles<R>
-where
-	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
-	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
-	R: pallet_crowdloan_rewards::Config + pallet_evm::Config,
-	BalanceOf<R>: TryFrom<sp_core::U256> + Debug,
-	R::Call: From<pallet_crowdloan_rewards::Call<R>>,
-{
-	fn execute(
-		address: H160,
-		input: &[u8],
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> Option<Result<PrecompileOutput, ExitError>> {
-		match address {
-			a if a == precompile_address() => Some(CrowdloanRewardsWrapper::<R>::execute(
-				input, target_gas, context,
-			)),
-			_ => None,
-		}
-	}
-}
-
-pub type Precompiles = TestPrecompiles<Test>;
-
-impl pallet_evm::Config for Test {
-	type FeeCalculator = ();
-	type GasWeightMapping = ();
-	type CallOrigin = EnsureAddressRoot<TestAccount>;
-	type WithdrawOrigin = EnsureAddressNever<TestAccount>;
-	type AddressMapping = TestAccount;
-	type Currency = Balances;
-	type Event = Event;
-	type Runner = pallet_evm::runner::stack::Runner<Self>;
-	type Precompiles = Precompiles;
-	type ChainId = ();
-	type OnChargeTransaction = ();
-	type BlockGasLimit = ();
-	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
-	type FindAuthor = ();
-}
-
-parameter_types! {
-	pub const MinimumPeriod: u64 = 5;
-}
-impl pallet_timestamp::Config for Test {
-	type Moment = u64;
-	type OnTimestampSet = ();
-	type MinimumPeriod = MinimumPeriod;
-	type WeightInfo = ();
-}
-
 parameter_types! {
 	pub const TestMaxInitContributors: u32 = 8;
 	pub const TestMinimumReward: u128 = 0;
@@ -240,7 +89,7 @@ parameter_types! {
 	pub const RelaySignaturesThreshold: Perbill = Perbill::from_percent(100);
 }
 
-impl pallet_crowdloan_rewards::Config for Test {
+impl pallet_crowdloan_rewards::Config for Runtime {
 	type Event = Event;
 	type Initialized = TestInitialized;
 	type InitializationPayment = TestInitializationPayment;
@@ -280,16 +129,16 @@ impl ExtBuilder {
 	}
 	pub(crate) fn build(self) -> sp_io::TestExternalities {
 		let mut t = frame_system::GenesisConfig::default()
-			.build_storage::<Test>()
+			.build_storage::<Runtime>()
 			.expect("Frame system builds valid default genesis config");
 
-		pallet_balances::GenesisConfig::<Test> {
+		pallet_balances::GenesisConfig::<Runtime> {
 			balances: self.balances,
 		}
 		.assimilate_storage(&mut t)
 		.expect("Pallet balances storage can be assimilated");
 
-		pallet_crowdloan_rewards::GenesisConfig::<Test> {
+		pallet_crowdloan_rewards::GenesisConfig::<Runtime> {
 			funded_amount: self.crowdloan_pot,
 		}
 		.assimilate_storage(&mut t)
@@ -352,12 +201,11 @@ pub(crate) fn events() -> Vec<Event> {
 		.collect::<Vec<_>>()
 }
 
-// Helper function to give a simple evm context suitable for tests.
-// We can remove this once https://github.com/rust-blockchain/evm/pull/35
-// is in our dependency graph.
+// Helper function to give a simple evm context suitable for tests, calling the precompile
+// from the default address.
 pub fn evm_test_context() -> evm::Context {
 	evm::Context {
-		address: Default::default(),
+		address: precompile_address(),
 		caller: Default::default(),
 		apparent_value: From::from(0),
 	}
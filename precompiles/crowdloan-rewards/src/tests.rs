@@ -15,8 +15,8 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::mock::{
-	events, evm_test_context, precompile_address, roll_to, Call, Crowdloan, ExtBuilder, Origin,
-	Precompiles, TestAccount::Alice, TestAccount::Bob, TestAccount::Charlie,
+	events, evm_test_context, precompile_address, roll_to, Account::Alice, Account::Bob,
+	Account::Charlie, Call, Crowdloan, ExtBuilder, Origin, Precompiles,
 };
 use crate::{Action, PrecompileOutput};
 use frame_support::{assert_ok, dispatch::Dispatchable};
@@ -328,3 +328,18 @@ fn test_bound_checks_for_address_parsing() {
 			);
 		})
 }
+
+#[test]
+fn claim_is_not_payable() {
+	ExtBuilder::default().build().execute_with(|| {
+		let selector = &Keccak256::digest(b"claim()")[0..4];
+
+		let mut context = evm_test_context();
+		context.apparent_value = U256::one();
+
+		assert_eq!(
+			Precompiles::execute(precompile_address(), selector, None, &context),
+			Some(Err(error("function is not payable")))
+		);
+	});
+}
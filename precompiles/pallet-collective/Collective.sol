@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Take part in a collective. Proposals are SCALE encoded runtime calls, and are identified by
+/// their hash and their index.
+/// This file is generated from the Rust implementation of the precompile.
+interface Collective {
+    /// Dispatch `proposal` with the origin of a single member of the collective, the caller
+    /// being a member.
+    /// Selector: 09c5eabe
+    function execute(bytes memory proposal) external;
+
+    /// Propose `proposal` to the collective, which is executed once `threshold` members approved
+    /// it. It is executed immediately if `threshold` is below 2.
+    /// Selector: c57f3260
+    function propose(uint32 threshold, bytes memory proposal) external;
+
+    /// Vote for or against the proposal `proposal_hash` with index `proposal_index`.
+    /// Selector: 73e37688
+    function vote(bytes32 proposal_hash, uint32 proposal_index, bool approve) external;
+
+    /// Close the vote on the proposal `proposal_hash` with index `proposal_index`, executing it
+    /// if it is approved. It can be closed before the end of the vote if its outcome is already
+    /// known. `proposal_weight_bound` and `length_bound` must be at least the weight and the
+    /// encoded length of the proposal.
+    /// Selector: 638d9d47
+    function close(bytes32 proposal_hash, uint32 proposal_index, uint64 proposal_weight_bound, uint32 length_bound) external;
+
+    /// The hashes of the open proposals.
+    /// Selector: 55ef20e6
+    function proposals() external view returns (bytes32[] memory);
+
+    /// The members of the collective.
+    /// Selector: bdd4d18d
+    function members() external view returns (address[] memory);
+}
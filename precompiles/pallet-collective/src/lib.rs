@@ -0,0 +1,200 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to take part in a collective (council, technical committee...) through
+//! pallet-collective.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::DecodeLimit;
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::GetCallMetadata,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{
+	error, precompile, Address, Bytes, EvmResult, PrecompileHandle, RuntimeHelper,
+};
+use sp_core::{H160, H256};
+use sp_std::{boxed::Box, convert::TryFrom, marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Maximum depth of the nested calls of a proposal, as for extrinsics.
+pub const PROPOSAL_DECODE_DEPTH_LIMIT: u32 = 256;
+
+pub type ProposalOf<Runtime, Instance> = <Runtime as pallet_collective::Config<Instance>>::Proposal;
+
+/// A precompile to wrap the functionality from pallet-collective.
+/// Each instance of the pallet has its own precompile address. It must be installed with
+/// `precompile_utils::check_not_delegated`, as the caller proposes and votes as a member.
+pub struct CollectiveWrapper<Runtime, Instance: 'static>(PhantomData<(Runtime, Instance)>);
+
+/// Take part in a collective. Proposals are SCALE encoded runtime calls, and are identified by
+/// their hash and their index.
+#[precompile(interface = "Collective")]
+impl<Runtime, Instance> CollectiveWrapper<Runtime, Instance>
+where
+	Instance: 'static,
+	Runtime: pallet_collective::Config<Instance> + pallet_evm::Config + frame_system::Config,
+	Runtime::AccountId: Into<H160>,
+	Runtime::Hash: From<H256> + Into<H256>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_collective::Call<Runtime, Instance>>,
+{
+	/// Dispatch `proposal` with the origin of a single member of the collective, the caller
+	/// being a member.
+	#[precompile::public("execute(bytes)")]
+	fn execute_proposal(handle: &mut PrecompileHandle, proposal: Bytes) -> EvmResult {
+		let (proposal, length_bound) = Self::decode_proposal(proposal)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call =
+			pallet_collective::Call::<Runtime, Instance>::execute(Box::new(proposal), length_bound);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Propose `proposal` to the collective, which is executed once `threshold` members approved
+	/// it. It is executed immediately if `threshold` is below 2.
+	#[precompile::public("propose(uint32,bytes)")]
+	fn propose(handle: &mut PrecompileHandle, threshold: u32, proposal: Bytes) -> EvmResult {
+		let (proposal, length_bound) = Self::decode_proposal(proposal)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_collective::Call::<Runtime, Instance>::propose(
+			threshold,
+			Box::new(proposal),
+			length_bound,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Vote for or against the proposal `proposal_hash` with index `proposal_index`.
+	#[precompile::public("vote(bytes32,uint32,bool)")]
+	fn vote(
+		handle: &mut PrecompileHandle,
+		proposal_hash: H256,
+		proposal_index: u32,
+		approve: bool,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_collective::Call::<Runtime, Instance>::vote(
+			proposal_hash.into(),
+			proposal_index,
+			approve,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Close the vote on the proposal `proposal_hash` with index `proposal_index`, executing it
+	/// if it is approved. It can be closed before the end of the vote if its outcome is already
+	/// known. `proposal_weight_bound` and `length_bound` must be at least the weight and the
+	/// encoded length of the proposal.
+	#[precompile::public("close(bytes32,uint32,uint64,uint32)")]
+	fn close(
+		handle: &mut PrecompileHandle,
+		proposal_hash: H256,
+		proposal_index: u32,
+		proposal_weight_bound: u64,
+		length_bound: u32,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_collective::Call::<Runtime, Instance>::close(
+			proposal_hash.into(),
+			proposal_index,
+			proposal_weight_bound,
+			length_bound,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// The hashes of the open proposals.
+	#[precompile::public("proposals()")]
+	#[precompile::view]
+	fn proposals(handle: &mut PrecompileHandle) -> EvmResult<Vec<H256>> {
+		let proposals =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_collective::Pallet::<Runtime, Instance>::proposals())
+			})?;
+
+		Ok(proposals.into_iter().map(Into::into).collect())
+	}
+
+	/// The members of the collective.
+	#[precompile::public("members()")]
+	#[precompile::view]
+	fn members(handle: &mut PrecompileHandle) -> EvmResult<Vec<Address>> {
+		let members =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_collective::Pallet::<Runtime, Instance>::members())
+			})?;
+
+		Ok(members
+			.into_iter()
+			.map(|member| Address(member.into()))
+			.collect())
+	}
+
+	/// Decode a SCALE encoded proposal, which must not be followed by other data, and return it
+	/// with its length.
+	fn decode_proposal(proposal: Bytes) -> EvmResult<(ProposalOf<Runtime, Instance>, u32)> {
+		let length = u32::try_from(proposal.0.len()).map_err(|_| error("proposal is too long"))?;
+
+		let proposal = ProposalOf::<Runtime, Instance>::decode_all_with_depth_limit(
+			PROPOSAL_DECODE_DEPTH_LIMIT,
+			&proposal.0,
+		)
+		.map_err(|_| error("failed decoding proposal"))?;
+
+		Ok((proposal, length))
+	}
+}
@@ -0,0 +1,122 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities.
+
+use super::*;
+
+use evm::{executor::PrecompileOutput, Context, ExitError};
+use frame_support::{construct_runtime, parameter_types};
+use pallet_evm::{Precompile, PrecompileSet};
+use precompile_utils::check_not_delegated;
+use sp_core::H160;
+
+pub use precompile_utils::testing::{Account, PRECOMPILE_ADDRESS};
+
+/// Address of the precompile of the technical committee, the precompile of the council being at
+/// `PRECOMPILE_ADDRESS`.
+pub const TECH_COMMITTEE_PRECOMPILE_ADDRESS: u64 = 2;
+
+pub type AccountId = Account;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles<Runtime>);
+
+parameter_types! {
+	pub const MotionDuration: BlockNumber = 10;
+	pub const MaxProposals: u32 = 100;
+	pub const MaxMembers: u32 = 100;
+}
+
+type CouncilInstance = pallet_collective::Instance1;
+type TechCommitteeInstance = pallet_collective::Instance2;
+
+impl pallet_collective::Config<CouncilInstance> for Runtime {
+	type Origin = Origin;
+	type Event = Event;
+	type Proposal = Call;
+	type MotionDuration = MotionDuration;
+	type MaxProposals = MaxProposals;
+	type MaxMembers = MaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+impl pallet_collective::Config<TechCommitteeInstance> for Runtime {
+	type Origin = Origin;
+	type Event = Event;
+	type Proposal = Call;
+	type MotionDuration = MotionDuration;
+	type MaxProposals = MaxProposals;
+	type MaxMembers = MaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>},
+		TechCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>},
+	}
+);
+
+pub struct Precompiles<R>(PhantomData<R>);
+
+impl<R> PrecompileSet for Precompiles<R>
+where
+	CollectiveWrapper<R, CouncilInstance>: Precompile,
+	CollectiveWrapper<R, TechCommitteeInstance>: Precompile,
+{
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Option<Result<PrecompileOutput, ExitError>> {
+		match address {
+			a if a == hash(PRECOMPILE_ADDRESS) => {
+				Some(check_not_delegated(a, context).and_then(|_| {
+					CollectiveWrapper::<R, CouncilInstance>::execute(input, target_gas, context)
+				}))
+			}
+			a if a == hash(TECH_COMMITTEE_PRECOMPILE_ADDRESS) => {
+				Some(check_not_delegated(a, context).and_then(|_| {
+					CollectiveWrapper::<R, TechCommitteeInstance>::execute(
+						input, target_gas, context,
+					)
+				}))
+			}
+			_ => None,
+		}
+	}
+}
+
+fn hash(a: u64) -> H160 {
+	H160::from_low_u64_be(a)
+}
+
+pub(crate) type ExtBuilder = precompile_utils::testing::ExtBuilder<Runtime>;
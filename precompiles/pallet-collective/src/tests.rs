@@ -0,0 +1,300 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use codec::Encode;
+use evm::Context;
+use frame_support::assert_ok;
+use pallet_evm::PrecompileSet;
+use precompile_utils::{
+	testing::{check_solidity_interface, PrecompileTesterExt},
+	EvmDataWriter,
+};
+use sp_core::U256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn proposal() -> Call {
+	Call::System(frame_system::Call::remark(b"hello".to_vec()))
+}
+
+fn proposal_hash() -> H256 {
+	BlakeTwo256::hash_of(&proposal())
+}
+
+fn propose_input(threshold: u32) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::Propose as u32)
+		.write(threshold)
+		.write(Bytes(proposal().encode()))
+		.build()
+}
+
+fn vote_input(approve: bool) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::Vote as u32)
+		.write(proposal_hash())
+		.write(0u32)
+		.write(approve)
+		.build()
+}
+
+fn close_input() -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::Close as u32)
+		.write(proposal_hash())
+		.write(0u32)
+		.write(1_000_000_000u64)
+		.write(proposal().encode().len() as u32)
+		.build()
+}
+
+/// Alice and Bob are the members of the council.
+fn set_council_members() {
+	assert_ok!(Council::set_members(
+		Origin::root(),
+		vec![Account::Alice, Account::Bob],
+		None,
+		0
+	));
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::ExecuteProposal as u32, 0x09c5eabe);
+	assert_eq!(Action::Propose as u32, 0xc57f3260);
+	assert_eq!(Action::Vote as u32, 0x73e37688);
+	assert_eq!(Action::Close as u32, 0x638d9d47);
+	assert_eq!(Action::Proposals as u32, 0x55ef20e6);
+	assert_eq!(Action::Members as u32, 0xbdd4d18d);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "Collective.sol");
+}
+
+#[test]
+fn members_are_those_of_the_instance() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+		assert_ok!(TechCommittee::set_members(
+			Origin::root(),
+			vec![Account::Charlie],
+			None,
+			0
+		));
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Charlie,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::Members as u32).build(),
+		)
+		.execute_returns_encoded(vec![
+			Address(Account::Alice.into()),
+			Address(Account::Bob.into()),
+		]);
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Charlie,
+			H160::from_low_u64_be(TECH_COMMITTEE_PRECOMPILE_ADDRESS),
+			EvmDataWriter::new_with_selector(Action::Members as u32).build(),
+		)
+		.execute_returns_encoded(vec![Address(Account::Charlie.into())]);
+	});
+}
+
+#[test]
+fn member_can_execute() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::ExecuteProposal as u32)
+				.write(Bytes(proposal().encode()))
+				.build(),
+		)
+		.execute_stops();
+	});
+}
+
+#[test]
+fn non_member_cannot_execute() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Charlie,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::ExecuteProposal as u32)
+				.write(Bytes(proposal().encode()))
+				.build(),
+		)
+		.execute_reverts(|output| output.starts_with("Dispatched call Council::execute failed"));
+	});
+}
+
+#[test]
+fn execute_fails_for_invalid_proposal() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		let mut encoded = proposal().encode();
+		encoded.push(0);
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::ExecuteProposal as u32)
+				.write(Bytes(encoded))
+				.build(),
+		)
+		.execute_reverts(|output| output == "failed decoding proposal");
+	});
+}
+
+#[test]
+fn propose_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Precompile, propose_input(2))
+			.execute_stops();
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Charlie,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::Proposals as u32).build(),
+		)
+		.execute_returns_encoded(vec![proposal_hash()]);
+
+		// The proposer votes for its proposal.
+		let votes = Council::voting(proposal_hash()).expect("proposal is open");
+		assert_eq!(votes.ayes, vec![Account::Alice]);
+		assert_eq!(votes.threshold, 2);
+	});
+}
+
+#[test]
+fn propose_with_threshold_below_two_executes_proposal() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Precompile, propose_input(1))
+			.execute_stops();
+
+		assert!(Council::proposals().is_empty());
+	});
+}
+
+#[test]
+fn vote_and_close_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Precompile, propose_input(2))
+			.execute_stops();
+
+		Precompiles::<Runtime>::prepare_test(Account::Bob, Account::Precompile, vote_input(true))
+			.execute_stops();
+
+		let votes = Council::voting(proposal_hash()).expect("proposal is open");
+		assert_eq!(votes.ayes, vec![Account::Alice, Account::Bob]);
+
+		// The outcome is known, the proposal can be closed before the end of the vote.
+		Precompiles::<Runtime>::prepare_test(Account::Charlie, Account::Precompile, close_input())
+			.execute_stops();
+
+		assert!(Council::proposals().is_empty());
+		assert!(Council::voting(proposal_hash()).is_none());
+	});
+}
+
+#[test]
+fn close_fails_before_the_outcome_is_known() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Precompile, propose_input(2))
+			.execute_stops();
+
+		Precompiles::<Runtime>::prepare_test(Account::Charlie, Account::Precompile, close_input())
+			.execute_reverts(|output| output.starts_with("Dispatched call Council::close failed"));
+	});
+}
+
+#[test]
+fn non_member_cannot_vote() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Precompile, propose_input(2))
+			.execute_stops();
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Charlie,
+			Account::Precompile,
+			vote_input(true),
+		)
+		.execute_reverts(|output| output.starts_with("Dispatched call Council::vote failed"));
+	});
+}
+
+#[test]
+fn proposals_are_those_of_the_instance() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		Precompiles::<Runtime>::prepare_test(Account::Alice, Account::Precompile, propose_input(2))
+			.execute_stops();
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Charlie,
+			H160::from_low_u64_be(TECH_COMMITTEE_PRECOMPILE_ADDRESS),
+			EvmDataWriter::new_with_selector(Action::Proposals as u32).build(),
+		)
+		.execute_returns_encoded(Vec::<H256>::new());
+	});
+}
+
+#[test]
+fn collective_cannot_be_delegated() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_council_members();
+
+		// With DELEGATECALL the context is the one of the calling contract (Charlie), while the
+		// caller is the account which called it (Alice).
+		let context = Context {
+			address: Account::Charlie.into(),
+			caller: Account::Alice.into(),
+			apparent_value: U256::zero(),
+		};
+
+		for precompile in [PRECOMPILE_ADDRESS, TECH_COMMITTEE_PRECOMPILE_ADDRESS] {
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					H160::from_low_u64_be(precompile),
+					&propose_input(2),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+		}
+
+		assert!(Council::proposals().is_empty());
+	});
+}
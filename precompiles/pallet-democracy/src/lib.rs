@@ -20,12 +20,13 @@
 
 use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
-use frame_support::traits::Currency;
+use frame_support::traits::{Currency, GetCallMetadata};
 use pallet_democracy::{AccountVote, Call as DemocracyCall, ReferendumInfo, Vote};
 use pallet_evm::AddressMapping;
 use pallet_evm::Precompile;
 use precompile_utils::{
-	error, Address, EvmData, EvmDataReader, EvmDataWriter, EvmResult, Gasometer, RuntimeHelper,
+	check_function_modifier, error, Address, EvmData, EvmDataReader, EvmDataWriter, EvmResult,
+	Gasometer, RuntimeHelper,
 };
 use sp_core::{H160, H256, U256};
 use sp_std::convert::{TryFrom, TryInto};
@@ -46,10 +47,15 @@ type DemocracyOf<Runtime> = pallet_democracy::Pallet<Runtime>;
 #[precompile_utils::generate_function_selector]
 #[derive(Debug, PartialEq, num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
 enum Action {
+	#[view]
 	PublicPropCount = "public_prop_count()",
+	#[view]
 	DepositOf = "deposit_of(uint256)",
+	#[view]
 	LowestUnbaked = "lowest_unbaked()",
+	#[view]
 	OngoingReferendumInfo = "ongoing_referendum_info(uint256)",
+	#[view]
 	FinishedReferendumInfo = "finished_referendum_info(uint256)",
 	Propose = "propose(bytes32,uint256)",
 	Second = "second(uint256,uint256)",
@@ -71,7 +77,7 @@ impl<Runtime> Precompile for DemocracyWrapper<Runtime>
 where
 	Runtime: pallet_democracy::Config + pallet_evm::Config,
 	BalanceOf<Runtime>: TryFrom<U256> + Debug + EvmData,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<DemocracyCall<Runtime>>,
 	Runtime::Hash: From<H256>,
@@ -80,6 +86,28 @@ where
 		input: &[u8], //Reminder this is big-endian
 		target_gas: Option<u64>,
 		context: &Context,
+	) -> Result<PrecompileOutput, ExitError> {
+		// The EVM version currently used doesn't provide `is_static` to precompiles.
+		Self::execute_with_static(input, target_gas, context, false)
+	}
+}
+
+impl<Runtime> DemocracyWrapper<Runtime>
+where
+	Runtime: pallet_democracy::Config + pallet_evm::Config,
+	BalanceOf<Runtime>: TryFrom<U256> + Debug + EvmData,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<DemocracyCall<Runtime>>,
+	Runtime::Hash: From<H256>,
+{
+	/// Execute the precompile, `is_static` being whether it is called in a static context
+	/// (STATICCALL).
+	pub fn execute_with_static(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
 	) -> Result<PrecompileOutput, ExitError> {
 		log::trace!(target: "democracy-precompile", "In democracy wrapper");
 
@@ -89,7 +117,11 @@ where
 		// These are the four-byte function selectors calculated from the DemocracyInterface.sol
 		// according to the solidity specification
 		// https://docs.soliditylang.org/en/v0.8.0/abi-spec.html#function-selector
-		match &input.read_selector()? {
+		let selector = input.read_selector()?;
+
+		check_function_modifier(context, is_static, selector.modifier())?;
+
+		match selector {
 			// Storage Accessors
 			Action::PublicPropCount => Self::public_prop_count(target_gas),
 			Action::DepositOf => Self::deposit_of(input, target_gas),
@@ -113,7 +145,7 @@ impl<Runtime> DemocracyWrapper<Runtime>
 where
 	Runtime: pallet_democracy::Config + pallet_evm::Config + frame_system::Config,
 	BalanceOf<Runtime>: TryFrom<U256> + TryInto<u128> + Debug + EvmData,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<DemocracyCall<Runtime>>,
 	Runtime::Hash: From<H256>,
@@ -124,8 +156,9 @@ where
 		let mut gasometer = Gasometer::new(target_gas);
 
 		// Fetch data from pallet
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let prop_count = DemocracyOf::<Runtime>::public_prop_count();
+		let prop_count = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| DemocracyOf::<Runtime>::public_prop_count())
+		})?;
 		log::trace!(target: "democracy-precompile", "Prop count from pallet is {:?}", prop_count);
 
 		Ok(PrecompileOutput {
@@ -147,10 +180,11 @@ where
 		let prop_index: u32 = input.read()?;
 
 		// Fetch data from pallet
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let deposit = DemocracyOf::<Runtime>::deposit_of(prop_index)
-			.ok_or_else(|| error("No such proposal in pallet democracy"))?
-			.1;
+		let deposit = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| DemocracyOf::<Runtime>::deposit_of(prop_index))
+		})?
+		.ok_or_else(|| error("No such proposal in pallet democracy"))?
+		.1;
 
 		log::trace!(
 			target: "democracy-precompile",
@@ -169,8 +203,10 @@ where
 		let mut gasometer = Gasometer::new(target_gas);
 
 		// Fetch data from pallet
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let lowest_unbaked = DemocracyOf::<Runtime>::lowest_unbaked();
+		let lowest_unbaked =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| DemocracyOf::<Runtime>::lowest_unbaked())
+			})?;
 		log::trace!(
 			target: "democracy-precompile",
 			"lowest unbaked referendum is {:?}", lowest_unbaked
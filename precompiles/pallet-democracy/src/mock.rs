@@ -16,83 +16,29 @@
 
 //! Test utilities
 use super::*;
-use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{Everything, OnFinalize, OnInitialize},
+	traits::{OnFinalize, OnInitialize},
 };
 use frame_system::{EnsureRoot, EnsureSigned};
 use pallet_democracy::VoteThreshold;
-use pallet_evm::{
-	AddressMapping, EnsureAddressNever, EnsureAddressRoot, SubstrateBlockHashMapping,
-};
-use serde::{Deserialize, Serialize};
 use sp_core::H256;
 use sp_io;
-use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
-};
 
-pub type AccountId = TestAccount;
+pub use precompile_utils::testing::{Account, TestPrecompiles};
+
+pub type AccountId = Account;
 pub type Balance = u128;
 pub type BlockNumber = u64;
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>;
-
-#[derive(
-	Eq,
-	PartialEq,
-	Ord,
-	PartialOrd,
-	Clone,
-	Encode,
-	Decode,
-	Debug,
-	MaxEncodedLen,
-	Serialize,
-	Deserialize,
-	derive_more::Display,
-)]
-pub enum TestAccount {
-	Alice,
-	Bob,
-	Charlie,
-	Bogus,
-}
-
-impl Default for TestAccount {
-	fn default() -> Self {
-		Self::Bogus
-	}
-}
-
-impl AddressMapping<TestAccount> for TestAccount {
-	fn into_account_id(h160_account: H160) -> TestAccount {
-		match h160_account {
-			a if a == H160::repeat_byte(0xAA) => Self::Alice,
-			a if a == H160::repeat_byte(0xBB) => Self::Bob,
-			a if a == H160::repeat_byte(0xCC) => Self::Charlie,
-			_ => Self::Bogus,
-		}
-	}
-}
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
 
-impl From<TestAccount> for H160 {
-	fn from(value: TestAccount) -> H160 {
-		match value {
-			TestAccount::Alice => H160::repeat_byte(0xAA),
-			TestAccount::Bob => H160::repeat_byte(0xBB),
-			TestAccount::Charlie => H160::repeat_byte(0xCC),
-			TestAccount::Bogus => Default::default(),
-		}
-	}
-}
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles);
 
 // Configure a mock runtime to test the pallet.
 construct_runtime!(
-	pub enum Test where
+	pub enum Runtime where
 		Block = Block,
 		NodeBlock = Block,
 		UncheckedExtrinsic = UncheckedExtrinsic,
@@ -106,82 +52,11 @@ construct_runtime!(
 	}
 );
 
-parameter_types! {
-	pub const BlockHashCount: u64 = 250;
-	pub const SS58Prefix: u8 = 42;
-}
-impl frame_system::Config for Test {
-	type BaseCallFilter = Everything;
-	type DbWeight = ();
-	type Origin = Origin;
-	type Index = u64;
-	type BlockNumber = BlockNumber;
-	type Call = Call;
-	type Hash = H256;
-	type Hashing = BlakeTwo256;
-	type AccountId = TestAccount;
-	type Lookup = IdentityLookup<Self::AccountId>;
-	type Header = Header;
-	type Event = Event;
-	type BlockHashCount = BlockHashCount;
-	type Version = ();
-	type PalletInfo = PalletInfo;
-	type AccountData = pallet_balances::AccountData<Balance>;
-	type OnNewAccount = ();
-	type OnKilledAccount = ();
-	type SystemWeightInfo = ();
-	type BlockWeights = ();
-	type BlockLength = ();
-	type SS58Prefix = SS58Prefix;
-	type OnSetCode = ();
-}
-parameter_types! {
-	pub const ExistentialDeposit: u128 = 0;
-}
-impl pallet_balances::Config for Test {
-	type MaxReserves = ();
-	type ReserveIdentifier = ();
-	type MaxLocks = ();
-	type Balance = Balance;
-	type Event = Event;
-	type DustRemoval = ();
-	type ExistentialDeposit = ExistentialDeposit;
-	type AccountStore = System;
-	type WeightInfo = ();
-}
-
 /// The democracy precompile is available at address 1 in the mock runtime.
 pub fn precompile_address() -> H160 {
-	H160::from_low_u64_be(1)
-}
-pub type Precompiles = (DemocracyWrapper<Test>,);
-
-impl pallet_evm::Config for Test {
-	type FeeCalculator = ();
-	type GasWeightMapping = ();
-	type CallOrigin = EnsureAddressRoot<TestAccount>;
-	type WithdrawOrigin = EnsureAddressNever<TestAccount>;
-	type AddressMapping = TestAccount;
-	type Currency = Balances;
-	type Event = Event;
-	type Runner = pallet_evm::runner::stack::Runner<Self>;
-	type Precompiles = Precompiles;
-	type ChainId = ();
-	type OnChargeTransaction = ();
-	type BlockGasLimit = ();
-	type BlockHashMapping = SubstrateBlockHashMapping<Self>;
-	type FindAuthor = ();
-}
-
-parameter_types! {
-	pub const MinimumPeriod: u64 = 5;
-}
-impl pallet_timestamp::Config for Test {
-	type Moment = u64;
-	type OnTimestampSet = ();
-	type MinimumPeriod = MinimumPeriod;
-	type WeightInfo = ();
+	Account::Precompile.into()
 }
+pub type Precompiles = TestPrecompiles<DemocracyWrapper<Runtime>>;
 
 parameter_types! {
 	pub const LaunchPeriod: BlockNumber = 10;
@@ -196,7 +71,7 @@ parameter_types! {
 	pub const InstantAllowed: bool = false;
 }
 
-impl pallet_democracy::Config for Test {
+impl pallet_democracy::Config for Runtime {
 	type Proposal = Call;
 	type Event = Event;
 	type Currency = Balances;
@@ -225,13 +100,13 @@ impl pallet_democracy::Config for Test {
 	type WeightInfo = ();
 	type MaxProposals = MaxProposals;
 }
-impl pallet_scheduler::Config for Test {
+impl pallet_scheduler::Config for Runtime {
 	type Event = Event;
 	type Origin = Origin;
 	type PalletsOrigin = OriginCaller;
 	type Call = Call;
 	type MaximumWeight = ();
-	type ScheduleOrigin = EnsureRoot<TestAccount>;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
 	type MaxScheduledPerBlock = ();
 	type WeightInfo = ();
 }
@@ -272,10 +147,10 @@ impl ExtBuilder {
 	/// Build the test externalities for use in tests
 	pub(crate) fn build(self) -> sp_io::TestExternalities {
 		let mut t = frame_system::GenesisConfig::default()
-			.build_storage::<Test>()
+			.build_storage::<Runtime>()
 			.expect("Frame system builds valid default genesis config");
 
-		pallet_balances::GenesisConfig::<Test> {
+		pallet_balances::GenesisConfig::<Runtime> {
 			balances: self.balances.clone(),
 		}
 		.assimilate_storage(&mut t)
@@ -325,51 +200,12 @@ pub(crate) fn events() -> Vec<Event> {
 		.collect::<Vec<_>>()
 }
 
-// Helper function to give a simple evm context suitable for tests.
-// We can remove this once https://github.com/rust-blockchain/evm/pull/35
-// is in our dependency graph.
+// Helper function to give a simple evm context suitable for tests, calling the precompile
+// from the default address.
 pub fn evm_test_context() -> evm::Context {
 	evm::Context {
-		address: Default::default(),
+		address: precompile_address(),
 		caller: Default::default(),
 		apparent_value: From::from(0),
 	}
 }
-
-#[test]
-fn test_account_id_mapping_works() {
-	// Bidirectional conversions for normal accounts
-	assert_eq!(
-		TestAccount::Alice,
-		TestAccount::into_account_id(TestAccount::Alice.into())
-	);
-	assert_eq!(
-		TestAccount::Bob,
-		TestAccount::into_account_id(TestAccount::Bob.into())
-	);
-	assert_eq!(
-		TestAccount::Charlie,
-		TestAccount::into_account_id(TestAccount::Charlie.into())
-	);
-
-	// Bidirectional conversion between bogus and default H160
-	assert_eq!(
-		TestAccount::Bogus,
-		TestAccount::into_account_id(H160::default())
-	);
-	assert_eq!(H160::default(), TestAccount::Bogus.into());
-
-	// All other H160s map to bogus
-	assert_eq!(
-		TestAccount::Bogus,
-		TestAccount::into_account_id(H160::zero())
-	);
-	assert_eq!(
-		TestAccount::Bogus,
-		TestAccount::into_account_id(H160::repeat_byte(0x12))
-	);
-	assert_eq!(
-		TestAccount::Bogus,
-		TestAccount::into_account_id(H160::repeat_byte(0xFF))
-	);
-}
@@ -16,9 +16,9 @@
 
 use crate::{
 	mock::{
-		events, evm_test_context, precompile_address, roll_to, Balances, Call, Democracy,
-		ExtBuilder, Origin, Precompiles, Test,
-		TestAccount::{self, Alice, Bob},
+		events, evm_test_context, precompile_address, roll_to,
+		Account::{self, Alice, Bob},
+		Balances, Call, Democracy, ExtBuilder, Origin, Precompiles, Runtime,
 	},
 	Action,
 };
@@ -255,7 +255,7 @@ fn lowest_unbaked_non_zero() {
 
 			// Assert that the vote was recorded in storage
 			assert_eq!(
-				pallet_democracy::VotingOf::<Test>::get(Alice),
+				pallet_democracy::VotingOf::<Runtime>::get(Alice),
 				Voting::Direct {
 					votes: vec![(
 						0,
@@ -274,8 +274,8 @@ fn lowest_unbaked_non_zero() {
 
 			// Run it through until it is baked
 			roll_to(
-				<Test as DemocracyConfig>::VotingPeriod::get()
-					+ <Test as DemocracyConfig>::LaunchPeriod::get()
+				<Runtime as DemocracyConfig>::VotingPeriod::get()
+					+ <Runtime as DemocracyConfig>::LaunchPeriod::get()
 					+ 1000,
 			);
 
@@ -478,7 +478,7 @@ fn standard_vote_aye_works() {
 			// Assert that the vote was recorded in storage
 			// Should check ReferendumInfoOf too, but can't because of private fields etc
 			assert_eq!(
-				pallet_democracy::VotingOf::<Test>::get(Alice),
+				pallet_democracy::VotingOf::<Runtime>::get(Alice),
 				Voting::Direct {
 					votes: vec![(
 						0,
@@ -542,7 +542,7 @@ fn standard_vote_nay_conviction_works() {
 			// Assert that the vote was recorded in storage
 			// Should check ReferendumInfoOf too, but can't because of private fields etc
 			assert_eq!(
-				pallet_democracy::VotingOf::<Test>::get(Alice),
+				pallet_democracy::VotingOf::<Runtime>::get(Alice),
 				Voting::Direct {
 					votes: vec![(
 						0,
@@ -621,7 +621,7 @@ fn remove_vote_works() {
 			// Assert that the vote was recorded in storage
 			// Should check ReferendumInfoOf too, but can't because of private fields etc
 			assert_eq!(
-				pallet_democracy::VotingOf::<Test>::get(Alice),
+				pallet_democracy::VotingOf::<Runtime>::get(Alice),
 				Voting::Direct {
 					votes: vec![],
 					delegations: Default::default(),
@@ -647,7 +647,7 @@ fn remove_vote_dne() {
 			.dispatch(Origin::signed(Alice)));
 
 			// Wait until it becomes a referendum
-			roll_to(<Test as DemocracyConfig>::LaunchPeriod::get());
+			roll_to(<Runtime as DemocracyConfig>::LaunchPeriod::get());
 
 			// Construct input data to remove a non-existant vote
 			let input = EvmDataWriter::new()
@@ -703,7 +703,7 @@ fn delegate_works() {
 
 			// Check that storage is correct
 			assert_eq!(
-				pallet_democracy::VotingOf::<Test>::get(Alice),
+				pallet_democracy::VotingOf::<Runtime>::get(Alice),
 				Voting::Delegating {
 					balance: 100,
 					target: Bob,
@@ -715,7 +715,7 @@ fn delegate_works() {
 			// Would be nice to check that it shows up for Bob too, but  can't because of
 			// private fields. At elast I can see it works manually when uncommenting this.
 			// assert_eq!(
-			// 	pallet_democracy::VotingOf::<Test>::get(Bob),
+			// 	pallet_democracy::VotingOf::<Runtime>::get(Bob),
 			// 	Voting::Direct {
 			// 		votes: Default::default(),
 			// 		delegations: pallet_democracy::Delegations {
@@ -773,7 +773,7 @@ fn undelegate_works() {
 			// Would be nice to check storage too, but I can't express PriorLock because
 			// it is private.
 			// assert_eq!(
-			// 	pallet_democracy::VotingOf::<Test>::get(Alice),
+			// 	pallet_democracy::VotingOf::<Runtime>::get(Alice),
 			// 	Voting::Direct{
 			// 		votes: Default::default(),
 			// 		delegations: Default::default(),
@@ -833,10 +833,7 @@ fn unlock_works() {
 			// One possible way to look further: I just noticed there is a `Locks` storage item in
 			// the pallet.
 			// And also, maybe write a test in the pallet to ensure the locks work as expected.
-			assert_eq!(
-				<Balances as Currency<TestAccount>>::free_balance(&Alice),
-				900
-			);
+			assert_eq!(<Balances as Currency<Account>>::free_balance(&Alice), 900);
 
 			// Let time elapse until she wins the vote and gets her tokens locked
 			roll_to(11);
@@ -909,3 +906,20 @@ fn unlock_with_nothing_locked() {
 			);
 		})
 }
+
+#[test]
+fn public_prop_count_is_not_payable() {
+	ExtBuilder::default().build().execute_with(|| {
+		let input = EvmDataWriter::new()
+			.write_selector(Action::PublicPropCount)
+			.build();
+
+		let mut context = evm_test_context();
+		context.apparent_value = U256::one();
+
+		assert_eq!(
+			Precompiles::execute(precompile_address(), &input, None, &context),
+			Some(Err(error("function is not payable")))
+		);
+	});
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// @title The interface through which solidity contracts will interact with pallet-identity
+/// Read the on-chain identities of accounts and manage the identity of the caller.
+/// The structs returned by the view functions have an `isValid` field which is false when the
+/// requested item does not exist.
+interface Identity {
+    /// @dev The kind of an identity field, as in `pallet_identity::Data`.
+    enum DataKind {
+        None,
+        Raw,
+        BlakeTwo256,
+        Sha256,
+        Keccak256,
+        ShaThree256
+    }
+
+    /// @dev An identity field. Raw data is at most 32 bytes long, and hashes are 32 bytes long.
+    struct Data {
+        DataKind kind;
+        bytes value;
+    }
+
+    /// @dev An additional field of an identity.
+    struct Additional {
+        Data key;
+        Data value;
+    }
+
+    /// @dev The information of an identity. The PGP fingerprint is either empty or 20 bytes
+    /// long.
+    struct IdentityInfo {
+        Additional[] additional;
+        Data display;
+        Data legal;
+        Data web;
+        Data riot;
+        Data email;
+        bytes pgpFingerprint;
+        Data image;
+        Data twitter;
+    }
+
+    /// @dev The judgement status of an identity, as in `pallet_identity::Judgement`.
+    enum JudgementStatus {
+        Unknown,
+        FeePaid,
+        Reasonable,
+        KnownGood,
+        OutOfDate,
+        LowQuality,
+        Erroneous
+    }
+
+    /// @dev The judgement of a registrar. The fee is only set for the `FeePaid` status.
+    struct Judgement {
+        uint32 registrarIndex;
+        JudgementStatus status;
+        uint256 feePaid;
+    }
+
+    /// @dev The identity of an account and its judgements.
+    struct Registration {
+        bool isValid;
+        Judgement[] judgements;
+        uint256 deposit;
+        IdentityInfo info;
+    }
+
+    /// @dev The super account of a sub account, and the name of the sub account.
+    struct SuperOf {
+        bool isValid;
+        address account;
+        Data data;
+    }
+
+    /// @dev The sub accounts of an account and the deposit reserved for them.
+    struct SubsOf {
+        uint256 deposit;
+        address[] accounts;
+    }
+
+    /// @dev A sub account and its name.
+    struct SubAccount {
+        address account;
+        Data data;
+    }
+
+    /// @dev A registrar slot. `fields` is the bit flags of the identity fields it judges.
+    struct Registrar {
+        bool isValid;
+        uint32 index;
+        address account;
+        uint256 fee;
+        uint64 fields;
+    }
+
+    /// @dev The identity of `who` and its judgements.
+    /// Selector: f0eb5e54
+    function identity(address who) external view returns (Registration memory);
+
+    /// @dev The super account of the sub account `who`, and the name of the sub account.
+    /// Selector: c18110d6
+    function superOf(address who) external view returns (SuperOf memory);
+
+    /// @dev The sub accounts of `who` and the deposit reserved for them.
+    /// Selector: 3f08986b
+    function subsOf(address who) external view returns (SubsOf memory);
+
+    /// @dev The registrars, indexed by their registrar index.
+    /// Selector: e88e512e
+    function registrars() external view returns (Registrar[] memory);
+
+    /// @dev Set the identity of the caller, reserving a deposit for it. Existing judgements
+    /// are removed, except the final ones (`Reasonable`, `KnownGood`...).
+    /// Selector: e041e431
+    function setIdentity(IdentityInfo memory info) external;
+
+    /// @dev Set the sub accounts of the caller and their names, replacing the existing ones.
+    /// Selector: 9d53362e
+    function setSubs(SubAccount[] memory subs) external;
+
+    /// @dev Clear the identity and the sub accounts of the caller, unreserving their deposits.
+    /// Selector: 7a6a10c7
+    function clearIdentity() external;
+
+    /// @dev Request a judgement of the identity of the caller from the registrar `reg_index`,
+    /// paying its fee which must not exceed `max_fee`.
+    /// Selector: d523ceb9
+    function requestJudgement(uint32 reg_index, uint256 max_fee) external;
+}
@@ -0,0 +1,568 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to read and manage on-chain identities through pallet-identity.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{Currency, GetCallMetadata},
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{
+	error, precompile, Address, Bytes, EvmData, EvmDataReader, EvmDataWriter, EvmResult,
+	PrecompileHandle, RuntimeHelper,
+};
+use sp_core::{H160, U256};
+use sp_std::{
+	boxed::Box,
+	convert::{TryFrom, TryInto},
+	marker::PhantomData,
+	vec::Vec,
+};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type BalanceOf<Runtime> = <<Runtime as pallet_identity::Config>::Currency as Currency<
+	<Runtime as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// Implement `EvmData` for a struct by encoding it as the tuple of its fields.
+macro_rules! impl_evmdata_as_tuple {
+	($name:ident { $($field:ident: $ty:ty),+ $(,)? }) => {
+		impl EvmData for $name {
+			fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+				let ($($field,)+) = <($($ty,)+)>::read(reader)?;
+
+				Ok(Self { $($field),+ })
+			}
+
+			fn write(writer: &mut EvmDataWriter, value: Self) {
+				<($($ty,)+)>::write(writer, ($(value.$field,)+));
+			}
+
+			fn has_static_size() -> bool {
+				<($($ty,)+)>::has_static_size()
+			}
+
+			fn head_size() -> usize {
+				<($($ty,)+)>::head_size()
+			}
+		}
+	};
+}
+
+/// An identity field, the `(uint8 kind, bytes value)` struct, whose kind is the index of the
+/// variant of `pallet_identity::Data`: `None`, `Raw`, `BlakeTwo256`, `Sha256`, `Keccak256` or
+/// `ShaThree256`. Raw data is at most 32 bytes long, and hashes are 32 bytes long.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Data {
+	pub kind: u8,
+	pub value: Bytes,
+}
+
+impl_evmdata_as_tuple!(Data {
+	kind: u8,
+	value: Bytes,
+});
+
+impl Data {
+	/// Raw data of the field.
+	pub fn raw(value: &[u8]) -> Self {
+		Self {
+			kind: 1,
+			value: value.into(),
+		}
+	}
+
+	/// Hash of the field, `kind` being the index of the hash variant.
+	pub fn hash(kind: u8, hash: [u8; 32]) -> Self {
+		Self {
+			kind,
+			value: hash[..].into(),
+		}
+	}
+
+	fn from_pallet(data: pallet_identity::Data) -> Self {
+		match data {
+			pallet_identity::Data::None => Self::default(),
+			pallet_identity::Data::Raw(value) => Self::raw(&value),
+			pallet_identity::Data::BlakeTwo256(hash) => Self::hash(2, hash),
+			pallet_identity::Data::Sha256(hash) => Self::hash(3, hash),
+			pallet_identity::Data::Keccak256(hash) => Self::hash(4, hash),
+			pallet_identity::Data::ShaThree256(hash) => Self::hash(5, hash),
+		}
+	}
+
+	fn into_pallet(self) -> EvmResult<pallet_identity::Data> {
+		let hash = |value: Vec<u8>| -> EvmResult<[u8; 32]> {
+			value
+				.try_into()
+				.map_err(|_| error("hash is not 32 bytes long"))
+		};
+
+		Ok(match self.kind {
+			0 => pallet_identity::Data::None,
+			1 => {
+				if self.value.0.len() > 32 {
+					return Err(error("data is longer than 32 bytes"));
+				}
+
+				let value = self
+					.value
+					.0
+					.try_into()
+					.map_err(|_| error("data is longer than 32 bytes"))?;
+
+				pallet_identity::Data::Raw(value)
+			}
+			2 => pallet_identity::Data::BlakeTwo256(hash(self.value.0)?),
+			3 => pallet_identity::Data::Sha256(hash(self.value.0)?),
+			4 => pallet_identity::Data::Keccak256(hash(self.value.0)?),
+			5 => pallet_identity::Data::ShaThree256(hash(self.value.0)?),
+			_ => return Err(error("unknown data kind")),
+		})
+	}
+}
+
+/// The information of an identity, the struct whose fields are those of
+/// `pallet_identity::IdentityInfo`. The PGP fingerprint is either empty or 20 bytes long.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IdentityInfo {
+	pub additional: Vec<(Data, Data)>,
+	pub display: Data,
+	pub legal: Data,
+	pub web: Data,
+	pub riot: Data,
+	pub email: Data,
+	pub pgp_fingerprint: Bytes,
+	pub image: Data,
+	pub twitter: Data,
+}
+
+impl EvmData for IdentityInfo {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let mut inner = reader.read_pointer()?;
+
+		Ok(Self {
+			additional: inner.read()?,
+			display: inner.read()?,
+			legal: inner.read()?,
+			web: inner.read()?,
+			riot: inner.read()?,
+			email: inner.read()?,
+			pgp_fingerprint: inner.read()?,
+			image: inner.read()?,
+			twitter: inner.read()?,
+		})
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let inner = EvmDataWriter::new()
+			.write(value.additional)
+			.write(value.display)
+			.write(value.legal)
+			.write(value.web)
+			.write(value.riot)
+			.write(value.email)
+			.write(value.pgp_fingerprint)
+			.write(value.image)
+			.write(value.twitter);
+
+		writer.write_pointer(inner.build());
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+}
+
+/// The judgement of a registrar, whose status is the index of the variant of
+/// `pallet_identity::Judgement`. The fee is only set for the `FeePaid` status.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Judgement {
+	pub registrar_index: u32,
+	pub status: u8,
+	pub fee_paid: U256,
+}
+
+impl_evmdata_as_tuple!(Judgement {
+	registrar_index: u32,
+	status: u8,
+	fee_paid: U256,
+});
+
+/// The identity of an account, which is not valid if the account has no identity.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Registration {
+	pub is_valid: bool,
+	pub judgements: Vec<Judgement>,
+	pub deposit: U256,
+	pub info: IdentityInfo,
+}
+
+impl_evmdata_as_tuple!(Registration {
+	is_valid: bool,
+	judgements: Vec<Judgement>,
+	deposit: U256,
+	info: IdentityInfo,
+});
+
+/// The super account of a sub account, which is not valid if the account has no super account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuperOf {
+	pub is_valid: bool,
+	pub account: Address,
+	pub data: Data,
+}
+
+impl_evmdata_as_tuple!(SuperOf {
+	is_valid: bool,
+	account: Address,
+	data: Data,
+});
+
+/// The sub accounts of an account and the deposit reserved for them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SubsOf {
+	pub deposit: U256,
+	pub accounts: Vec<Address>,
+}
+
+impl_evmdata_as_tuple!(SubsOf {
+	deposit: U256,
+	accounts: Vec<Address>,
+});
+
+/// A registrar slot, which is not valid if the registrar has been removed.
+/// `fields` is the bit flags of the `pallet_identity::IdentityField`s it judges.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Registrar {
+	pub is_valid: bool,
+	pub index: u32,
+	pub account: Address,
+	pub fee: U256,
+	pub fields: u64,
+}
+
+impl_evmdata_as_tuple!(Registrar {
+	is_valid: bool,
+	index: u32,
+	account: Address,
+	fee: U256,
+	fields: u64,
+});
+
+/// A precompile to wrap the functionality from pallet-identity.
+/// It must be installed with `precompile_utils::check_not_delegated`, as the identity of the
+/// caller is managed.
+pub struct IdentityWrapper<Runtime>(PhantomData<Runtime>);
+
+/// Read the on-chain identities of accounts and manage the identity of the caller.
+/// The structs returned by the view functions have an `isValid` field which is false when the
+/// requested item does not exist.
+#[precompile(interface = "Identity")]
+impl<Runtime> IdentityWrapper<Runtime>
+where
+	Runtime: pallet_identity::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::AccountId: Into<H160>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_identity::Call<Runtime>>,
+	BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+	/// The identity of `who` and its judgements.
+	#[precompile::public("identity(address)")]
+	#[precompile::returns(
+		"(bool,(uint32,uint8,uint256)[],uint256,(((uint8,bytes),(uint8,bytes))[],(uint8,bytes),\
+		(uint8,bytes),(uint8,bytes),(uint8,bytes),(uint8,bytes),bytes,(uint8,bytes),(uint8,bytes)))"
+	)]
+	#[precompile::view]
+	fn identity(handle: &mut PrecompileHandle, who: Address) -> EvmResult<Registration> {
+		let who = Runtime::AddressMapping::into_account_id(who.0);
+
+		let registration =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_identity::Pallet::<Runtime>::identity(&who))
+			})?;
+
+		Ok(match registration {
+			Some(registration) => Registration {
+				is_valid: true,
+				judgements: registration
+					.judgements
+					.into_iter()
+					.map(|(index, judgement)| Self::judgement_from_pallet(index, judgement))
+					.collect(),
+				deposit: registration.deposit.into(),
+				info: Self::identity_info_from_pallet(registration.info),
+			},
+			None => Registration::default(),
+		})
+	}
+
+	/// The super account of the sub account `who`, and the name of the sub account.
+	#[precompile::public("superOf(address)")]
+	#[precompile::returns("(bool,address,(uint8,bytes))")]
+	#[precompile::view]
+	fn super_of(handle: &mut PrecompileHandle, who: Address) -> EvmResult<SuperOf> {
+		let who = Runtime::AddressMapping::into_account_id(who.0);
+
+		let super_of =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_identity::Pallet::<Runtime>::super_of(&who))
+			})?;
+
+		Ok(match super_of {
+			Some((account, data)) => SuperOf {
+				is_valid: true,
+				account: Address(account.into()),
+				data: Data::from_pallet(data),
+			},
+			None => SuperOf {
+				is_valid: false,
+				account: Address(H160::zero()),
+				data: Data::default(),
+			},
+		})
+	}
+
+	/// The sub accounts of `who` and the deposit reserved for them.
+	#[precompile::public("subsOf(address)")]
+	#[precompile::returns("(uint256,address[])")]
+	#[precompile::view]
+	fn subs_of(handle: &mut PrecompileHandle, who: Address) -> EvmResult<SubsOf> {
+		let who = Runtime::AddressMapping::into_account_id(who.0);
+
+		let (deposit, accounts) =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_identity::Pallet::<Runtime>::subs_of(&who))
+			})?;
+
+		Ok(SubsOf {
+			deposit: deposit.into(),
+			accounts: accounts
+				.into_iter()
+				.map(|account| Address(account.into()))
+				.collect(),
+		})
+	}
+
+	/// The registrars, indexed by their registrar index.
+	#[precompile::public("registrars()")]
+	#[precompile::returns("(bool,uint32,address,uint256,uint64)[]")]
+	#[precompile::view]
+	fn registrars(handle: &mut PrecompileHandle) -> EvmResult<Vec<Registrar>> {
+		let registrars =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_identity::Pallet::<Runtime>::registrars())
+			})?;
+
+		Ok(registrars
+			.into_iter()
+			.enumerate()
+			.map(|(index, registrar)| match registrar {
+				Some(registrar) => Registrar {
+					is_valid: true,
+					index: index as u32,
+					account: Address(registrar.account.into()),
+					fee: registrar.fee.into(),
+					// The bit flags are not exposed, but are encoded as an `u64`.
+					fields: u64::decode(&mut &registrar.fields.encode()[..]).unwrap_or_default(),
+				},
+				None => Registrar {
+					is_valid: false,
+					index: index as u32,
+					account: Address(H160::zero()),
+					fee: U256::zero(),
+					fields: 0,
+				},
+			})
+			.collect())
+	}
+
+	/// Set the identity of the caller, reserving a deposit for it. Existing judgements are
+	/// removed, except the final ones (`Reasonable`, `KnownGood`...).
+	#[precompile::public(
+		"setIdentity((((uint8,bytes),(uint8,bytes))[],(uint8,bytes),(uint8,bytes),(uint8,bytes),\
+		(uint8,bytes),(uint8,bytes),bytes,(uint8,bytes),(uint8,bytes)))"
+	)]
+	fn set_identity(handle: &mut PrecompileHandle, info: IdentityInfo) -> EvmResult {
+		let info = Self::identity_info_into_pallet(info)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_identity::Call::<Runtime>::set_identity(Box::new(info));
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Set the sub accounts of the caller and their names, replacing the existing ones.
+	#[precompile::public("setSubs((address,(uint8,bytes))[])")]
+	fn set_subs(handle: &mut PrecompileHandle, subs: Vec<(Address, Data)>) -> EvmResult {
+		let subs = subs
+			.into_iter()
+			.map(|(account, data)| {
+				Ok((
+					Runtime::AddressMapping::into_account_id(account.0),
+					data.into_pallet()?,
+				))
+			})
+			.collect::<EvmResult<Vec<_>>>()?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_identity::Call::<Runtime>::set_subs(subs);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Clear the identity and the sub accounts of the caller, unreserving their deposits.
+	#[precompile::public("clearIdentity()")]
+	fn clear_identity(handle: &mut PrecompileHandle) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_identity::Call::<Runtime>::clear_identity();
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Request a judgement of the identity of the caller from the registrar `reg_index`, paying
+	/// its fee which must not exceed `max_fee`.
+	#[precompile::public("requestJudgement(uint32,uint256)")]
+	fn request_judgement(
+		handle: &mut PrecompileHandle,
+		reg_index: u32,
+		max_fee: U256,
+	) -> EvmResult {
+		let max_fee: BalanceOf<Runtime> = max_fee
+			.try_into()
+			.map_err(|_| error("amount is too large for provided balance type"))?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_identity::Call::<Runtime>::request_judgement(reg_index, max_fee);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	fn identity_info_from_pallet(
+		info: pallet_identity::IdentityInfo<Runtime::MaxAdditionalFields>,
+	) -> IdentityInfo {
+		IdentityInfo {
+			additional: info
+				.additional
+				.into_iter()
+				.map(|(key, value)| (Data::from_pallet(key), Data::from_pallet(value)))
+				.collect(),
+			display: Data::from_pallet(info.display),
+			legal: Data::from_pallet(info.legal),
+			web: Data::from_pallet(info.web),
+			riot: Data::from_pallet(info.riot),
+			email: Data::from_pallet(info.email),
+			pgp_fingerprint: info
+				.pgp_fingerprint
+				.map(|fingerprint| Bytes(fingerprint.to_vec()))
+				.unwrap_or_default(),
+			image: Data::from_pallet(info.image),
+			twitter: Data::from_pallet(info.twitter),
+		}
+	}
+
+	fn identity_info_into_pallet(
+		info: IdentityInfo,
+	) -> EvmResult<pallet_identity::IdentityInfo<Runtime::MaxAdditionalFields>> {
+		let additional = info
+			.additional
+			.into_iter()
+			.map(|(key, value)| Ok((key.into_pallet()?, value.into_pallet()?)))
+			.collect::<EvmResult<Vec<_>>>()?;
+
+		let pgp_fingerprint = match info.pgp_fingerprint.0.len() {
+			0 => None,
+			_ => Some(
+				info.pgp_fingerprint
+					.0
+					.try_into()
+					.map_err(|_| error("pgp fingerprint is not 20 bytes long"))?,
+			),
+		};
+
+		Ok(pallet_identity::IdentityInfo {
+			additional: additional
+				.try_into()
+				.map_err(|_| error("too many additional fields"))?,
+			display: info.display.into_pallet()?,
+			legal: info.legal.into_pallet()?,
+			web: info.web.into_pallet()?,
+			riot: info.riot.into_pallet()?,
+			email: info.email.into_pallet()?,
+			pgp_fingerprint,
+			image: info.image.into_pallet()?,
+			twitter: info.twitter.into_pallet()?,
+		})
+	}
+
+	fn judgement_from_pallet(
+		registrar_index: u32,
+		judgement: pallet_identity::Judgement<BalanceOf<Runtime>>,
+	) -> Judgement {
+		let (status, fee_paid) = match judgement {
+			pallet_identity::Judgement::Unknown => (0, U256::zero()),
+			pallet_identity::Judgement::FeePaid(fee) => (1, fee.into()),
+			pallet_identity::Judgement::Reasonable => (2, U256::zero()),
+			pallet_identity::Judgement::KnownGood => (3, U256::zero()),
+			pallet_identity::Judgement::OutOfDate => (4, U256::zero()),
+			pallet_identity::Judgement::LowQuality => (5, U256::zero()),
+			pallet_identity::Judgement::Erroneous => (6, U256::zero()),
+		};
+
+		Judgement {
+			registrar_index,
+			status,
+			fee_paid,
+		}
+	}
+}
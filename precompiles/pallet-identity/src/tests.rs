@@ -0,0 +1,543 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::Context;
+use frame_support::assert_ok;
+use pallet_evm::PrecompileSet;
+use precompile_utils::{testing::PrecompileTesterExt, EvmDataWriter};
+use sha3::{Digest, Keccak256};
+
+fn identity_info() -> IdentityInfo {
+	IdentityInfo {
+		additional: vec![(Data::raw(b"discord"), Data::raw(b"alice#1234"))],
+		display: Data::raw(b"Alice"),
+		email: Data::raw(b"alice@example.com"),
+		pgp_fingerprint: Bytes(vec![0x42; 20]),
+		..Default::default()
+	}
+}
+
+fn set_identity_input(info: IdentityInfo) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::SetIdentity as u32)
+		.write(info)
+		.build()
+}
+
+fn identity_input(who: Account) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::Identity as u32)
+		.write(Address(who.into()))
+		.build()
+}
+
+fn set_identity(who: Account) {
+	Precompiles::<Runtime>::prepare_test(
+		who,
+		Account::Precompile,
+		set_identity_input(identity_info()),
+	)
+	.execute_stops();
+}
+
+/// A registrar with a fee of 10, judging the identities.
+fn add_registrar(registrar: Account) {
+	assert_ok!(Identity::add_registrar(Origin::root(), registrar.clone()));
+	assert_ok!(Identity::set_fee(Origin::signed(registrar), 0, 10));
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::Identity as u32, 0xf0eb5e54);
+	assert_eq!(Action::SuperOf as u32, 0xc18110d6);
+	assert_eq!(Action::SubsOf as u32, 0x3f08986b);
+	assert_eq!(Action::Registrars as u32, 0xe88e512e);
+	assert_eq!(Action::SetIdentity as u32, 0xe041e431);
+	assert_eq!(Action::SetSubs as u32, 0x9d53362e);
+	assert_eq!(Action::ClearIdentity as u32, 0x7a6a10c7);
+	assert_eq!(Action::RequestJudgement as u32, 0xd523ceb9);
+}
+
+#[test]
+fn selectors_match_the_solidity_interface() {
+	let selector = |signature: &str| {
+		let mut buffer = [0u8; 4];
+		buffer.copy_from_slice(&Keccak256::digest(signature.as_bytes())[0..4]);
+		u32::from_be_bytes(buffer)
+	};
+
+	// Solidity encodes the `DataKind` enum of the `Data` struct as an `uint8`.
+	let data = "(uint8,bytes)";
+	let identity_info = format!(
+		"(({data},{data})[],{data},{data},{data},{data},{data},bytes,{data},{data})",
+		data = data
+	);
+
+	assert_eq!(
+		selector(&format!("setIdentity({})", identity_info)),
+		0xe041e431
+	);
+	assert_eq!(
+		selector(&format!("setSubs((address,{})[])", data)),
+		0x9d53362e
+	);
+	assert_eq!(
+		Action::SetIdentity as u32,
+		selector(&format!("setIdentity({})", identity_info))
+	);
+	assert_eq!(
+		Action::SetSubs as u32,
+		selector(&format!("setSubs((address,{})[])", data))
+	);
+}
+
+#[test]
+fn identity_is_not_valid_without_identity() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Bob,
+			Account::Precompile,
+			identity_input(Account::Alice),
+		)
+		.execute_returns_encoded(Registration::default());
+	});
+}
+
+#[test]
+fn set_identity_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			set_identity(Account::Alice);
+
+			// Basic deposit and one additional field.
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 110);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				identity_input(Account::Alice),
+			)
+			.execute_returns_encoded(Registration {
+				is_valid: true,
+				judgements: vec![],
+				deposit: 110.into(),
+				info: identity_info(),
+			});
+		});
+}
+
+#[test]
+fn set_identity_rejects_too_long_data() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let info = IdentityInfo {
+				display: Data::raw(&[b'a'; 33]),
+				..Default::default()
+			};
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				set_identity_input(info),
+			)
+			.execute_reverts(|output| output == "data is longer than 32 bytes");
+		});
+}
+
+#[test]
+fn set_identity_keeps_the_kind_of_hashed_data() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let info = IdentityInfo {
+				display: Data::raw(b"Alice"),
+				legal: Data::hash(2, [0x22; 32]),
+				web: Data::hash(3, [0x33; 32]),
+				riot: Data::hash(4, [0x44; 32]),
+				email: Data::hash(5, [0x55; 32]),
+				..Default::default()
+			};
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				set_identity_input(info.clone()),
+			)
+			.execute_stops();
+
+			assert_eq!(
+				Identity::identity(&Account::Alice).unwrap().info.riot,
+				pallet_identity::Data::Keccak256([0x44; 32])
+			);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				identity_input(Account::Alice),
+			)
+			.execute_returns_encoded(Registration {
+				is_valid: true,
+				judgements: vec![],
+				deposit: 100.into(),
+				info,
+			});
+		});
+}
+
+#[test]
+fn set_identity_rejects_invalid_data_kinds() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			for (display, reason) in [
+				(
+					Data {
+						kind: 6,
+						value: Bytes(vec![]),
+					},
+					"unknown data kind",
+				),
+				(
+					Data {
+						kind: 2,
+						value: Bytes(vec![0x22; 31]),
+					},
+					"hash is not 32 bytes long",
+				),
+			] {
+				let info = IdentityInfo {
+					display,
+					..Default::default()
+				};
+
+				Precompiles::<Runtime>::prepare_test(
+					Account::Alice,
+					Account::Precompile,
+					set_identity_input(info),
+				)
+				.execute_reverts(|output| output == reason);
+			}
+
+			assert!(Identity::identity(&Account::Alice).is_none());
+		});
+}
+
+#[test]
+fn set_identity_rejects_invalid_pgp_fingerprint() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let info = IdentityInfo {
+				pgp_fingerprint: Bytes(vec![0x42; 19]),
+				..Default::default()
+			};
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				set_identity_input(info),
+			)
+			.execute_reverts(|output| output == "pgp fingerprint is not 20 bytes long");
+		});
+}
+
+#[test]
+fn set_identity_rejects_too_many_additional_fields() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let info = IdentityInfo {
+				additional: vec![(Data::raw(b"key"), Data::raw(b"value")); 3],
+				..Default::default()
+			};
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				set_identity_input(info),
+			)
+			.execute_reverts(|output| output == "too many additional fields");
+		});
+}
+
+#[test]
+fn set_subs_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			set_identity(Account::Alice);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::SetSubs as u32)
+					.write(vec![
+						(Address(Account::Bob.into()), Data::raw(b"bob")),
+						(Address(Account::Charlie.into()), Data::default()),
+					])
+					.build(),
+			)
+			.execute_stops();
+
+			// Identity deposit and two sub accounts.
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 150);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::SubsOf as u32)
+					.write(Address(Account::Alice.into()))
+					.build(),
+			)
+			.execute_returns_encoded(SubsOf {
+				deposit: 40.into(),
+				accounts: vec![
+					Address(Account::Bob.into()),
+					Address(Account::Charlie.into()),
+				],
+			});
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::SuperOf as u32)
+					.write(Address(Account::Bob.into()))
+					.build(),
+			)
+			.execute_returns_encoded(SuperOf {
+				is_valid: true,
+				account: Address(Account::Alice.into()),
+				data: Data::raw(b"bob"),
+			});
+		});
+}
+
+#[test]
+fn super_of_is_not_valid_without_super_account() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Bob,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::SuperOf as u32)
+				.write(Address(Account::Bob.into()))
+				.build(),
+		)
+		.execute_returns_encoded(SuperOf {
+			is_valid: false,
+			account: Address(H160::zero()),
+			data: Data::default(),
+		});
+	});
+}
+
+#[test]
+fn set_subs_fails_without_identity() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::SetSubs as u32)
+					.write(vec![(Address(Account::Bob.into()), Data::raw(b"bob"))])
+					.build(),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call Identity::set_subs failed")
+			});
+		});
+}
+
+#[test]
+fn clear_identity_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			set_identity(Account::Alice);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::ClearIdentity as u32).build(),
+			)
+			.execute_stops();
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				identity_input(Account::Alice),
+			)
+			.execute_returns_encoded(Registration::default());
+		});
+}
+
+#[test]
+fn request_judgement_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			add_registrar(Account::Charlie);
+			set_identity(Account::Alice);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::RequestJudgement as u32)
+					.write(0u32)
+					.write(U256::from(10))
+					.build(),
+			)
+			.execute_stops();
+
+			// Identity deposit and registrar fee.
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 120);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				identity_input(Account::Alice),
+			)
+			.execute_returns_encoded(Registration {
+				is_valid: true,
+				judgements: vec![Judgement {
+					registrar_index: 0,
+					status: 1,
+					fee_paid: 10.into(),
+				}],
+				deposit: 110.into(),
+				info: identity_info(),
+			});
+
+			assert_ok!(Identity::provide_judgement(
+				Origin::signed(Account::Charlie),
+				0,
+				Account::Alice,
+				pallet_identity::Judgement::Reasonable,
+			));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				identity_input(Account::Alice),
+			)
+			.execute_returns_encoded(Registration {
+				is_valid: true,
+				judgements: vec![Judgement {
+					registrar_index: 0,
+					status: 2,
+					fee_paid: U256::zero(),
+				}],
+				deposit: 110.into(),
+				info: identity_info(),
+			});
+		});
+}
+
+#[test]
+fn request_judgement_fails_if_fee_is_above_max_fee() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			add_registrar(Account::Charlie);
+			set_identity(Account::Alice);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::RequestJudgement as u32)
+					.write(0u32)
+					.write(U256::from(9))
+					.build(),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call Identity::request_judgement failed")
+			});
+		});
+}
+
+#[test]
+fn registrars_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_registrar(Account::Charlie);
+		assert_ok!(Identity::add_registrar(Origin::root(), Account::Bob));
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::Registrars as u32).build(),
+		)
+		.execute_returns_encoded(vec![
+			Registrar {
+				is_valid: true,
+				index: 0,
+				account: Address(Account::Charlie.into()),
+				fee: 10.into(),
+				fields: 0,
+			},
+			Registrar {
+				is_valid: true,
+				index: 1,
+				account: Address(Account::Bob.into()),
+				fee: U256::zero(),
+				fields: 0,
+			},
+		]);
+	});
+}
+
+#[test]
+fn identity_cannot_be_delegated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context is the one of the calling contract (Charlie), while
+			// the caller is the account which called it (Alice).
+			let context = Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&set_identity_input(identity_info()),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert!(Identity::identity(&Account::Alice).is_none());
+		});
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Propose spendings of the treasury and read its proposals, approvals and balance.
+/// This file is generated from the Rust implementation of the precompile.
+interface Treasury {
+    /// Propose to spend `value` from the treasury for `beneficiary`. A bond proportional to
+    /// `value` is reserved from the caller, and is slashed if the proposal is rejected.
+    /// Selector: 76fd21a6
+    function proposeSpend(uint256 value, address beneficiary) external;
+
+    /// The number of proposals that have been made, which is also the index of the next one.
+    /// Selector: da35c664
+    function proposalCount() external view returns (uint32);
+
+    /// The proposal `proposal_index` as `(isValid, proposer, value, beneficiary, bond)`.
+    /// It is not valid if the proposal does not exist, or no longer exists once it has been
+    /// rejected or paid out.
+    /// Selector: 2a4910ab
+    function proposals(uint32 proposal_index) external view returns (bool, address, uint256, address, uint256);
+
+    /// The indices of the approved proposals, which are paid out at the next spend period.
+    /// Selector: b05dba96
+    function approvals() external view returns (uint32[] memory);
+
+    /// The free balance of the treasury account.
+    /// Selector: b69ef8a8
+    function balance() external view returns (uint256);
+}
@@ -0,0 +1,177 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to propose treasury spendings and read the state of the treasury through
+//! pallet-treasury.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::traits::StaticLookup,
+	traits::{Currency, GetCallMetadata},
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{error, precompile, Address, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_core::{H160, U256};
+use sp_std::{
+	convert::{TryFrom, TryInto},
+	marker::PhantomData,
+	vec::Vec,
+};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type BalanceOf<Runtime> = <<Runtime as pallet_treasury::Config>::Currency as Currency<
+	<Runtime as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// Same layout as `pallet_treasury::Proposal`, whose fields are private. It is decoded from the
+/// SCALE encoding of the pallet proposal.
+#[derive(Decode)]
+struct Proposal<AccountId, Balance> {
+	proposer: AccountId,
+	value: Balance,
+	beneficiary: AccountId,
+	bond: Balance,
+}
+
+/// A precompile to wrap the functionality from pallet-treasury.
+/// It must be installed with `precompile_utils::check_not_delegated`, as spends are proposed on
+/// behalf of the caller, reserving its bond.
+pub struct TreasuryWrapper<Runtime>(PhantomData<Runtime>);
+
+/// Propose spendings of the treasury and read its proposals, approvals and balance.
+#[precompile(interface = "Treasury")]
+impl<Runtime> TreasuryWrapper<Runtime>
+where
+	Runtime: pallet_treasury::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::AccountId: Into<H160>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_treasury::Call<Runtime>>,
+	BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+	/// Propose to spend `value` from the treasury for `beneficiary`. A bond proportional to
+	/// `value` is reserved from the caller, and is slashed if the proposal is rejected.
+	#[precompile::public("proposeSpend(uint256,address)")]
+	fn propose_spend(
+		handle: &mut PrecompileHandle,
+		value: U256,
+		beneficiary: Address,
+	) -> EvmResult {
+		let value: BalanceOf<Runtime> = value
+			.try_into()
+			.map_err(|_| error("amount is too large for provided balance type"))?;
+		let beneficiary = Runtime::AddressMapping::into_account_id(beneficiary.0);
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_treasury::Call::<Runtime>::propose_spend(
+			value,
+			Runtime::Lookup::unlookup(beneficiary),
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// The number of proposals that have been made, which is also the index of the next one.
+	#[precompile::public("proposalCount()")]
+	#[precompile::view]
+	fn proposal_count(handle: &mut PrecompileHandle) -> EvmResult<u32> {
+		RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+			reads.read(|| pallet_treasury::Pallet::<Runtime>::proposal_count())
+		})
+	}
+
+	/// The proposal `proposal_index` as `(isValid, proposer, value, beneficiary, bond)`.
+	/// It is not valid if the proposal does not exist, or no longer exists once it has been
+	/// rejected or paid out.
+	#[precompile::public("proposals(uint32)")]
+	#[precompile::view]
+	fn proposals(
+		handle: &mut PrecompileHandle,
+		proposal_index: u32,
+	) -> EvmResult<(bool, Address, U256, Address, U256)> {
+		let proposal =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_treasury::Pallet::<Runtime>::proposals(proposal_index))
+			})?;
+
+		let proposal = match proposal {
+			Some(proposal) => proposal,
+			None => {
+				return Ok((
+					false,
+					Address(H160::zero()),
+					U256::zero(),
+					Address(H160::zero()),
+					U256::zero(),
+				))
+			}
+		};
+
+		let proposal =
+			Proposal::<Runtime::AccountId, BalanceOf<Runtime>>::decode(&mut &proposal.encode()[..])
+				.map_err(|_| error("failed decoding proposal"))?;
+
+		Ok((
+			true,
+			Address(proposal.proposer.into()),
+			proposal.value.into(),
+			Address(proposal.beneficiary.into()),
+			proposal.bond.into(),
+		))
+	}
+
+	/// The indices of the approved proposals, which are paid out at the next spend period.
+	#[precompile::public("approvals()")]
+	#[precompile::view]
+	fn approvals(handle: &mut PrecompileHandle) -> EvmResult<Vec<u32>> {
+		let approvals =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_treasury::Pallet::<Runtime>::approvals())
+			})?;
+
+		Ok(approvals.iter().copied().collect())
+	}
+
+	/// The free balance of the treasury account.
+	#[precompile::public("balance()")]
+	#[precompile::view]
+	fn balance(handle: &mut PrecompileHandle) -> EvmResult<U256> {
+		let balance =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| {
+					<Runtime as pallet_treasury::Config>::Currency::free_balance(
+						&pallet_treasury::Pallet::<Runtime>::account_id(),
+					)
+				})
+			})?;
+
+		Ok(balance.into())
+	}
+}
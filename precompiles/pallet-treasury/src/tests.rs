@@ -0,0 +1,209 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::Context;
+use frame_support::assert_ok;
+use pallet_evm::PrecompileSet;
+use precompile_utils::{
+	testing::{check_solidity_interface, PrecompileTesterExt},
+	EvmDataWriter,
+};
+
+fn propose_spend_input(value: U256, beneficiary: Account) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::ProposeSpend as u32)
+		.write(value)
+		.write(Address(beneficiary.into()))
+		.build()
+}
+
+fn proposals_input(proposal_index: u32) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::Proposals as u32)
+		.write(proposal_index)
+		.build()
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::ProposeSpend as u32, 0x76fd21a6);
+	assert_eq!(Action::ProposalCount as u32, 0xda35c664);
+	assert_eq!(Action::Proposals as u32, 0x2a4910ab);
+	assert_eq!(Action::Approvals as u32, 0xb05dba96);
+	assert_eq!(Action::Balance as u32, 0xb69ef8a8);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "Treasury.sol");
+}
+
+#[test]
+fn propose_spend_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				propose_spend_input(400.into(), Account::Bob),
+			)
+			.execute_stops();
+
+			// 5% of the value.
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 20);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::ProposalCount as u32).build(),
+			)
+			.execute_returns_encoded(1u32);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				proposals_input(0),
+			)
+			.execute_returns_encoded((
+				true,
+				Address(Account::Alice.into()),
+				U256::from(400),
+				Address(Account::Bob.into()),
+				U256::from(20),
+			));
+		});
+}
+
+#[test]
+fn propose_spend_fails_without_bond() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 9)])
+		.build()
+		.execute_with(|| {
+			// The bond is at least 10.
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				propose_spend_input(100.into(), Account::Bob),
+			)
+			.execute_reverts_with_output(EvmDataWriter::revert_reason(
+				"Dispatched call Treasury::propose_spend failed: InsufficientProposersBalance",
+			));
+		});
+}
+
+#[test]
+fn propose_spend_rejects_too_large_value() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				propose_spend_input(U256::MAX, Account::Bob),
+			)
+			.execute_reverts_with_output(EvmDataWriter::revert_reason(
+				"amount is too large for provided balance type",
+			));
+		});
+}
+
+#[test]
+fn proposals_is_not_valid_without_proposal() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(Account::Bob, Account::Precompile, proposals_input(0))
+			.execute_returns_encoded((
+				false,
+				Address(H160::zero()),
+				U256::zero(),
+				Address(H160::zero()),
+				U256::zero(),
+			));
+	});
+}
+
+#[test]
+fn approvals_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			for _ in 0..3 {
+				Precompiles::<Runtime>::prepare_test(
+					Account::Alice,
+					Account::Precompile,
+					propose_spend_input(100.into(), Account::Bob),
+				)
+				.execute_stops();
+			}
+
+			assert_ok!(Treasury::approve_proposal(Origin::root(), 2));
+			assert_ok!(Treasury::approve_proposal(Origin::root(), 0));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::Approvals as u32).build(),
+			)
+			.execute_returns_encoded(vec![2u32, 0u32]);
+		});
+}
+
+#[test]
+fn balance_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		Balances::make_free_balance_be(&Treasury::account_id(), 1000);
+
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::Balance as u32).build(),
+		)
+		.execute_returns_encoded(U256::from(1000));
+	});
+}
+
+#[test]
+fn treasury_cannot_be_delegated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context is the one of the calling contract (Charlie), while
+			// the caller is the account which called it (Alice).
+			let context = Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&propose_spend_input(400.into(), Account::Bob),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+		});
+}
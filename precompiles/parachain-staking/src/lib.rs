@@ -25,11 +25,12 @@ mod tests;
 
 use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
-use frame_support::traits::{Currency, Get};
+use frame_support::traits::{Currency, Get, GetCallMetadata};
 use pallet_evm::AddressMapping;
 use pallet_evm::Precompile;
 use precompile_utils::{
-	error, Address, EvmData, EvmDataReader, EvmDataWriter, Gasometer, RuntimeHelper,
+	check_function_modifier, error, Address, EvmData, EvmDataReader, EvmDataWriter, Gasometer,
+	RuntimeHelper,
 };
 use sp_std::convert::TryInto;
 use sp_std::fmt::Debug;
@@ -43,13 +44,21 @@ type BalanceOf<Runtime> = <<Runtime as parachain_staking::Config>::Currency as C
 #[precompile_utils::generate_function_selector]
 #[derive(Debug, PartialEq, num_enum::TryFromPrimitive)]
 enum Action {
+	#[view]
 	MinNomination = "min_nomination()",
+	#[view]
 	Points = "points(uint256)",
+	#[view]
 	CandidateCount = "candidate_count()",
+	#[view]
 	CollatorNominationCount = "collator_nomination_count(address)",
+	#[view]
 	NominatorNominationCount = "nominator_nomination_count(address)",
+	#[view]
 	IsNominator = "is_nominator(address)",
+	#[view]
 	IsCandidate = "is_candidate(address)",
+	#[view]
 	IsSelectedCandidate = "is_selected_candidate(address)",
 	JoinCandidates = "join_candidates(uint256,uint256)",
 	LeaveCandidates = "leave_candidates(uint256)",
@@ -77,7 +86,7 @@ impl<Runtime> Precompile for ParachainStakingWrapper<Runtime>
 where
 	Runtime: parachain_staking::Config + pallet_evm::Config,
 	BalanceOf<Runtime>: EvmData,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<parachain_staking::Call<Runtime>>,
 {
@@ -85,10 +94,35 @@ where
 		input: &[u8], //Reminder this is big-endian
 		target_gas: Option<u64>,
 		context: &Context,
+	) -> Result<PrecompileOutput, ExitError> {
+		// The EVM version currently used doesn't provide `is_static` to precompiles.
+		Self::execute_with_static(input, target_gas, context, false)
+	}
+}
+
+impl<Runtime> ParachainStakingWrapper<Runtime>
+where
+	Runtime: parachain_staking::Config + pallet_evm::Config,
+	BalanceOf<Runtime>: EvmData,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<parachain_staking::Call<Runtime>>,
+{
+	/// Execute the precompile, `is_static` being whether it is called in a static context
+	/// (STATICCALL).
+	pub fn execute_with_static(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
 	) -> Result<PrecompileOutput, ExitError> {
 		let mut input = EvmDataReader::new(input);
+		let selector = input.read_selector()?;
+
+		check_function_modifier(context, is_static, selector.modifier())?;
+
 		// Return early if storage getter; return (origin, call) if dispatchable
-		let (origin, call) = match &input.read_selector()? {
+		let (origin, call) = match selector {
 			// constants
 			Action::MinNomination => return Self::min_nomination(target_gas),
 			// storage getters
@@ -136,7 +170,7 @@ impl<Runtime> ParachainStakingWrapper<Runtime>
 where
 	Runtime: parachain_staking::Config + pallet_evm::Config,
 	BalanceOf<Runtime>: EvmData,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<parachain_staking::Call<Runtime>>,
 {
@@ -145,13 +179,18 @@ where
 	fn min_nomination(target_gas: Option<u64>) -> Result<PrecompileOutput, ExitError> {
 		let mut gasometer = Gasometer::new(target_gas);
 
-		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let min_nomination: u128 = <<Runtime as parachain_staking::Config>::MinNomination as Get<
-			BalanceOf<Runtime>,
-		>>::get()
-		.try_into()
-		.map_err(|_| error("Amount is too large for provided balance type"))?;
+		// Fetch info. The constant may be stored (eg. a `storage` parameter type), so it is
+		// charged as a read.
+		let min_nomination: u128 =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| {
+					<<Runtime as parachain_staking::Config>::MinNomination as Get<
+						BalanceOf<Runtime>,
+					>>::get()
+				})
+			})?
+			.try_into()
+			.map_err(|_| error("Amount is too large for provided balance type"))?;
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -175,8 +214,9 @@ where
 		let round = input.read::<u32>()?;
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let points: u32 = parachain_staking::Pallet::<Runtime>::points(round);
+		let points: u32 = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| parachain_staking::Pallet::<Runtime>::points(round))
+		})?;
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -191,8 +231,10 @@ where
 		let mut gasometer = Gasometer::new(target_gas);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let candidate_count: u32 = <parachain_staking::Pallet<Runtime>>::candidate_pool()
+		let candidate_count: u32 =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| <parachain_staking::Pallet<Runtime>>::candidate_pool())
+			})?
 			.0
 			.len() as u32;
 
@@ -216,25 +258,26 @@ where
 		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let result =
-			if let Some(state) = <parachain_staking::Pallet<Runtime>>::collator_state2(&address) {
-				let collator_nomination_count: u32 = state.nominators.0.len() as u32;
-
-				log::trace!(
-					target: "staking-precompile",
-					"Result from pallet is {:?}",
-					collator_nomination_count
-				);
+		let state = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| <parachain_staking::Pallet<Runtime>>::collator_state2(&address))
+		})?;
+		let result = if let Some(state) = state {
+			let collator_nomination_count: u32 = state.nominators.0.len() as u32;
+
+			log::trace!(
+				target: "staking-precompile",
+				"Result from pallet is {:?}",
 				collator_nomination_count
-			} else {
-				log::trace!(
-					target: "staking-precompile",
-					"Collator {:?} not found, so nomination count is 0",
-					address
-				);
-				0u32
-			};
+			);
+			collator_nomination_count
+		} else {
+			log::trace!(
+				target: "staking-precompile",
+				"Collator {:?} not found, so nomination count is 0",
+				address
+			);
+			0u32
+		};
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -256,26 +299,27 @@ where
 		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let result =
-			if let Some(state) = <parachain_staking::Pallet<Runtime>>::nominator_state2(&address) {
-				let nominator_nomination_count: u32 = state.nominations.0.len() as u32;
-
-				log::trace!(
-					target: "staking-precompile",
-					"Result from pallet is {:?}",
-					nominator_nomination_count
-				);
-
+		let state = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| <parachain_staking::Pallet<Runtime>>::nominator_state2(&address))
+		})?;
+		let result = if let Some(state) = state {
+			let nominator_nomination_count: u32 = state.nominations.0.len() as u32;
+
+			log::trace!(
+				target: "staking-precompile",
+				"Result from pallet is {:?}",
 				nominator_nomination_count
-			} else {
-				log::trace!(
-					target: "staking-precompile",
-					"Nominator {:?} not found, so nomination count is 0",
-					address
-				);
-				0u32
-			};
+			);
+
+			nominator_nomination_count
+		} else {
+			log::trace!(
+				target: "staking-precompile",
+				"Nominator {:?} not found, so nomination count is 0",
+				address
+			);
+			0u32
+		};
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -299,8 +343,10 @@ where
 		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let is_nominator = parachain_staking::Pallet::<Runtime>::is_nominator(&address);
+		let is_nominator =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| parachain_staking::Pallet::<Runtime>::is_nominator(&address))
+			})?;
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -322,8 +368,10 @@ where
 		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let is_candidate = parachain_staking::Pallet::<Runtime>::is_candidate(&address);
+		let is_candidate =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+				reads.read(|| parachain_staking::Pallet::<Runtime>::is_candidate(&address))
+			})?;
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -345,8 +393,9 @@ where
 		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let is_selected = parachain_staking::Pallet::<Runtime>::is_selected_candidate(&address);
+		let is_selected = RuntimeHelper::<Runtime>::with_declared_reads(&mut gasometer, |reads| {
+			reads.read(|| parachain_staking::Pallet::<Runtime>::is_selected_candidate(&address))
+		})?;
 
 		// Build output.
 		Ok(PrecompileOutput {
@@ -16,32 +16,26 @@
 
 //! Test utilities
 use super::*;
-use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	construct_runtime, parameter_types,
-	traits::{Everything, GenesisBuild},
-	weights::Weight,
+	construct_runtime, parameter_types, traits::GenesisBuild, weights::RuntimeDbWeight,
 };
-use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot, PrecompileSet};
 use parachain_staking::{InflationInfo, Range};
-use serde::{Deserialize, Serialize};
-use sp_core::{H160, H256};
 use sp_io;
-use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
-	Perbill, Percent,
-};
+use sp_runtime::{Perbill, Percent};
+
+pub use precompile_utils::testing::{Account, TestPrecompiles};
 
-pub type AccountId = TestAccount;
+pub type AccountId = Account;
 pub type Balance = u128;
 pub type BlockNumber = u64;
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles, TestDbWeight);
 
 construct_runtime!(
-	pub enum Test where
+	pub enum Runtime where
 		Block = Block,
 		NodeBlock = Block,
 		UncheckedExtrinsic = UncheckedExtrinsic,
@@ -54,169 +48,19 @@ construct_runtime!(
 	}
 );
 
-// FRom https://github.com/PureStake/moonbeam/pull/518. Merge to common once is merged
-#[derive(
-	Eq,
-	PartialEq,
-	Ord,
-	PartialOrd,
-	Clone,
-	Encode,
-	Decode,
-	Debug,
-	MaxEncodedLen,
-	Serialize,
-	Deserialize,
-	derive_more::Display,
-)]
-pub enum TestAccount {
-	Alice,
-	Bob,
-	Charlie,
-	Bogus,
-}
-
-impl Default for TestAccount {
-	fn default() -> Self {
-		Self::Bogus
-	}
-}
-
-impl AddressMapping<TestAccount> for TestAccount {
-	fn into_account_id(h160_account: H160) -> TestAccount {
-		match h160_account {
-			a if a == H160::repeat_byte(0xAA) => Self::Alice,
-			a if a == H160::repeat_byte(0xBB) => Self::Bob,
-			a if a == H160::repeat_byte(0xCC) => Self::Charlie,
-			_ => Self::Bogus,
-		}
-	}
-}
-
-impl TestAccount {
-	pub(crate) fn to_h160(&self) -> H160 {
-		match self {
-			Self::Alice => H160::repeat_byte(0xAA),
-			Self::Bob => H160::repeat_byte(0xBB),
-			Self::Charlie => H160::repeat_byte(0xCC),
-			Self::Bogus => Default::default(),
-		}
-	}
-}
-
-impl From<H160> for TestAccount {
-	fn from(x: H160) -> TestAccount {
-		TestAccount::into_account_id(x)
-	}
-}
-
-parameter_types! {
-	pub const BlockHashCount: u64 = 250;
-	pub const MaximumBlockWeight: Weight = 1024;
-	pub const MaximumBlockLength: u32 = 2 * 1024;
-	pub const AvailableBlockRatio: Perbill = Perbill::one();
-	pub const SS58Prefix: u8 = 42;
-}
-impl frame_system::Config for Test {
-	type BaseCallFilter = Everything;
-	type DbWeight = ();
-	type Origin = Origin;
-	type Index = u64;
-	type BlockNumber = BlockNumber;
-	type Call = Call;
-	type Hash = H256;
-	type Hashing = BlakeTwo256;
-	type AccountId = AccountId;
-	type Lookup = IdentityLookup<Self::AccountId>;
-	type Header = Header;
-	type Event = Event;
-	type BlockHashCount = BlockHashCount;
-	type Version = ();
-	type PalletInfo = PalletInfo;
-	type AccountData = pallet_balances::AccountData<Balance>;
-	type OnNewAccount = ();
-	type OnKilledAccount = ();
-	type SystemWeightInfo = ();
-	type BlockWeights = ();
-	type BlockLength = ();
-	type SS58Prefix = SS58Prefix;
-	type OnSetCode = ();
-}
 parameter_types! {
-	pub const ExistentialDeposit: u128 = 1;
-}
-impl pallet_balances::Config for Test {
-	type MaxReserves = ();
-	type ReserveIdentifier = [u8; 4];
-	type MaxLocks = ();
-	type Balance = Balance;
-	type Event = Event;
-	type DustRemoval = ();
-	type ExistentialDeposit = ExistentialDeposit;
-	type AccountStore = System;
-	type WeightInfo = ();
+	pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight {
+		read: 100,
+		write: 1_000,
+	};
 }
 
 /// The staking precompile is available at address one in the mock runtime.
 pub fn precompile_address() -> H160 {
-	H160::from_low_u64_be(1)
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct TestPrecompiles<R>(PhantomData<R>);
-
-impl<R> PrecompileSet for TestPrecompiles<R>
-where
-	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
-	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
-	R: parachain_staking::Config + pallet_evm::Config,
-	R::AccountId: From<H160>,
-	BalanceOf<R>: EvmData,
-	R::Call: From<parachain_staking::Call<R>>,
-{
-	fn execute(
-		address: H160,
-		input: &[u8],
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> Option<Result<PrecompileOutput, ExitError>> {
-		match address {
-			a if a == precompile_address() => Some(ParachainStakingWrapper::<R>::execute(
-				input, target_gas, context,
-			)),
-			_ => None,
-		}
-	}
-}
-
-pub type Precompiles = TestPrecompiles<Test>;
-
-impl pallet_evm::Config for Test {
-	type FeeCalculator = ();
-	type GasWeightMapping = ();
-	type CallOrigin = EnsureAddressRoot<AccountId>;
-	type WithdrawOrigin = EnsureAddressNever<AccountId>;
-	type AddressMapping = AccountId;
-	type Currency = Balances;
-	type Event = Event;
-	type Runner = pallet_evm::runner::stack::Runner<Self>;
-	type Precompiles = Precompiles;
-	type ChainId = ();
-	type OnChargeTransaction = ();
-	type BlockGasLimit = ();
-	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
-	type FindAuthor = ();
+	Account::Precompile.into()
 }
 
-parameter_types! {
-	pub const MinimumPeriod: u64 = 5;
-}
-impl pallet_timestamp::Config for Test {
-	type Moment = u64;
-	type OnTimestampSet = ();
-	type MinimumPeriod = MinimumPeriod;
-	type WeightInfo = ();
-}
+pub type Precompiles = TestPrecompiles<ParachainStakingWrapper<Runtime>>;
 
 parameter_types! {
 	pub const MinBlocksPerRound: u32 = 3;
@@ -234,7 +78,7 @@ parameter_types! {
 	pub const MinNominatorStk: u128 = 5;
 	pub const MinNomination: u128 = 3;
 }
-impl parachain_staking::Config for Test {
+impl parachain_staking::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type MonetaryGovernanceOrigin = frame_system::EnsureRoot<AccountId>;
@@ -323,15 +167,15 @@ impl ExtBuilder {
 
 	pub(crate) fn build(self) -> sp_io::TestExternalities {
 		let mut t = frame_system::GenesisConfig::default()
-			.build_storage::<Test>()
+			.build_storage::<Runtime>()
 			.expect("Frame system builds valid default genesis config");
 
-		pallet_balances::GenesisConfig::<Test> {
+		pallet_balances::GenesisConfig::<Runtime> {
 			balances: self.balances,
 		}
 		.assimilate_storage(&mut t)
 		.expect("Pallet balances storage can be assimilated");
-		parachain_staking::GenesisConfig::<Test> {
+		parachain_staking::GenesisConfig::<Runtime> {
 			candidates: self.collators,
 			nominations: self.nominations,
 			inflation_config: self.inflation,
@@ -346,9 +190,9 @@ impl ExtBuilder {
 }
 
 // Same storage changes as EventHandler::note_author impl
-pub(crate) fn set_points(round: u32, acc: TestAccount, pts: u32) {
-	<parachain_staking::Points<Test>>::mutate(round, |p| *p += pts);
-	<parachain_staking::AwardedPts<Test>>::mutate(round, acc, |p| *p += pts);
+pub(crate) fn set_points(round: u32, acc: Account, pts: u32) {
+	<parachain_staking::Points<Runtime>>::mutate(round, |p| *p += pts);
+	<parachain_staking::AwardedPts<Runtime>>::mutate(round, acc, |p| *p += pts);
 }
 
 pub(crate) fn events() -> Vec<Event> {
@@ -358,12 +202,11 @@ pub(crate) fn events() -> Vec<Event> {
 		.collect::<Vec<_>>()
 }
 
-// Helper function to give a simple evm context suitable for tests.
-// We can remove this once https://github.com/rust-blockchain/evm/pull/35
-// is in our dependency graph.
+// Helper function to give a simple evm context suitable for tests, calling the precompile
+// from the default address.
 pub fn evm_test_context() -> evm::Context {
 	evm::Context {
-		address: Default::default(),
+		address: precompile_address(),
 		caller: Default::default(),
 		apparent_value: From::from(0),
 	}
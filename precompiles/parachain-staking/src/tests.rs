@@ -15,8 +15,8 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::mock::{
-	events, evm_test_context, precompile_address, set_points, Call, ExtBuilder, Origin,
-	ParachainStaking, Precompiles, TestAccount,
+	events, evm_test_context, precompile_address, set_points, Account, Call, ExtBuilder, Origin,
+	ParachainStaking, Precompiles,
 };
 use crate::PrecompileOutput;
 use frame_support::{assert_ok, dispatch::Dispatchable};
@@ -25,7 +25,7 @@ use pallet_evm::{ExitSucceed, PrecompileSet};
 use parachain_staking::Event as StakingEvent;
 use precompile_utils::{error, EvmDataWriter};
 use sha3::{Digest, Keccak256};
-use sp_core::U256;
+use sp_core::{H160, U256};
 
 #[test]
 fn selector_less_than_four_bytes() {
@@ -81,7 +81,7 @@ fn min_nomination_works() {
 		let expected_one_result = Some(Ok(PrecompileOutput {
 			exit_status: ExitSucceed::Returned,
 			output: EvmDataWriter::new().write(3u32).build(),
-			cost: Default::default(),
+			cost: 100, // 1 db read
 			logs: Default::default(),
 		}));
 
@@ -96,8 +96,8 @@ fn min_nomination_works() {
 #[test]
 fn points_zero() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"points(uint256)")[0..4];
@@ -111,7 +111,7 @@ fn points_zero() {
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(0u32).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
@@ -126,8 +126,8 @@ fn points_zero() {
 #[test]
 fn points_non_zero() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"points(uint256)")[0..4];
@@ -141,11 +141,11 @@ fn points_non_zero() {
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(100u32).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
-			set_points(1u32, TestAccount::Alice, 100);
+			set_points(1u32, Account::Alice, 100);
 
 			// Assert that there are total 100 points in round 1
 			assert_eq!(
@@ -159,16 +159,16 @@ fn points_non_zero() {
 fn collator_nomination_count_works() {
 	ExtBuilder::default()
 		.with_balances(vec![
-			(TestAccount::Alice, 1_000),
-			(TestAccount::Bob, 50),
-			(TestAccount::Charlie, 50),
-			(TestAccount::Bogus, 50),
+			(Account::Alice, 1_000),
+			(Account::Bob, 50),
+			(Account::Charlie, 50),
+			(Account::Bogus, 50),
 		])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.with_nominations(vec![
-			(TestAccount::Bob, TestAccount::Alice, 50),
-			(TestAccount::Charlie, TestAccount::Alice, 50),
-			(TestAccount::Bogus, TestAccount::Alice, 50),
+			(Account::Bob, Account::Alice, 50),
+			(Account::Charlie, Account::Alice, 50),
+			(Account::Bogus, Account::Alice, 50),
 		])
 		.build()
 		.execute_with(|| {
@@ -177,13 +177,13 @@ fn collator_nomination_count_works() {
 			// Construct data to read collator nomination count
 			let mut input_data = Vec::<u8>::from([0u8; 36]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 
 			// Expected result 3
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(3u32).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
@@ -199,14 +199,14 @@ fn collator_nomination_count_works() {
 fn nominator_nomination_count_works() {
 	ExtBuilder::default()
 		.with_balances(vec![
-			(TestAccount::Alice, 1_000),
-			(TestAccount::Bob, 1_000),
-			(TestAccount::Charlie, 200),
+			(Account::Alice, 1_000),
+			(Account::Bob, 1_000),
+			(Account::Charlie, 200),
 		])
-		.with_candidates(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000), (Account::Bob, 1_000)])
 		.with_nominations(vec![
-			(TestAccount::Charlie, TestAccount::Alice, 100),
-			(TestAccount::Charlie, TestAccount::Bob, 100),
+			(Account::Charlie, Account::Alice, 100),
+			(Account::Charlie, Account::Bob, 100),
 		])
 		.build()
 		.execute_with(|| {
@@ -215,13 +215,13 @@ fn nominator_nomination_count_works() {
 			// Construct data to read nominator nomination count
 			let mut input_data = Vec::<u8>::from([0u8; 36]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Charlie.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Charlie).0);
 
 			// Expected result is 2
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(2u32).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
@@ -241,13 +241,13 @@ fn is_nominator_false() {
 		// Construct data to read is_nominator
 		let mut input_data = Vec::<u8>::from([0u8; 36]);
 		input_data[0..4].copy_from_slice(&selector);
-		input_data[16..36].copy_from_slice(&TestAccount::Charlie.to_h160().0);
+		input_data[16..36].copy_from_slice(&H160::from(Account::Charlie).0);
 
 		// Expected result is false
 		let expected_one_result = Some(Ok(PrecompileOutput {
 			exit_status: ExitSucceed::Returned,
 			output: EvmDataWriter::new().write(false).build(),
-			cost: Default::default(),
+			cost: 100, // 1 db read
 			logs: Default::default(),
 		}));
 
@@ -262,9 +262,9 @@ fn is_nominator_false() {
 #[test]
 fn is_nominator_true() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 50)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
-		.with_nominations(vec![(TestAccount::Bob, TestAccount::Alice, 50)])
+		.with_balances(vec![(Account::Alice, 1_000), (Account::Bob, 50)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
+		.with_nominations(vec![(Account::Bob, Account::Alice, 50)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"is_nominator(address)")[0..4];
@@ -272,13 +272,13 @@ fn is_nominator_true() {
 			// Construct data to read is_nominator
 			let mut input_data = Vec::<u8>::from([0u8; 36]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Bob.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Bob).0);
 
 			// Expected result is true
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(true).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
@@ -298,13 +298,13 @@ fn is_candidate_false() {
 		// Construct data to read is_candidate
 		let mut input_data = Vec::<u8>::from([0u8; 36]);
 		input_data[0..4].copy_from_slice(&selector);
-		input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+		input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 
 		// Expected result is false
 		let expected_one_result = Some(Ok(PrecompileOutput {
 			exit_status: ExitSucceed::Returned,
 			output: EvmDataWriter::new().write(false).build(),
-			cost: Default::default(),
+			cost: 100, // 1 db read
 			logs: Default::default(),
 		}));
 
@@ -319,8 +319,8 @@ fn is_candidate_false() {
 #[test]
 fn is_candidate_true() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"is_candidate(address)")[0..4];
@@ -328,13 +328,13 @@ fn is_candidate_true() {
 			// Construct data to read is_candidate
 			let mut input_data = Vec::<u8>::from([0u8; 36]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 
 			// Expected result is true
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(true).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
@@ -354,13 +354,13 @@ fn is_selected_candidate_false() {
 		// Construct data to read is_candidate
 		let mut input_data = Vec::<u8>::from([0u8; 36]);
 		input_data[0..4].copy_from_slice(&selector);
-		input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+		input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 
 		// Expected result is false
 		let expected_one_result = Some(Ok(PrecompileOutput {
 			exit_status: ExitSucceed::Returned,
 			output: EvmDataWriter::new().write(false).build(),
-			cost: Default::default(),
+			cost: 100, // 1 db read
 			logs: Default::default(),
 		}));
 
@@ -375,8 +375,8 @@ fn is_selected_candidate_false() {
 #[test]
 fn is_selected_candidate_true() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"is_selected_candidate(address)")[0..4];
@@ -384,13 +384,13 @@ fn is_selected_candidate_true() {
 			// Construct data to read is_selected_candidate
 			let mut input_data = Vec::<u8>::from([0u8; 36]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 
 			// Expected result is true
 			let expected_one_result = Some(Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				output: EvmDataWriter::new().write(true).build(),
-				cost: Default::default(),
+				cost: 100, // 1 db read
 				logs: Default::default(),
 			}));
 
@@ -405,7 +405,7 @@ fn is_selected_candidate_true() {
 #[test]
 fn join_candidates_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"join_candidates(uint256,uint256)")[0..4];
@@ -420,7 +420,7 @@ fn join_candidates_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Alice.to_h160(),
+				H160::from(Account::Alice),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -431,7 +431,7 @@ fn join_candidates_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::JoinedCollatorCandidates(TestAccount::Alice, 1000, 1000).into();
+				StakingEvent::JoinedCollatorCandidates(Account::Alice, 1000, 1000).into();
 			// Assert that the events vector contains the one expected
 			println!("{:?}", events());
 			assert!(events().contains(&expected));
@@ -441,8 +441,8 @@ fn join_candidates_works() {
 #[test]
 fn leave_candidates_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"leave_candidates(uint256)")[0..4];
@@ -455,7 +455,7 @@ fn leave_candidates_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Alice.to_h160(),
+				H160::from(Account::Alice),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -466,7 +466,7 @@ fn leave_candidates_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::CollatorScheduledExit(1, TestAccount::Alice, 3).into();
+				StakingEvent::CollatorScheduledExit(1, Account::Alice, 3).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -475,13 +475,11 @@ fn leave_candidates_works() {
 #[test]
 fn go_online_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
-			assert_ok!(ParachainStaking::go_offline(Origin::signed(
-				TestAccount::Alice
-			)));
+			assert_ok!(ParachainStaking::go_offline(Origin::signed(Account::Alice)));
 			let selector = &Keccak256::digest(b"go_online()")[0..4];
 
 			// Construct selector for go_offline
@@ -490,7 +488,7 @@ fn go_online_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Alice.to_h160(),
+				H160::from(Account::Alice),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -501,7 +499,7 @@ fn go_online_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::CollatorBackOnline(1, TestAccount::Alice).into();
+				StakingEvent::CollatorBackOnline(1, Account::Alice).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -510,8 +508,8 @@ fn go_online_works() {
 #[test]
 fn go_offline_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"go_offline()")[0..4];
@@ -522,7 +520,7 @@ fn go_offline_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Alice.to_h160(),
+				H160::from(Account::Alice),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -533,7 +531,7 @@ fn go_offline_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::CollatorWentOffline(1, TestAccount::Alice).into();
+				StakingEvent::CollatorWentOffline(1, Account::Alice).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -542,8 +540,8 @@ fn go_offline_works() {
 #[test]
 fn candidate_bond_more_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_500)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_500)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"candidate_bond_more(uint256)")[0..4];
@@ -556,7 +554,7 @@ fn candidate_bond_more_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Alice.to_h160(),
+				H160::from(Account::Alice),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -567,7 +565,7 @@ fn candidate_bond_more_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::CollatorBondedMore(TestAccount::Alice, 1_000, 1_500).into();
+				StakingEvent::CollatorBondedMore(Account::Alice, 1_000, 1_500).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -576,8 +574,8 @@ fn candidate_bond_more_works() {
 #[test]
 fn candidate_bond_less_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"candidate_bond_less(uint256)")[0..4];
@@ -590,7 +588,7 @@ fn candidate_bond_less_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Alice.to_h160(),
+				H160::from(Account::Alice),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -601,7 +599,7 @@ fn candidate_bond_less_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::CollatorBondedLess(TestAccount::Alice, 1_000, 500).into();
+				StakingEvent::CollatorBondedLess(Account::Alice, 1_000, 500).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -610,8 +608,8 @@ fn candidate_bond_less_works() {
 #[test]
 fn nominate_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000), (Account::Bob, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"nominate(address,uint256,uint256,uint256)")[0..4];
@@ -619,7 +617,7 @@ fn nominate_works() {
 			// Construct selector for nominate
 			let mut input_data = Vec::<u8>::from([0u8; 132]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 			let nomination_amount: U256 = 1_000.into();
 			nomination_amount.to_big_endian(&mut input_data[36..68]);
 			let collator_nominator_count = U256::zero();
@@ -629,7 +627,7 @@ fn nominate_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Bob.to_h160(),
+				H160::from(Account::Bob),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -639,12 +637,12 @@ fn nominate_works() {
 			))
 			.dispatch(Origin::root()));
 
-			assert!(ParachainStaking::is_nominator(&TestAccount::Bob));
+			assert!(ParachainStaking::is_nominator(&Account::Bob));
 
 			let expected: crate::mock::Event = StakingEvent::Nomination(
-				TestAccount::Bob,
+				Account::Bob,
 				1_000,
-				TestAccount::Alice,
+				Account::Alice,
 				parachain_staking::NominatorAdded::AddedToTop { new_total: 2_000 },
 			)
 			.into();
@@ -656,9 +654,9 @@ fn nominate_works() {
 #[test]
 fn leave_nominators_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
-		.with_nominations(vec![(TestAccount::Bob, TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000), (Account::Bob, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
+		.with_nominations(vec![(Account::Bob, Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"leave_nominators(uint256)")[0..4];
@@ -671,7 +669,7 @@ fn leave_nominators_works() {
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Bob.to_h160(),
+				H160::from(Account::Bob),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -682,7 +680,7 @@ fn leave_nominators_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::NominatorExitScheduled(1, TestAccount::Bob, 3).into();
+				StakingEvent::NominatorExitScheduled(1, Account::Bob, 3).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -691,9 +689,9 @@ fn leave_nominators_works() {
 #[test]
 fn revoke_nomination_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 1_000)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
-		.with_nominations(vec![(TestAccount::Bob, TestAccount::Alice, 1_000)])
+		.with_balances(vec![(Account::Alice, 1_000), (Account::Bob, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
+		.with_nominations(vec![(Account::Bob, Account::Alice, 1_000)])
 		.build()
 		.execute_with(|| {
 			let selector = &Keccak256::digest(b"revoke_nomination(address)")[0..4];
@@ -701,11 +699,11 @@ fn revoke_nomination_works() {
 			// Construct selector for revoke_nomination
 			let mut input_data = Vec::<u8>::from([0u8; 36]);
 			input_data[0..4].copy_from_slice(&selector);
-			input_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			input_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 
 			// Make sure the call goes through successfully
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Bob.to_h160(),
+				H160::from(Account::Bob),
 				precompile_address(),
 				input_data,
 				U256::zero(), // No value sent in EVM
@@ -716,7 +714,7 @@ fn revoke_nomination_works() {
 			.dispatch(Origin::root()));
 
 			let expected: crate::mock::Event =
-				StakingEvent::NominatorExitScheduled(1, TestAccount::Bob, 3).into();
+				StakingEvent::NominatorExitScheduled(1, Account::Bob, 3).into();
 			// Assert that the events vector contains the one expected
 			assert!(events().contains(&expected));
 		});
@@ -725,21 +723,21 @@ fn revoke_nomination_works() {
 #[test]
 fn nominator_bond_more_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 1_500)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
-		.with_nominations(vec![(TestAccount::Bob, TestAccount::Alice, 500)])
+		.with_balances(vec![(Account::Alice, 1_000), (Account::Bob, 1_500)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
+		.with_nominations(vec![(Account::Bob, Account::Alice, 500)])
 		.build()
 		.execute_with(|| {
 			// Construct the nominator_bond_more call
 			let mut bond_more_call_data = Vec::<u8>::from([0u8; 68]);
 			bond_more_call_data[0..4]
 				.copy_from_slice(&Keccak256::digest(b"nominator_bond_more(address,uint256)")[0..4]);
-			bond_more_call_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			bond_more_call_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 			let bond_more_amount: U256 = 500.into();
 			bond_more_amount.to_big_endian(&mut bond_more_call_data[36..68]);
 
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Bob.to_h160(),
+				H160::from(Account::Bob),
 				precompile_address(),
 				bond_more_call_data,
 				U256::zero(), // No value sent in EVM
@@ -751,8 +749,7 @@ fn nominator_bond_more_works() {
 
 			// Check for the right events.
 			let expected_event: crate::mock::Event =
-				StakingEvent::NominationIncreased(TestAccount::Bob, TestAccount::Alice, 500, true)
-					.into();
+				StakingEvent::NominationIncreased(Account::Bob, Account::Alice, 500, true).into();
 
 			assert!(events().contains(&expected_event));
 		});
@@ -761,21 +758,21 @@ fn nominator_bond_more_works() {
 #[test]
 fn nominator_bond_less_works() {
 	ExtBuilder::default()
-		.with_balances(vec![(TestAccount::Alice, 1_000), (TestAccount::Bob, 1_500)])
-		.with_candidates(vec![(TestAccount::Alice, 1_000)])
-		.with_nominations(vec![(TestAccount::Bob, TestAccount::Alice, 1_500)])
+		.with_balances(vec![(Account::Alice, 1_000), (Account::Bob, 1_500)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
+		.with_nominations(vec![(Account::Bob, Account::Alice, 1_500)])
 		.build()
 		.execute_with(|| {
 			// Construct the nominator_bond_less call
 			let mut bond_less_call_data = Vec::<u8>::from([0u8; 68]);
 			bond_less_call_data[0..4]
 				.copy_from_slice(&Keccak256::digest(b"nominator_bond_less(address,uint256)")[0..4]);
-			bond_less_call_data[16..36].copy_from_slice(&TestAccount::Alice.to_h160().0);
+			bond_less_call_data[16..36].copy_from_slice(&H160::from(Account::Alice).0);
 			let bond_less_amount: U256 = 500.into();
 			bond_less_amount.to_big_endian(&mut bond_less_call_data[36..68]);
 
 			assert_ok!(Call::Evm(EvmCall::call(
-				TestAccount::Bob.to_h160(),
+				H160::from(Account::Bob),
 				precompile_address(),
 				bond_less_call_data,
 				U256::zero(), // No value sent in EVM
@@ -787,9 +784,28 @@ fn nominator_bond_less_works() {
 
 			// Check for the right events.
 			let expected_event: crate::mock::Event =
-				StakingEvent::NominationDecreased(TestAccount::Bob, TestAccount::Alice, 500, true)
-					.into();
+				StakingEvent::NominationDecreased(Account::Bob, Account::Alice, 500, true).into();
 
 			assert!(events().contains(&expected_event));
 		});
 }
+
+#[test]
+fn go_online_is_not_payable() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1_000)])
+		.with_candidates(vec![(Account::Alice, 1_000)])
+		.build()
+		.execute_with(|| {
+			let selector = &Keccak256::digest(b"go_online()")[0..4];
+
+			let mut context = evm_test_context();
+			context.caller = H160::from(Account::Alice);
+			context.apparent_value = U256::one();
+
+			assert_eq!(
+				Precompiles::execute(precompile_address(), selector, None, &context),
+				Some(Err(error("function is not payable")))
+			);
+		});
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Manage the proxies of the caller. Proxy types are given by their index in the `ProxyType`
+/// enum of the runtime, and delays are in blocks.
+/// This file is generated from the Rust implementation of the precompile.
+interface Proxy {
+    /// Register `delegate` as a proxy of the caller, allowed to dispatch the calls of
+    /// `proxy_type` after `delay` blocks. A contract cannot add a proxy whose type allows to
+    /// manage proxies.
+    /// Selector: 74a34dd3
+    function addProxy(address delegate, uint8 proxy_type, uint32 delay) external;
+
+    /// Unregister the proxy `delegate` of the caller with `proxy_type` and `delay`.
+    /// Selector: fef3f708
+    function removeProxy(address delegate, uint8 proxy_type, uint32 delay) external;
+
+    /// Unregister all the proxies of the caller.
+    /// Selector: 14a5b5fa
+    function removeProxies() external;
+
+    /// Whether `delegate` is a proxy of `real` with `proxy_type` and `delay`.
+    /// Selector: e26d38ed
+    function isProxy(address real, address delegate, uint8 proxy_type, uint32 delay) external view returns (bool);
+}
@@ -0,0 +1,186 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to manage the proxies of the caller through pallet-proxy.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Decode;
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{GetCallMetadata, InstanceFilter},
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{error, precompile, Address, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type SystemCallOf<Runtime> = <Runtime as frame_system::Config>::Call;
+type ProxyCallOf<Runtime> = <Runtime as pallet_proxy::Config>::Call;
+
+/// A precompile to wrap the functionality from pallet-proxy.
+/// It must be installed with `precompile_utils::check_not_delegated`, as proxies are added and
+/// removed on behalf of the caller.
+pub struct ProxyWrapper<Runtime>(PhantomData<Runtime>);
+
+/// Manage the proxies of the caller. Proxy types are given by their index in the `ProxyType`
+/// enum of the runtime, and delays are in blocks.
+#[precompile(interface = "Proxy")]
+impl<Runtime> ProxyWrapper<Runtime>
+where
+	Runtime: pallet_proxy::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::BlockNumber: From<u32>,
+	SystemCallOf<Runtime>:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<SystemCallOf<Runtime> as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	SystemCallOf<Runtime>: From<pallet_proxy::Call<Runtime>>,
+{
+	/// Register `delegate` as a proxy of the caller, allowed to dispatch the calls of
+	/// `proxy_type` after `delay` blocks. A contract cannot add a proxy whose type allows to
+	/// manage proxies, except from its constructor (see `caller_is_contract`).
+	#[precompile::public("addProxy(address,uint8,uint32)")]
+	fn add_proxy(
+		handle: &mut PrecompileHandle,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult {
+		let proxy_type = Self::decode_proxy_type(proxy_type)?;
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.0);
+
+		if Self::caller_is_contract(handle)? && Self::allows_managing_proxies(&proxy_type) {
+			return Err(error("proxy type cannot be added by a contract"));
+		}
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_proxy::Call::<Runtime>::add_proxy(delegate, proxy_type, delay.into());
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Unregister the proxy `delegate` of the caller with `proxy_type` and `delay`.
+	#[precompile::public("removeProxy(address,uint8,uint32)")]
+	fn remove_proxy(
+		handle: &mut PrecompileHandle,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult {
+		let proxy_type = Self::decode_proxy_type(proxy_type)?;
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.0);
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_proxy::Call::<Runtime>::remove_proxy(delegate, proxy_type, delay.into());
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Unregister all the proxies of the caller.
+	#[precompile::public("removeProxies()")]
+	fn remove_proxies(handle: &mut PrecompileHandle) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_proxy::Call::<Runtime>::remove_proxies();
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Whether `delegate` is a proxy of `real` with `proxy_type` and `delay`.
+	#[precompile::public("isProxy(address,address,uint8,uint32)")]
+	#[precompile::view]
+	fn is_proxy(
+		handle: &mut PrecompileHandle,
+		real: Address,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult<bool> {
+		let proxy_type = Self::decode_proxy_type(proxy_type)?;
+		let real = Runtime::AddressMapping::into_account_id(real.0);
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.0);
+		let delay: Runtime::BlockNumber = delay.into();
+
+		let (proxies, _) =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_proxy::Pallet::<Runtime>::proxies(&real))
+			})?;
+
+		Ok(proxies.iter().any(|proxy| {
+			proxy.delegate == delegate && proxy.proxy_type == proxy_type && proxy.delay == delay
+		}))
+	}
+
+	fn decode_proxy_type(proxy_type: u8) -> EvmResult<Runtime::ProxyType> {
+		Runtime::ProxyType::decode(&mut &[proxy_type][..])
+			.map_err(|_| error("failed decoding proxy type"))
+	}
+
+	/// Whether the caller of the precompile is a contract, in which case it is not the one
+	/// signing the transaction.
+	///
+	/// The code of a contract is only stored once its constructor returns, so a contract calling
+	/// the precompile from its constructor is not detected and can add any proxy. This can't be
+	/// closed with what the precompile knows of its caller: the transaction origin is not
+	/// provided to precompiles, and the nonce doesn't help as a contract has nonce 1 in its
+	/// constructor, like an account in its first transaction, and can increase it by creating
+	/// contracts. The deployer of a contract controls its constructor and can thus already decide
+	/// of its proxies; other accounts must check the proxies of a contract before trusting it, as
+	/// they would check its code.
+	fn caller_is_contract(handle: &mut PrecompileHandle) -> EvmResult<bool> {
+		let caller = handle.context.caller;
+
+		RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+			reads.read(|| pallet_evm::AccountCodes::<Runtime>::contains_key(caller))
+		})
+	}
+
+	/// Whether a proxy of this type can add proxies to the account, including proxies of a more
+	/// permissive type. Such a proxy added by a contract allows to escalate to the full control
+	/// of the contract account.
+	fn allows_managing_proxies(proxy_type: &Runtime::ProxyType) -> bool {
+		let call = SystemCallOf::<Runtime>::from(pallet_proxy::Call::<Runtime>::add_proxy(
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		));
+
+		proxy_type.filter(&ProxyCallOf::<Runtime>::from(call))
+	}
+}
@@ -0,0 +1,392 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::Context;
+use pallet_evm::{PrecompileSet, Runner};
+use precompile_utils::{
+	testing::{check_solidity_interface, PrecompileTesterExt},
+	EvmDataWriter,
+};
+use sp_core::{H160, U256};
+
+fn proxy_input(action: Action, delegate: Account, proxy_type: u8, delay: u32) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(action as u32)
+		.write(Address(delegate.into()))
+		.write(proxy_type)
+		.write(delay)
+		.build()
+}
+
+fn is_proxy_input(real: Account, delegate: Account, proxy_type: ProxyType, delay: u32) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::IsProxy as u32)
+		.write(Address(real.into()))
+		.write(Address(delegate.into()))
+		.write(proxy_type as u8)
+		.write(delay)
+		.build()
+}
+
+fn is_proxy(real: Account, delegate: Account, proxy_type: ProxyType, delay: u32) -> bool {
+	pallet_proxy::Pallet::<Runtime>::proxies(real)
+		.0
+		.iter()
+		.any(|p| p.delegate == delegate && p.proxy_type == proxy_type && p.delay == delay)
+}
+
+/// A contract is deployed at the account, which is then the caller of the precompile when the
+/// contract calls it.
+fn install_contract(account: Account) {
+	pallet_evm::AccountCodes::<Runtime>::insert(H160::from(account), vec![0x00]);
+}
+
+fn add_and_remove_proxy(proxy_type: ProxyType) {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, proxy_type as u8, 1),
+			)
+			.execute_stops();
+
+			assert!(is_proxy(Account::Alice, Account::Bob, proxy_type, 1));
+			// Deposit base and factor
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 101);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				is_proxy_input(Account::Alice, Account::Bob, proxy_type, 1),
+			)
+			.execute_returns_encoded(true);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::RemoveProxy, Account::Bob, proxy_type as u8, 1),
+			)
+			.execute_stops();
+
+			assert!(!is_proxy(Account::Alice, Account::Bob, proxy_type, 1));
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				is_proxy_input(Account::Alice, Account::Bob, proxy_type, 1),
+			)
+			.execute_returns_encoded(false);
+		});
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::AddProxy as u32, 0x74a34dd3);
+	assert_eq!(Action::RemoveProxy as u32, 0xfef3f708);
+	assert_eq!(Action::RemoveProxies as u32, 0x14a5b5fa);
+	assert_eq!(Action::IsProxy as u32, 0xe26d38ed);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "Proxy.sol");
+}
+
+#[test]
+fn add_and_remove_proxy_any() {
+	add_and_remove_proxy(ProxyType::Any);
+}
+
+#[test]
+fn add_and_remove_proxy_non_transfer() {
+	add_and_remove_proxy(ProxyType::NonTransfer);
+}
+
+#[test]
+fn add_and_remove_proxy_governance() {
+	add_and_remove_proxy(ProxyType::Governance);
+}
+
+#[test]
+fn add_and_remove_proxy_staking() {
+	add_and_remove_proxy(ProxyType::Staking);
+}
+
+#[test]
+fn add_and_remove_proxy_cancel_proxy() {
+	add_and_remove_proxy(ProxyType::CancelProxy);
+}
+
+#[test]
+fn contract_cannot_add_proxy_any() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			install_contract(Account::Alice);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any as u8, 0),
+			)
+			.execute_reverts(|reason| reason == "proxy type cannot be added by a contract");
+
+			assert!(!is_proxy(Account::Alice, Account::Bob, ProxyType::Any, 0));
+		});
+}
+
+#[test]
+fn contract_cannot_add_proxy_non_transfer() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			install_contract(Account::Alice);
+
+			// NonTransfer proxies can add proxies, which could be of type Any.
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(
+					Action::AddProxy,
+					Account::Bob,
+					ProxyType::NonTransfer as u8,
+					0,
+				),
+			)
+			.execute_reverts(|reason| reason == "proxy type cannot be added by a contract");
+		});
+}
+
+#[test]
+fn contract_can_add_restricted_proxies() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			install_contract(Account::Alice);
+
+			for proxy_type in [
+				ProxyType::Governance,
+				ProxyType::Staking,
+				ProxyType::CancelProxy,
+			] {
+				Precompiles::<Runtime>::prepare_test(
+					Account::Alice,
+					Account::Precompile,
+					proxy_input(Action::AddProxy, Account::Bob, proxy_type as u8, 0),
+				)
+				.execute_stops();
+
+				assert!(is_proxy(Account::Alice, Account::Bob, proxy_type, 0));
+			}
+		});
+}
+
+/// Known limitation, see `caller_is_contract`.
+#[test]
+fn contract_constructor_is_not_detected_as_a_contract() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let input = proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any as u8, 0);
+			assert_eq!(input.len(), 0x64);
+
+			// Copies the input appended after it in memory, then calls the precompile with it
+			// (`PUSH1 0x64 PUSH1 0x16 PUSH1 0 CODECOPY PUSH1 0 PUSH1 0 PUSH1 0x64 PUSH1 0
+			// PUSH1 0 PUSH1 1 GAS CALL STOP`).
+			let mut init = vec![
+				0x60, 0x64, 0x60, 0x16, 0x60, 0x00, 0x39, 0x60, 0x00, 0x60, 0x00, 0x60, 0x64, 0x60,
+				0x00, 0x60, 0x00, 0x60, 0x01, 0x5a, 0xf1, 0x00,
+			];
+			assert_eq!(init.len(), 0x16);
+			init.extend(input);
+
+			// The value pays for the proxy deposit of the contract.
+			let info = <Runtime as pallet_evm::Config>::Runner::create(
+				Account::Alice.into(),
+				init,
+				U256::from(200),
+				1_000_000,
+				None,
+				None,
+				<Runtime as pallet_evm::Config>::config(),
+			)
+			.expect("the contract is created");
+			assert!(matches!(info.exit_reason, evm::ExitReason::Succeed(_)));
+
+			// The address of the contract is mapped to `Bogus`.
+			assert!(is_proxy(Account::Bogus, Account::Bob, ProxyType::Any, 0));
+
+			// The nonce can't tell a constructor from an account in its first transaction.
+			assert_eq!(
+				frame_system::Pallet::<Runtime>::account_nonce(&Account::Bogus),
+				1
+			);
+			assert_eq!(
+				frame_system::Pallet::<Runtime>::account_nonce(&Account::Alice),
+				1
+			);
+		});
+}
+
+#[test]
+fn remove_proxies() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any as u8, 0),
+			)
+			.execute_stops();
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(
+					Action::AddProxy,
+					Account::Charlie,
+					ProxyType::Staking as u8,
+					0,
+				),
+			)
+			.execute_stops();
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::RemoveProxies as u32).build(),
+			)
+			.execute_stops();
+
+			assert!(pallet_proxy::Pallet::<Runtime>::proxies(Account::Alice)
+				.0
+				.is_empty());
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+		});
+}
+
+#[test]
+fn is_proxy_checks_type_and_delay() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, ProxyType::Staking as u8, 1),
+			)
+			.execute_stops();
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				is_proxy_input(Account::Alice, Account::Bob, ProxyType::Any, 1),
+			)
+			.execute_returns_encoded(false);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				is_proxy_input(Account::Alice, Account::Bob, ProxyType::Staking, 0),
+			)
+			.execute_returns_encoded(false);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Charlie,
+				Account::Precompile,
+				is_proxy_input(Account::Alice, Account::Charlie, ProxyType::Staking, 1),
+			)
+			.execute_returns_encoded(false);
+		});
+}
+
+#[test]
+fn add_proxy_fails_for_unknown_proxy_type() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, 5, 0),
+			)
+			.execute_reverts(|reason| reason == "failed decoding proxy type");
+		});
+}
+
+#[test]
+fn add_proxy_fails_twice() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any as u8, 0),
+			)
+			.execute_stops();
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any as u8, 0),
+			)
+			.execute_reverts(|reason| {
+				reason.starts_with("Dispatched call Proxy::add_proxy failed")
+			});
+		});
+}
+
+#[test]
+fn proxy_cannot_be_delegated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context is the one of the calling contract (Charlie), while
+			// the caller is the account which called it (Alice).
+			let context = Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any as u8, 0),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert!(!is_proxy(Account::Alice, Account::Bob, ProxyType::Any, 0));
+		});
+}
@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Request randomness from the relay chain VRF output, and fulfil it once the delay has passed.
+/// A deposit is reserved from the caller for each request, and returned when it is fulfilled or
+/// expired.
+/// This file is generated from the Rust implementation of the precompile.
+interface Randomness {
+    /// Request randomness to be fulfilled `delay` blocks from now, mixed with `salt`.
+    /// Returns the id of the request.
+    /// Selector: ec12ccee
+    function requestRandomness(bytes32 salt, uint32 delay) external returns (uint64);
+
+    /// Fulfil the request `request_id` of the caller, which returns its deposit.
+    /// Returns the random word of the request.
+    /// Selector: 781dfefd
+    function fulfilRequest(uint64 request_id) external returns (bytes32);
+
+    /// Expire the request `request_id`, whose target block has passed without relay randomness,
+    /// which returns its deposit to the requester.
+    /// Selector: 87e9acc0
+    function expireRequest(uint64 request_id) external;
+
+    /// The deposit reserved for each request.
+    /// Selector: d0e30db0
+    function deposit() external view returns (uint256);
+}
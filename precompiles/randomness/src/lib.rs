@@ -0,0 +1,125 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to request randomness from the relay chain VRF output and fulfil it after a delay,
+//! through pallet-randomness.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{Get, GetCallMetadata},
+};
+use pallet_evm::AddressMapping;
+use pallet_randomness::BalanceOf;
+use precompile_utils::{error, precompile, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_core::{H256, U256};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// A precompile to wrap the functionality from pallet-randomness.
+/// It must be installed with `precompile_utils::check_not_delegated`, as the requests of the
+/// caller are made and fulfilled, reserving and returning its deposit.
+pub struct RandomnessWrapper<Runtime>(PhantomData<Runtime>);
+
+/// Request randomness from the relay chain VRF output, and fulfil it once the delay has passed.
+/// A deposit is reserved from the caller for each request, and returned when it is fulfilled or
+/// expired.
+#[precompile(interface = "Randomness")]
+impl<Runtime> RandomnessWrapper<Runtime>
+where
+	Runtime: pallet_randomness::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::BlockNumber: From<u32>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_randomness::Call<Runtime>>,
+	BalanceOf<Runtime>: Into<U256>,
+{
+	/// Request randomness to be fulfilled `delay` blocks from now, mixed with `salt`.
+	/// Returns the id of the request.
+	#[precompile::public("requestRandomness(bytes32,uint32)")]
+	fn request_randomness(handle: &mut PrecompileHandle, salt: H256, delay: u32) -> EvmResult<u64> {
+		let request_id =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_randomness::Pallet::<Runtime>::request_count())
+			})?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_randomness::Call::<Runtime>::request_randomness(salt, delay.into());
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(request_id)
+	}
+
+	/// Fulfil the request `request_id` of the caller, which returns its deposit.
+	/// Returns the random word of the request.
+	#[precompile::public("fulfilRequest(uint64)")]
+	fn fulfil_request(handle: &mut PrecompileHandle, request_id: u64) -> EvmResult<H256> {
+		let random_word =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read_many(2, || {
+					pallet_randomness::Pallet::<Runtime>::random_word(request_id)
+				})
+			})?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_randomness::Call::<Runtime>::fulfil_request(request_id);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		// The dispatch fails if the random word is not available.
+		random_word.ok_or_else(|| error("randomness is not available"))
+	}
+
+	/// Expire the request `request_id`, whose target block has passed without relay randomness,
+	/// which returns its deposit to the requester.
+	#[precompile::public("expireRequest(uint64)")]
+	fn expire_request(handle: &mut PrecompileHandle, request_id: u64) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_randomness::Call::<Runtime>::expire_request(request_id);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// The deposit reserved for each request.
+	#[precompile::public("deposit()")]
+	#[precompile::view]
+	fn deposit(_handle: &mut PrecompileHandle) -> EvmResult<U256> {
+		Ok(<Runtime as pallet_randomness::Config>::Deposit::get().into())
+	}
+}
@@ -0,0 +1,139 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Testing utilities.
+
+use super::*;
+
+use codec::Encode;
+use cumulus_primitives_core::PersistedValidationData;
+use cumulus_primitives_parachain_inherent::{ParachainInherentData, INHERENT_IDENTIFIER};
+use frame_support::{
+	construct_runtime,
+	inherent::{InherentData, ProvideInherent},
+	parameter_types,
+	traits::UnfilteredDispatchable,
+	weights::RuntimeDbWeight,
+};
+use frame_system::RawOrigin;
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+use sp_trie::StorageProof;
+use std::cell::RefCell;
+
+pub use precompile_utils::testing::{Account, TestPrecompiles, PRECOMPILE_ADDRESS};
+
+pub type AccountId = Account;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+precompile_utils::impl_mock_runtime_config!(Runtime, Precompiles<Runtime>, TestDbWeight);
+
+thread_local! {
+	static RELAY_STORAGE_ROOT: RefCell<Option<H256>> = RefCell::new(None);
+}
+
+/// The storage root of the mocked relay state, as set by `mock_relay_state`.
+pub struct MockRelayStorageRoot;
+impl Get<Option<H256>> for MockRelayStorageRoot {
+	fn get() -> Option<H256> {
+		RELAY_STORAGE_ROOT.with(|root| *root.borrow())
+	}
+}
+
+parameter_types! {
+	pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight {
+		read: 100,
+		write: 1_000,
+	};
+	pub const Deposit: Balance = 100;
+	pub const MinBlockDelay: BlockNumber = 2;
+	pub const MaxBlockDelay: BlockNumber = 10;
+}
+
+impl pallet_randomness::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Deposit = Deposit;
+	type MinBlockDelay = MinBlockDelay;
+	type MaxBlockDelay = MaxBlockDelay;
+	type RelayStorageRoot = MockRelayStorageRoot;
+}
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		Randomness: pallet_randomness::{Pallet, Call, Storage, Event<T>, Inherent},
+	}
+);
+
+pub type Precompiles<R> = TestPrecompiles<RandomnessWrapper<R>>;
+
+pub(crate) type ExtBuilder = precompile_utils::testing::ExtBuilder<Runtime>;
+
+/// Mock the relay state of the relay parent, where BABE's `AuthorVrfRandomness` is `randomness`.
+/// Its storage root becomes the trusted relay parent storage root, and the parachain inherent data
+/// holding its proof is returned.
+pub(crate) fn mock_relay_state(randomness: Option<[u8; 32]>) -> InherentData {
+	let key = Randomness::relay_randomness_key();
+
+	let mut backend = sp_state_machine::new_in_mem::<BlakeTwo256>();
+	backend.insert(vec![(None, vec![(key.clone(), Some(randomness.encode()))])]);
+	let relay_parent_storage_root = *backend.root();
+	let relay_chain_state: StorageProof =
+		sp_state_machine::prove_read(backend, vec![key]).expect("prove read");
+
+	RELAY_STORAGE_ROOT.with(|root| *root.borrow_mut() = Some(relay_parent_storage_root));
+
+	let mut inherent_data = InherentData::default();
+	inherent_data
+		.put_data(
+			INHERENT_IDENTIFIER,
+			&ParachainInherentData {
+				validation_data: PersistedValidationData {
+					relay_parent_storage_root,
+					..Default::default()
+				},
+				relay_chain_state,
+				downward_messages: Default::default(),
+				horizontal_messages: Default::default(),
+			},
+		)
+		.expect("failed to put parachain inherent data");
+	inherent_data
+}
+
+/// Move to block `n`, including the randomness inherent in each block where it is created for the
+/// given relay randomness.
+pub(crate) fn roll_to_with_relay_randomness(n: BlockNumber, randomness: Option<[u8; 32]>) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+
+		if let Some(call) = Randomness::create_inherent(&mock_relay_state(randomness)) {
+			call.dispatch_bypass_filter(RawOrigin::None.into())
+				.expect("dispatch succeeded");
+		}
+	}
+}
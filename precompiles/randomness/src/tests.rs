@@ -0,0 +1,271 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::Context;
+use pallet_evm::PrecompileSet;
+use precompile_utils::{
+	testing::{check_solidity_interface, PrecompileTesterExt},
+	EvmDataWriter,
+};
+
+const RELAY_RANDOMNESS: [u8; 32] = [7; 32];
+
+fn request_randomness_input(salt: H256, delay: u32) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::RequestRandomness as u32)
+		.write(salt)
+		.write(delay)
+		.build()
+}
+
+fn fulfil_request_input(request_id: u64) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(Action::FulfilRequest as u32)
+		.write(request_id)
+		.build()
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::RequestRandomness as u32, 0xec12ccee);
+	assert_eq!(Action::FulfilRequest as u32, 0x781dfefd);
+	assert_eq!(Action::ExpireRequest as u32, 0x87e9acc0);
+	assert_eq!(Action::Deposit as u32, 0xd0e30db0);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "Randomness.sol");
+}
+
+#[test]
+fn request_randomness_returns_request_id() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.execute_returns_encoded(0u64);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.execute_returns_encoded(1u64);
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 200);
+		});
+}
+
+#[test]
+fn request_randomness_fails_with_invalid_delay() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 11),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call Randomness::request_randomness failed")
+			});
+		});
+}
+
+#[test]
+fn fulfil_request_returns_random_word() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.execute_returns_encoded(0u64);
+
+			roll_to_with_relay_randomness(3, Some(RELAY_RANDOMNESS));
+			let random_word = Randomness::random_word(0).expect("randomness is available");
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				fulfil_request_input(0),
+			)
+			.execute_returns_encoded(random_word);
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+		});
+}
+
+#[test]
+fn requests_are_charged_for_their_reads() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// The request count, and the 3 reads and 4 writes of the dispatched call.
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.expect_cost(100 + 3 * 100 + 4 * 1_000)
+			.execute_returns_encoded(0u64);
+
+			roll_to_with_relay_randomness(3, Some(RELAY_RANDOMNESS));
+			let random_word = Randomness::random_word(0).expect("randomness is available");
+
+			// The request and the randomness result read by `random_word`, and the 2 reads and
+			// 3 writes of the dispatched call.
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				fulfil_request_input(0),
+			)
+			.expect_cost(2 * 100 + 2 * 100 + 3 * 1_000)
+			.execute_returns_encoded(random_word);
+		});
+}
+
+#[test]
+fn fulfil_request_fails_before_the_delay() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.execute_returns_encoded(0u64);
+
+			roll_to_with_relay_randomness(2, Some(RELAY_RANDOMNESS));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				fulfil_request_input(0),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call Randomness::fulfil_request failed")
+			});
+		});
+}
+
+#[test]
+fn fulfil_request_fails_for_another_caller() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.execute_returns_encoded(0u64);
+
+			roll_to_with_relay_randomness(3, Some(RELAY_RANDOMNESS));
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				fulfil_request_input(0),
+			)
+			.execute_reverts(|output| {
+				output.starts_with("Dispatched call Randomness::fulfil_request failed")
+			});
+		});
+}
+
+#[test]
+fn expire_request_returns_deposit_without_relay_randomness() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			Precompiles::<Runtime>::prepare_test(
+				Account::Alice,
+				Account::Precompile,
+				request_randomness_input(H256::repeat_byte(1), 2),
+			)
+			.execute_returns_encoded(0u64);
+
+			roll_to_with_relay_randomness(4, None);
+
+			Precompiles::<Runtime>::prepare_test(
+				Account::Bob,
+				Account::Precompile,
+				EvmDataWriter::new_with_selector(Action::ExpireRequest as u32)
+					.write(0u64)
+					.build(),
+			)
+			.execute_stops();
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+		});
+}
+
+#[test]
+fn deposit_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		Precompiles::<Runtime>::prepare_test(
+			Account::Alice,
+			Account::Precompile,
+			EvmDataWriter::new_with_selector(Action::Deposit as u32).build(),
+		)
+		.execute_returns_encoded(U256::from(100));
+	});
+}
+
+#[test]
+fn randomness_cannot_be_delegated() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context is the one of the calling contract (Charlie), while
+			// the caller is the account which called it (Alice).
+			let context = Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: U256::zero(),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&request_randomness_input(H256::repeat_byte(1), 2),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert_eq!(Balances::reserved_balance(&Account::Alice), 0);
+		});
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Encode relay chain staking calls, which can be sent to the relay chain with XCM `Transact`.
+/// Accounts are given as their 32 bytes id, and reward destinations as their SCALE encoding.
+/// This file is generated from the Rust implementation of the precompile.
+interface RelayEncoder {
+    /// Encode `staking.bond(controller, amount, reward_destination)`.
+    /// Selector: 6748727f
+    function encodeBond(bytes32 controller, uint256 amount, bytes memory reward_destination) external view returns (bytes memory);
+
+    /// Encode `staking.bond_extra(amount)`.
+    /// Selector: 813667a0
+    function encodeBondExtra(uint256 amount) external view returns (bytes memory);
+
+    /// Encode `staking.unbond(amount)`.
+    /// Selector: 51b14e57
+    function encodeUnbond(uint256 amount) external view returns (bytes memory);
+
+    /// Encode `staking.withdraw_unbonded(slashes)`.
+    /// Selector: d5ad108e
+    function encodeWithdrawUnbonded(uint32 slashes) external view returns (bytes memory);
+
+    /// Encode `staking.nominate(nominees)`.
+    /// Selector: dcf06883
+    function encodeNominate(bytes32[] memory nominees) external view returns (bytes memory);
+
+    /// Encode `staking.chill()`.
+    /// Selector: b5eaac43
+    function encodeChill() external view returns (bytes memory);
+
+    /// Encode `staking.set_payee(reward_destination)`.
+    /// Selector: 414be337
+    function encodeSetPayee(bytes memory reward_destination) external view returns (bytes memory);
+}
@@ -0,0 +1,135 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to encode relay chain staking calls, to be sent through XCM `Transact`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::traits::Get;
+use parity_scale_codec::Decode;
+use precompile_utils::{error, precompile, Bytes, EvmResult, PrecompileHandle};
+use sp_core::{H256, U256};
+use sp_runtime::AccountId32;
+use sp_std::{convert::TryInto, marker::PhantomData, vec::Vec};
+use xcm_primitives::relay_calls::{RewardDestination, StakingCall, StakingCallIndices};
+
+#[cfg(test)]
+mod tests;
+
+/// A precompile to encode relay chain staking calls.
+/// The indices of the staking pallet of the relay chain and of its calls are provided by
+/// `StakingIndices`, so that each runtime can target its own relay chain.
+pub struct RelayEncoderWrapper<StakingIndices>(PhantomData<StakingIndices>);
+
+/// Encode relay chain staking calls, which can be sent to the relay chain with XCM `Transact`.
+/// Accounts are given as their 32 bytes id, and reward destinations as their SCALE encoding.
+#[precompile(interface = "RelayEncoder")]
+impl<StakingIndices> RelayEncoderWrapper<StakingIndices>
+where
+	StakingIndices: Get<StakingCallIndices>,
+{
+	/// Encode `staking.bond(controller, amount, reward_destination)`.
+	#[precompile::public("encodeBond(bytes32,uint256,bytes)")]
+	#[precompile::view]
+	fn encode_bond(
+		_handle: &mut PrecompileHandle,
+		controller: H256,
+		amount: U256,
+		reward_destination: Bytes,
+	) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::Bond {
+			controller: account_id(controller),
+			value: amount_to_u128(amount)?,
+			payee: reward_destination_from_bytes(reward_destination)?,
+		}))
+	}
+
+	/// Encode `staking.bond_extra(amount)`.
+	#[precompile::public("encodeBondExtra(uint256)")]
+	#[precompile::view]
+	fn encode_bond_extra(_handle: &mut PrecompileHandle, amount: U256) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::BondExtra(amount_to_u128(
+			amount,
+		)?)))
+	}
+
+	/// Encode `staking.unbond(amount)`.
+	#[precompile::public("encodeUnbond(uint256)")]
+	#[precompile::view]
+	fn encode_unbond(_handle: &mut PrecompileHandle, amount: U256) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::Unbond(amount_to_u128(amount)?)))
+	}
+
+	/// Encode `staking.withdraw_unbonded(slashes)`.
+	#[precompile::public("encodeWithdrawUnbonded(uint32)")]
+	#[precompile::view]
+	fn encode_withdraw_unbonded(_handle: &mut PrecompileHandle, slashes: u32) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::WithdrawUnbonded(slashes)))
+	}
+
+	/// Encode `staking.nominate(nominees)`.
+	#[precompile::public("encodeNominate(bytes32[])")]
+	#[precompile::view]
+	fn encode_nominate(_handle: &mut PrecompileHandle, nominees: Vec<H256>) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::Nominate(
+			nominees.into_iter().map(account_id).collect(),
+		)))
+	}
+
+	/// Encode `staking.chill()`.
+	#[precompile::public("encodeChill()")]
+	#[precompile::view]
+	fn encode_chill(_handle: &mut PrecompileHandle) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::Chill))
+	}
+
+	/// Encode `staking.set_payee(reward_destination)`.
+	#[precompile::public("encodeSetPayee(bytes)")]
+	#[precompile::view]
+	fn encode_set_payee(
+		_handle: &mut PrecompileHandle,
+		reward_destination: Bytes,
+	) -> EvmResult<Bytes> {
+		Ok(Self::encode(StakingCall::SetPayee(
+			reward_destination_from_bytes(reward_destination)?,
+		)))
+	}
+
+	fn encode(call: StakingCall) -> Bytes {
+		Bytes(call.encode_call(&StakingIndices::get()))
+	}
+}
+
+fn account_id(account: H256) -> AccountId32 {
+	AccountId32::new(account.to_fixed_bytes())
+}
+
+fn amount_to_u128(amount: U256) -> EvmResult<u128> {
+	amount.try_into().map_err(|_| error("amount is too large"))
+}
+
+/// Decode a SCALE encoded reward destination, which must not be followed by other data.
+fn reward_destination_from_bytes(encoded: Bytes) -> EvmResult<RewardDestination> {
+	let mut data = &encoded.0[..];
+	let destination = RewardDestination::decode(&mut data)
+		.map_err(|_| error("failed to decode reward destination"))?;
+
+	if !data.is_empty() {
+		return Err(error("reward destination has trailing data"));
+	}
+
+	Ok(destination)
+}
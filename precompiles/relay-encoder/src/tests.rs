@@ -0,0 +1,235 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::*;
+
+use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
+use frame_support::parameter_types;
+use pallet_evm::Precompile;
+use parity_scale_codec::Encode;
+use precompile_utils::{testing::check_solidity_interface, EvmDataWriter};
+use sp_core::H160;
+
+parameter_types! {
+	pub const TestStakingIndices: StakingCallIndices = StakingCallIndices {
+		pallet: 6,
+		bond: 0,
+		bond_extra: 1,
+		unbond: 2,
+		withdraw_unbonded: 3,
+		nominate: 5,
+		chill: 6,
+		set_payee: 7,
+	};
+}
+
+type Encoder = RelayEncoderWrapper<TestStakingIndices>;
+
+fn execute(input: Vec<u8>) -> Result<PrecompileOutput, ExitError> {
+	let context = Context {
+		address: H160::from_low_u64_be(1),
+		caller: H160::repeat_byte(0xAA),
+		apparent_value: U256::zero(),
+	};
+
+	Encoder::execute(&input, None, &context)
+}
+
+fn assert_returns_encoded(input: Vec<u8>, expected: Vec<u8>) {
+	assert_eq!(
+		execute(input),
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: 0,
+			output: EvmDataWriter::new().write(Bytes(expected)).build(),
+			logs: Default::default(),
+		})
+	);
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::EncodeBond as u32, 0x6748727f);
+	assert_eq!(Action::EncodeBondExtra as u32, 0x813667a0);
+	assert_eq!(Action::EncodeUnbond as u32, 0x51b14e57);
+	assert_eq!(Action::EncodeWithdrawUnbonded as u32, 0xd5ad108e);
+	assert_eq!(Action::EncodeNominate as u32, 0xdcf06883);
+	assert_eq!(Action::EncodeChill as u32, 0xb5eaac43);
+	assert_eq!(Action::EncodeSetPayee as u32, 0x414be337);
+}
+
+#[test]
+fn solidity_interface_is_up_to_date() {
+	check_solidity_interface(Action::SOLIDITY_INTERFACE, "RelayEncoder.sol");
+}
+
+#[test]
+fn encode_bond() {
+	let controller = [1u8; 32];
+
+	// pallet, call, MultiAddress::Id, controller, compact(100), RewardDestination::Controller
+	let mut expected = vec![6, 0, 0];
+	expected.extend_from_slice(&controller);
+	expected.extend_from_slice(&[0x91, 0x01, 2]);
+
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeBond as u32)
+			.write(H256::from(controller))
+			.write(U256::from(100))
+			.write(Bytes(RewardDestination::Controller.encode()))
+			.build(),
+		expected,
+	);
+}
+
+#[test]
+fn encode_bond_to_account() {
+	let controller = [1u8; 32];
+	let payee = [2u8; 32];
+
+	let mut expected = vec![6, 0, 0];
+	expected.extend_from_slice(&controller);
+	expected.push(0x04);
+	expected.push(3);
+	expected.extend_from_slice(&payee);
+
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeBond as u32)
+			.write(H256::from(controller))
+			.write(U256::from(1))
+			.write(Bytes(
+				RewardDestination::Account(AccountId32::new(payee)).encode(),
+			))
+			.build(),
+		expected,
+	);
+}
+
+#[test]
+fn encode_bond_extra() {
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeBondExtra as u32)
+			.write(U256::from(100))
+			.build(),
+		vec![6, 1, 0x91, 0x01],
+	);
+}
+
+#[test]
+fn encode_unbond() {
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeUnbond as u32)
+			.write(U256::from(100))
+			.build(),
+		vec![6, 2, 0x91, 0x01],
+	);
+}
+
+#[test]
+fn encode_withdraw_unbonded() {
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeWithdrawUnbonded as u32)
+			.write(10u32)
+			.build(),
+		vec![6, 3, 10, 0, 0, 0],
+	);
+}
+
+#[test]
+fn encode_nominate() {
+	let mut expected = vec![6, 5, 0x08];
+	expected.push(0);
+	expected.extend_from_slice(&[1u8; 32]);
+	expected.push(0);
+	expected.extend_from_slice(&[2u8; 32]);
+
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeNominate as u32)
+			.write(vec![H256::repeat_byte(1), H256::repeat_byte(2)])
+			.build(),
+		expected,
+	);
+}
+
+#[test]
+fn encode_chill() {
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeChill as u32).build(),
+		vec![6, 6],
+	);
+}
+
+#[test]
+fn encode_set_payee() {
+	assert_returns_encoded(
+		EvmDataWriter::new_with_selector(Action::EncodeSetPayee as u32)
+			.write(Bytes(RewardDestination::Stash.encode()))
+			.build(),
+		vec![6, 7, 1],
+	);
+}
+
+#[test]
+fn encode_matches_call_indices() {
+	assert_eq!(
+		StakingCall::Chill.encode_call(&TestStakingIndices::get()),
+		vec![6, 6]
+	);
+	assert_eq!(
+		xcm_primitives::relay_calls::encode_as_derivative(
+			&xcm_primitives::relay_calls::UtilityCallIndices {
+				pallet: 16,
+				as_derivative: 1,
+			},
+			2,
+			vec![6, 6],
+		),
+		vec![16, 1, 2, 0, 6, 6]
+	);
+}
+
+#[test]
+fn rejects_invalid_reward_destination() {
+	assert_eq!(
+		execute(
+			EvmDataWriter::new_with_selector(Action::EncodeSetPayee as u32)
+				.write(Bytes(vec![5]))
+				.build()
+		),
+		Err(error("failed to decode reward destination"))
+	);
+
+	assert_eq!(
+		execute(
+			EvmDataWriter::new_with_selector(Action::EncodeSetPayee as u32)
+				.write(Bytes(vec![1, 0]))
+				.build()
+		),
+		Err(error("reward destination has trailing data"))
+	);
+}
+
+#[test]
+fn rejects_too_large_amount() {
+	assert_eq!(
+		execute(
+			EvmDataWriter::new_with_selector(Action::EncodeBondExtra as u32)
+				.write(U256::from(u128::MAX) + 1)
+				.build()
+		),
+		Err(error("amount is too large"))
+	);
+}
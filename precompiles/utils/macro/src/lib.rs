@@ -19,7 +19,12 @@ use proc_macro2::Literal;
 use quote::{quote, quote_spanned};
 use sha3::{Digest, Keccak256};
 use std::convert::TryInto;
-use syn::{parse_macro_input, spanned::Spanned, Expr, ExprLit, Ident, ItemEnum, Lit, LitStr};
+use syn::{
+	parse_macro_input, spanned::Spanned, AttributeArgs, Expr, ExprLit, Ident, ItemEnum, ItemImpl,
+	Lit, LitStr,
+};
+
+mod precompile;
 
 struct Bytes(Vec<u8>);
 
@@ -79,6 +84,20 @@ pub fn keccak256(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// Variants can be annotated with `#[view]` or `#[payable]`, in which case an `Action::modifier`
+/// function is also generated, returning the `precompile_utils::FunctionModifier` of the function
+/// called with a selector (`NonPayable` for variants without annotation). It is meant to be given
+/// to `precompile_utils::check_function_modifier`.
+///
+/// ```ignore
+/// #[generate_function_selector]
+/// enum Action {
+/// 	#[view]
+/// 	BalanceOf = "balanceOf(address)",
+/// 	Transfer = "transfer(address,uint256)",
+/// }
+/// ```
+///
 #[proc_macro_attribute]
 pub fn generate_function_selector(_: TokenStream, input: TokenStream) -> TokenStream {
 	let item = parse_macro_input!(input as ItemEnum);
@@ -94,7 +113,27 @@ pub fn generate_function_selector(_: TokenStream, input: TokenStream) -> TokenSt
 
 	let mut ident_expressions: Vec<Ident> = vec![];
 	let mut variant_expressions: Vec<Expr> = vec![];
+	let mut modifier_expressions: Vec<proc_macro2::TokenStream> = vec![];
+	let mut has_modifiers = false;
 	for variant in variants {
+		let is_view = variant.attrs.iter().any(|attr| attr.path.is_ident("view"));
+		let is_payable = variant
+			.attrs
+			.iter()
+			.any(|attr| attr.path.is_ident("payable"));
+		modifier_expressions.push(match (is_view, is_payable) {
+			(false, false) => quote!(::precompile_utils::FunctionModifier::NonPayable),
+			(true, false) => quote!(::precompile_utils::FunctionModifier::View),
+			(false, true) => quote!(::precompile_utils::FunctionModifier::Payable),
+			(true, true) => {
+				return quote_spanned! {
+					variant.span() => compile_error!("A function can't be both view and payable");
+				}
+				.into()
+			}
+		});
+		has_modifiers |= is_view || is_payable;
+
 		match variant.discriminant {
 			Some((_, Expr::Lit(ExprLit { lit, .. }))) => {
 				if let Lit::Str(lit_str) = lit {
@@ -130,6 +169,23 @@ pub fn generate_function_selector(_: TokenStream, input: TokenStream) -> TokenSt
 		}
 	}
 
+	let modifier_impl = if has_modifiers {
+		quote! {
+			impl #ident {
+				/// Modifier of the function called with this selector.
+				pub fn modifier(&self) -> ::precompile_utils::FunctionModifier {
+					match self {
+						#(
+							Self::#ident_expressions => #modifier_expressions,
+						)*
+					}
+				}
+			}
+		}
+	} else {
+		quote!()
+	};
+
 	(quote! {
 		#(#attrs)*
 		#[repr(u32)]
@@ -138,6 +194,54 @@ pub fn generate_function_selector(_: TokenStream, input: TokenStream) -> TokenSt
 				#ident_expressions = #variant_expressions,
 			)*
 		}
+
+		#modifier_impl
 	})
 	.into()
 }
+
+/// Turns an `impl` block into an EVM precompile.
+/// Functions annotated with `#[precompile::public("signature")]` are exposed to Solidity. They
+/// take a `&mut PrecompileHandle` followed by their Solidity arguments, and return an
+/// `EvmResult` of their Solidity outputs (a tuple for multiple outputs).
+/// Functions are non-payable unless annotated with `#[precompile::payable]`, and can be
+/// annotated with `#[precompile::view]` to be declared as such in the Solidity interface.
+/// The Solidity types of the outputs are deduced from their Rust types, or can be provided with
+/// `#[precompile::returns("type1,type2")]` for types implementing `EvmData` such as structs.
+///
+/// It generates the selector enum (named `Action` unless provided with `action = ".."`) with a
+/// `SOLIDITY_INTERFACE` constant, and the `pallet_evm::Precompile` implementation. The latter
+/// calls the generated `execute_with_static` function, which rejects calls to non-view functions
+/// in a static context, with `is_static: false` as the EVM version in use doesn't provide it.
+/// The crate tests should check the constant against the crate's `.sol` file with
+/// `precompile_utils::testing::check_solidity_interface`.
+///
+/// Usage:
+///
+/// ```ignore
+/// #[precompile_utils::precompile(interface = "Example")]
+/// impl<Runtime: pallet_evm::Config> ExamplePrecompile<Runtime> {
+/// 	/// Returns the stored value
+/// 	#[precompile::public("value()")]
+/// 	#[precompile::view]
+/// 	fn value(handle: &mut PrecompileHandle) -> EvmResult<U256> {
+/// 		RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+/// 			reads.read(|| Value::get())
+/// 		})
+/// 	}
+///
+/// 	/// Deposit the sent funds
+/// 	#[precompile::public("deposit(address)")]
+/// 	#[precompile::payable]
+/// 	fn deposit(handle: &mut PrecompileHandle, owner: Address) -> EvmResult {
+/// 		...
+/// 	}
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn precompile(attr: TokenStream, input: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(attr as AttributeArgs);
+	let item = parse_macro_input!(input as ItemImpl);
+
+	precompile::expand(args, item).into()
+}
@@ -0,0 +1,699 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+//! Implementation of the `#[precompile]` attribute macro.
+//!
+//! The macro is applied on an `impl` block whose functions annotated with
+//! `#[precompile::public("signature")]` are the functions exposed to Solidity. It generates:
+//! - the selector enum, with one variant per public function,
+//! - the `pallet_evm::Precompile` implementation, which reads the selector, checks the call
+//!   against the function modifier (`#[precompile::view]`, `#[precompile::payable]` or
+//!   non-payable by default), decodes the arguments with `EvmDataReader`, calls the
+//!   function and encodes its output with `EvmDataWriter`,
+//! - a `SOLIDITY_INTERFACE` constant of the selector enum containing the Solidity interface of
+//!   the precompile, which tests can compare against the `.sol` file of the crate with
+//!   `precompile_utils::testing::check_solidity_interface`.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use sha3::{Digest, Keccak256};
+use std::convert::TryInto;
+use syn::{
+	spanned::Spanned, Attribute, AttributeArgs, FnArg, GenericArgument, ImplItem, ImplItemMethod,
+	ItemImpl, Lit, Meta, MetaNameValue, NestedMeta, Pat, PathArguments, ReturnType, Type,
+};
+
+/// Arguments of the `#[precompile(...)]` attribute.
+struct PrecompileArgs {
+	/// Name of the Solidity interface.
+	interface: String,
+	/// Name of the generated selector enum.
+	action: String,
+}
+
+/// A function exposed to Solidity.
+struct PublicFunction {
+	ident: syn::Ident,
+	variant: syn::Ident,
+	signature: String,
+	selector: u32,
+	docs: Vec<String>,
+	payable: bool,
+	view: bool,
+	arguments: Vec<(syn::Ident, Type)>,
+	solidity_arguments: Vec<String>,
+	output: Output,
+	/// Solidity types of the outputs provided with `#[precompile::returns(..)]`.
+	solidity_returns: Option<Vec<String>>,
+}
+
+/// What a public function returns, deduced from its `EvmResult<..>` return type.
+enum Output {
+	/// `EvmResult` or `EvmResult<()>`: the call stops without output.
+	Nothing,
+	/// A single value.
+	Single(Box<Type>),
+	/// A tuple of values, encoded as multiple return values.
+	Multiple(Vec<Type>),
+}
+
+pub fn expand(args: AttributeArgs, mut item: ItemImpl) -> TokenStream {
+	let args = match parse_args(args) {
+		Ok(args) => args,
+		Err(e) => return e.to_compile_error(),
+	};
+
+	let interface_docs = extract_docs(&item.attrs);
+
+	let mut functions = vec![];
+	for impl_item in item.items.iter_mut() {
+		if let ImplItem::Method(method) = impl_item {
+			match parse_function(method) {
+				Ok(Some(function)) => functions.push(function),
+				Ok(None) => (),
+				Err(e) => return e.to_compile_error(),
+			}
+		}
+	}
+
+	if functions.is_empty() {
+		return syn::Error::new(
+			item.span(),
+			"expected at least one function annotated with #[precompile::public(..)]",
+		)
+		.to_compile_error();
+	}
+
+	for (i, function) in functions.iter().enumerate() {
+		if let Some(other) = functions[..i]
+			.iter()
+			.find(|other| other.selector == function.selector)
+		{
+			return syn::Error::new(
+				function.ident.span(),
+				format!(
+					"selector of `{}` collides with the selector of `{}`",
+					function.signature, other.signature
+				),
+			)
+			.to_compile_error();
+		}
+	}
+
+	let solidity = match solidity_interface(&args.interface, &interface_docs, &functions) {
+		Ok(solidity) => solidity,
+		Err(e) => return e.to_compile_error(),
+	};
+
+	let action_name = &args.action;
+	let action = format_ident!("{}", args.action);
+	let self_ty = &item.self_ty;
+	let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+	let variants: Vec<_> = functions.iter().map(|f| &f.variant).collect();
+	let selectors: Vec<_> = functions.iter().map(|f| f.selector).collect();
+	let signatures: Vec<_> = functions.iter().map(|f| &f.signature).collect();
+	let arms: Vec<_> = functions
+		.iter()
+		.map(|function| dispatch_arm(&action, function))
+		.collect();
+
+	quote! {
+		#[doc = "Selectors of the functions exposed by the precompile."]
+		#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+		#[repr(u32)]
+		pub enum #action {
+			#(
+				#[doc = #signatures]
+				#variants = #selectors,
+			)*
+		}
+
+		impl ::precompile_utils::num_enum::TryFromPrimitive for #action {
+			type Primitive = u32;
+
+			const NAME: &'static str = #action_name;
+
+			fn try_from_primitive(
+				number: u32,
+			) -> Result<Self, ::precompile_utils::num_enum::TryFromPrimitiveError<Self>> {
+				match number {
+					#(#selectors => Ok(Self::#variants),)*
+					_ => Err(::precompile_utils::num_enum::TryFromPrimitiveError { number }),
+				}
+			}
+		}
+
+		#item
+
+		impl #action {
+			/// Solidity interface of the precompile.
+			pub const SOLIDITY_INTERFACE: &'static str = #solidity;
+		}
+
+		impl #impl_generics #self_ty #where_clause {
+			/// Execute the precompile, `is_static` being whether it is called in a static context
+			/// (STATICCALL).
+			pub fn execute_with_static(
+				input: &[u8],
+				target_gas: Option<u64>,
+				context: &::evm::Context,
+				is_static: bool,
+			) -> Result<::evm::executor::PrecompileOutput, ::evm::ExitError> {
+				let mut input = ::precompile_utils::EvmDataReader::new(input);
+				let mut handle =
+					::precompile_utils::PrecompileHandle::new(target_gas, context, is_static);
+
+				match input.read_selector::<#action>()? {
+					#(#arms)*
+				}
+			}
+		}
+
+		impl #impl_generics ::pallet_evm::Precompile for #self_ty #where_clause {
+			fn execute(
+				input: &[u8],
+				target_gas: Option<u64>,
+				context: &::evm::Context,
+			) -> Result<::evm::executor::PrecompileOutput, ::evm::ExitError> {
+				// The EVM version currently used doesn't provide `is_static` to precompiles.
+				Self::execute_with_static(input, target_gas, context, false)
+			}
+		}
+	}
+}
+
+fn parse_args(args: AttributeArgs) -> syn::Result<PrecompileArgs> {
+	let mut interface = None;
+	let mut action = None;
+
+	for arg in args {
+		match arg {
+			NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+				path,
+				lit: Lit::Str(value),
+				..
+			})) => {
+				let target = if path.is_ident("interface") {
+					&mut interface
+				} else if path.is_ident("action") {
+					&mut action
+				} else {
+					return Err(syn::Error::new(
+						path.span(),
+						"expected `interface` or `action`",
+					));
+				};
+
+				if target.replace(value.value()).is_some() {
+					return Err(syn::Error::new(path.span(), "duplicated argument"));
+				}
+			}
+			arg => {
+				return Err(syn::Error::new(
+					arg.span(),
+					"expected `name = \"value\"` argument",
+				))
+			}
+		}
+	}
+
+	Ok(PrecompileArgs {
+		interface: interface.ok_or_else(|| {
+			syn::Error::new(Span::call_site(), "missing `interface = \"..\"` argument")
+		})?,
+		action: action.unwrap_or_else(|| "Action".into()),
+	})
+}
+
+/// Parse a method of the impl block. Returns `None` if it is not exposed to Solidity.
+/// The `#[precompile::..]` attributes are removed from the method.
+fn parse_function(method: &mut ImplItemMethod) -> syn::Result<Option<PublicFunction>> {
+	let mut signature = None;
+	let mut payable = false;
+	let mut view = false;
+	let mut returns = None;
+	let mut attrs = vec![];
+
+	for attr in method.attrs.drain(..) {
+		let segments: Vec<_> = attr
+			.path
+			.segments
+			.iter()
+			.map(|s| s.ident.to_string())
+			.collect();
+		let segments: Vec<_> = segments.iter().map(String::as_str).collect();
+
+		match segments[..] {
+			["precompile", "public"] => {
+				let lit: syn::LitStr = attr.parse_args()?;
+				signature = Some(lit);
+			}
+			["precompile", "payable"] => payable = true,
+			["precompile", "view"] => view = true,
+			["precompile", "returns"] => {
+				let lit: syn::LitStr = attr.parse_args()?;
+				returns = Some(lit);
+			}
+			["precompile", _] => {
+				return Err(syn::Error::new(
+					attr.span(),
+					"expected `precompile::public`, `precompile::view`, `precompile::payable` or \
+					`precompile::returns`",
+				))
+			}
+			_ => attrs.push(attr),
+		}
+	}
+	method.attrs = attrs;
+
+	let signature = match signature {
+		Some(signature) => signature,
+		None if payable || view || returns.is_some() => {
+			return Err(syn::Error::new(
+				method.sig.ident.span(),
+				"`precompile::view`, `precompile::payable` and `precompile::returns` require \
+				`precompile::public`",
+			))
+		}
+		None => return Ok(None),
+	};
+
+	if payable && view {
+		return Err(syn::Error::new(
+			method.sig.ident.span(),
+			"a function cannot be both `view` and `payable`",
+		));
+	}
+
+	let (_, solidity_arguments) = parse_signature(&signature.value())
+		.ok_or_else(|| syn::Error::new(signature.span(), "malformed Solidity signature"))?;
+
+	let mut inputs = method.sig.inputs.iter();
+
+	match inputs.next() {
+		Some(FnArg::Typed(_)) => (),
+		_ => {
+			return Err(syn::Error::new(
+				method.sig.span(),
+				"the first argument of a public function must be a `&mut PrecompileHandle`",
+			))
+		}
+	}
+
+	let mut arguments = vec![];
+	for input in inputs {
+		match input {
+			FnArg::Typed(pat_type) => match &*pat_type.pat {
+				Pat::Ident(pat) => arguments.push((pat.ident.clone(), (*pat_type.ty).clone())),
+				pat => {
+					return Err(syn::Error::new(
+						pat.span(),
+						"arguments of public functions must be identifiers",
+					))
+				}
+			},
+			FnArg::Receiver(receiver) => {
+				return Err(syn::Error::new(
+					receiver.span(),
+					"public functions cannot take `self`",
+				))
+			}
+		}
+	}
+
+	if arguments.len() != solidity_arguments.len() {
+		return Err(syn::Error::new(
+			signature.span(),
+			format!(
+				"signature has {} arguments while the function takes {}",
+				solidity_arguments.len(),
+				arguments.len()
+			),
+		));
+	}
+
+	let output = parse_output(&method.sig.output)?;
+
+	let solidity_returns = match returns {
+		Some(returns) => {
+			let types = split_types(&returns.value())
+				.ok_or_else(|| syn::Error::new(returns.span(), "malformed Solidity types"))?;
+
+			let expected = match &output {
+				Output::Nothing => 0,
+				Output::Single(_) => 1,
+				Output::Multiple(types) => types.len(),
+			};
+			if types.len() != expected {
+				return Err(syn::Error::new(
+					returns.span(),
+					format!(
+						"{} Solidity output types are provided while the function returns {}",
+						types.len(),
+						expected
+					),
+				));
+			}
+
+			Some(types)
+		}
+		None => None,
+	};
+
+	let selector = u32::from_be_bytes(
+		Keccak256::digest(signature.value().as_bytes())[..4]
+			.try_into()
+			.expect("slice of length 4; qed"),
+	);
+
+	Ok(Some(PublicFunction {
+		ident: method.sig.ident.clone(),
+		variant: format_ident!("{}", camel_case(&method.sig.ident.to_string())),
+		signature: signature.value(),
+		selector,
+		docs: extract_docs(&method.attrs),
+		payable,
+		view,
+		arguments,
+		solidity_arguments,
+		output,
+		solidity_returns,
+	}))
+}
+
+/// Split `name(type1,type2)` into the function name and the argument types.
+fn parse_signature(signature: &str) -> Option<(String, Vec<String>)> {
+	let open = signature.find('(')?;
+	if !signature.ends_with(')') {
+		return None;
+	}
+
+	let name = &signature[..open];
+	if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+		return None;
+	}
+
+	let arguments = split_types(&signature[open + 1..signature.len() - 1])?;
+
+	Some((name.to_string(), arguments))
+}
+
+/// Split a comma separated list of Solidity types (`uint8,(bool,bytes)[]`).
+/// Commas inside tuples are not considered as separators.
+fn split_types(inner: &str) -> Option<Vec<String>> {
+	let mut arguments = vec![];
+	let mut depth = 0usize;
+	let mut start = 0;
+
+	for (i, c) in inner.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth = depth.checked_sub(1)?,
+			',' if depth == 0 => {
+				arguments.push(inner[start..i].to_string());
+				start = i + 1;
+			}
+			c if c.is_whitespace() => return None,
+			_ => (),
+		}
+	}
+
+	if depth != 0 {
+		return None;
+	}
+
+	if !inner.is_empty() {
+		arguments.push(inner[start..].to_string());
+	}
+
+	if arguments.iter().any(String::is_empty) {
+		return None;
+	}
+
+	Some(arguments)
+}
+
+fn parse_output(output: &ReturnType) -> syn::Result<Output> {
+	let ty = match output {
+		ReturnType::Type(_, ty) => ty,
+		ReturnType::Default => {
+			return Err(syn::Error::new(
+				output.span(),
+				"public functions must return an `EvmResult`",
+			))
+		}
+	};
+
+	let segment = match &**ty {
+		Type::Path(path) => path.path.segments.last(),
+		_ => None,
+	}
+	.filter(|segment| segment.ident == "EvmResult" || segment.ident == "Result")
+	.ok_or_else(|| syn::Error::new(ty.span(), "public functions must return an `EvmResult`"))?;
+
+	let inner = match &segment.arguments {
+		PathArguments::None => return Ok(Output::Nothing),
+		PathArguments::AngleBracketed(args) => match args.args.first() {
+			Some(GenericArgument::Type(ty)) => ty,
+			_ => return Err(syn::Error::new(args.span(), "expected a type")),
+		},
+		PathArguments::Parenthesized(args) => {
+			return Err(syn::Error::new(args.span(), "expected a type"))
+		}
+	};
+
+	Ok(match inner {
+		Type::Tuple(tuple) if tuple.elems.is_empty() => Output::Nothing,
+		Type::Tuple(tuple) => Output::Multiple(tuple.elems.iter().cloned().collect()),
+		ty => Output::Single(Box::new(ty.clone())),
+	})
+}
+
+/// Generate the match arm of a public function in `execute`.
+fn dispatch_arm(action: &syn::Ident, function: &PublicFunction) -> TokenStream {
+	let PublicFunction {
+		ident,
+		variant,
+		arguments,
+		payable,
+		view,
+		..
+	} = function;
+
+	let modifier = if *payable {
+		quote!(::precompile_utils::FunctionModifier::Payable)
+	} else if *view {
+		quote!(::precompile_utils::FunctionModifier::View)
+	} else {
+		quote!(::precompile_utils::FunctionModifier::NonPayable)
+	};
+
+	let arguments_count = arguments.len();
+	let names: Vec<_> = arguments.iter().map(|(name, _)| name).collect();
+	let types: Vec<_> = arguments.iter().map(|(_, ty)| ty).collect();
+
+	let output = match &function.output {
+		Output::Nothing => quote_spanned! { ident.span() =>
+			Self::#ident(&mut handle, #(#names),*)?;
+			Ok(handle.stopped())
+		},
+		Output::Single(_) => quote_spanned! { ident.span() =>
+			let output = Self::#ident(&mut handle, #(#names),*)?;
+			Ok(handle.returned(
+				::precompile_utils::EvmDataWriter::new().write(output).build()
+			))
+		},
+		Output::Multiple(types) => {
+			let indices = (0..types.len()).map(syn::Index::from);
+			quote_spanned! { ident.span() =>
+				let output = Self::#ident(&mut handle, #(#names),*)?;
+				Ok(handle.returned(
+					::precompile_utils::EvmDataWriter::new()
+						#(.write(output.#indices))*
+						.build()
+				))
+			}
+		}
+	};
+
+	quote! {
+		#action::#variant => {
+			handle.check_function_modifier(#modifier)?;
+			input.expect_arguments(#arguments_count)?;
+			#(
+				let #names: #types = input.read()?;
+			)*
+			#output
+		}
+	}
+}
+
+/// Build the Solidity interface of the precompile.
+fn solidity_interface(
+	interface: &str,
+	interface_docs: &[String],
+	functions: &[PublicFunction],
+) -> syn::Result<String> {
+	let mut out = String::new();
+	out.push_str("// SPDX-License-Identifier: GPL-3.0-only\n");
+	out.push_str("pragma solidity >=0.8.0;\n\n");
+	out.push_str("/// @author The Moonbeam Team\n");
+	for doc in interface_docs {
+		out.push_str(&format!("/// {}\n", doc.trim()));
+	}
+	out.push_str("/// This file is generated from the Rust implementation of the precompile.\n");
+	out.push_str(&format!("interface {} {{\n", interface));
+
+	for (i, function) in functions.iter().enumerate() {
+		if i > 0 {
+			out.push('\n');
+		}
+
+		for doc in &function.docs {
+			out.push_str(&format!("    /// {}\n", doc.trim()));
+		}
+		out.push_str(&format!("    /// Selector: {:08x}\n", function.selector));
+
+		let name = &function.signature[..function.signature.find('(').unwrap_or(0)];
+		let arguments: Vec<_> = function
+			.solidity_arguments
+			.iter()
+			.zip(function.arguments.iter())
+			.map(|(ty, (ident, _))| {
+				format!(
+					"{}{} {}",
+					ty,
+					data_location(ty),
+					ident.to_string().trim_start_matches('_')
+				)
+			})
+			.collect();
+
+		let mutability = if function.payable {
+			" payable"
+		} else if function.view {
+			" view"
+		} else {
+			""
+		};
+
+		let returns = match (&function.solidity_returns, &function.output) {
+			(Some(returns), _) => returns.clone(),
+			(None, Output::Nothing) => vec![],
+			(None, Output::Single(ty)) => vec![solidity_type(ty)?],
+			(None, Output::Multiple(types)) => types
+				.iter()
+				.map(solidity_type)
+				.collect::<syn::Result<_>>()?,
+		};
+		let returns = if returns.is_empty() {
+			String::new()
+		} else {
+			let returns: Vec<_> = returns
+				.iter()
+				.map(|ty| format!("{}{}", ty, data_location(ty)))
+				.collect();
+			format!(" returns ({})", returns.join(", "))
+		};
+
+		out.push_str(&format!(
+			"    function {}({}) external{}{};\n",
+			name,
+			arguments.join(", "),
+			mutability,
+			returns
+		));
+	}
+
+	out.push_str("}\n");
+
+	Ok(out)
+}
+
+/// Data location required by Solidity for reference types in external functions.
+fn data_location(ty: &str) -> &'static str {
+	if ty.ends_with(']') || ty == "bytes" || ty == "string" || ty.starts_with('(') {
+		" memory"
+	} else {
+		""
+	}
+}
+
+/// Solidity type corresponding to a Rust type implementing `EvmData`.
+fn solidity_type(ty: &Type) -> syn::Result<String> {
+	let error = || {
+		syn::Error::new(
+			ty.span(),
+			"cannot deduce the Solidity type of this type, expected one of \
+			`bool`, `u8`, `u16`, `u32`, `u64`, `u128`, `U256`, `H256`, `Address`, `Bytes`, \
+			`String`, `Vec<_>` or `[_; N]`, or to be provided with `precompile::returns`",
+		)
+	};
+
+	let segment = match ty {
+		Type::Path(path) => path.path.segments.last().ok_or_else(error)?,
+		Type::Array(array) => {
+			let len = &array.len;
+			return Ok(format!("{}[{}]", solidity_type(&array.elem)?, quote!(#len)));
+		}
+		_ => return Err(error()),
+	};
+
+	Ok(match segment.ident.to_string().as_str() {
+		"bool" => "bool".into(),
+		"u8" => "uint8".into(),
+		"u16" => "uint16".into(),
+		"u32" => "uint32".into(),
+		"u64" => "uint64".into(),
+		"u128" => "uint128".into(),
+		"U256" => "uint256".into(),
+		"H256" => "bytes32".into(),
+		"Address" => "address".into(),
+		"Bytes" => "bytes".into(),
+		"String" => "string".into(),
+		"Vec" => match &segment.arguments {
+			PathArguments::AngleBracketed(args) => match args.args.first() {
+				Some(GenericArgument::Type(inner)) => format!("{}[]", solidity_type(inner)?),
+				_ => return Err(error()),
+			},
+			_ => return Err(error()),
+		},
+		_ => return Err(error()),
+	})
+}
+
+fn extract_docs(attrs: &[Attribute]) -> Vec<String> {
+	attrs
+		.iter()
+		.filter(|attr| attr.path.is_ident("doc"))
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::NameValue(MetaNameValue {
+				lit: Lit::Str(doc), ..
+			})) => Some(doc.value()),
+			_ => None,
+		})
+		.collect()
+}
+
+fn camel_case(name: &str) -> String {
+	name.split('_')
+		.map(|word| {
+			let mut chars = word.chars();
+			match chars.next() {
+				Some(first) => first.to_uppercase().chain(chars).collect(),
+				None => String::new(),
+			}
+		})
+		.collect()
+}
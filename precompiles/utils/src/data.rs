@@ -15,6 +15,7 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{error, EvmResult};
+use alloc::string::String;
 use core::{any::type_name, ops::Range};
 use sp_core::{H160, H256, U256};
 use sp_std::{convert::TryInto, vec, vec::Vec};
@@ -37,8 +38,50 @@ impl From<Address> for H160 {
 	}
 }
 
+/// The `bytes` type of Solidity.
+/// It is different from `Vec<u8>`, which is the `uint8[]` type of Solidity where each element
+/// is padded to 32 bytes, while `bytes` is tightly packed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+	/// Interpret the bytes as an UTF-8 string.
+	pub fn as_str(&self) -> Result<&str, core::str::Utf8Error> {
+		core::str::from_utf8(&self.0)
+	}
+}
+
+impl From<Vec<u8>> for Bytes {
+	fn from(a: Vec<u8>) -> Bytes {
+		Bytes(a)
+	}
+}
+
+impl From<&[u8]> for Bytes {
+	fn from(a: &[u8]) -> Bytes {
+		Bytes(a.to_vec())
+	}
+}
+
+impl From<&str> for Bytes {
+	fn from(a: &str) -> Bytes {
+		a.as_bytes().into()
+	}
+}
+
+impl From<Bytes> for Vec<u8> {
+	fn from(a: Bytes) -> Vec<u8> {
+		a.0
+	}
+}
+
+/// Selector of the `Error(string)` revert reason of Solidity.
+pub const ERROR_SELECTOR: u32 = 0x08c3_79a0;
+
 /// Wrapper around an EVM input slice, helping to parse it.
 /// Provide functions to parse common types.
+/// Offsets of dynamic types are relative to the start of the slice, which is the start of the
+/// arguments once the selector has been read.
 #[derive(Clone, Copy, Debug)]
 pub struct EvmDataReader<'a> {
 	input: &'a [u8],
@@ -53,10 +96,13 @@ impl<'a> EvmDataReader<'a> {
 
 	/// Check the input has at least the correct amount of arguments before the end (32 bytes values).
 	pub fn expect_arguments(&self, args: usize) -> EvmResult {
-		if self.input.len() >= self.cursor + args * 32 {
-			Ok(())
-		} else {
-			Err(error("input doesn't match expected length"))
+		let expected = args
+			.checked_mul(32)
+			.and_then(|len| len.checked_add(self.cursor));
+
+		match expected {
+			Some(expected) if self.input.len() >= expected => Ok(()),
+			_ => Err(error("input doesn't match expected length")),
 		}
 	}
 
@@ -81,6 +127,8 @@ impl<'a> EvmDataReader<'a> {
 
 	/// Parse (4 bytes) selector.
 	/// Returns an error if trying to parse out of bounds.
+	/// The following reads are relative to the end of the selector, as offsets of dynamic
+	/// arguments don't account for it.
 	pub fn read_selector<T>(&mut self) -> EvmResult<T>
 	where
 		T: num_enum::TryFromPrimitive<Primitive = u32>,
@@ -90,6 +138,9 @@ impl<'a> EvmDataReader<'a> {
 			self.read_raw_bytes(4)
 				.map_err(|_| error("tried to parse selector out of bounds"))?,
 		);
+
+		*self = self.frame_from_cursor();
+
 		T::try_from_primitive(u32::from_be_bytes(buffer)).map_err(|_| {
 			log::trace!(
 				target: "precompile-utils",
@@ -100,6 +151,55 @@ impl<'a> EvmDataReader<'a> {
 		})
 	}
 
+	/// Read an offset and return a reader of the data it points to, whose offsets are relative to
+	/// the pointed location.
+	/// Returns an error if the offset points outside of the input.
+	pub fn read_pointer(&mut self) -> EvmResult<Self> {
+		let offset: usize = self
+			.read::<U256>()
+			.map_err(|_| error("tried to parse offset out of bounds"))?
+			.try_into()
+			.map_err(|_| error("offset is too large"))?;
+
+		if offset >= self.input.len() {
+			return Err(error("offset points out of bounds"));
+		}
+
+		Ok(Self {
+			input: &self.input[offset..],
+			cursor: 0,
+		})
+	}
+
+	/// Read the length of an array or of bytes, and check the input contains at least
+	/// `item_size` bytes for each item after it.
+	fn read_length(&mut self, item_size: usize) -> EvmResult<usize> {
+		let length: usize = self
+			.read::<U256>()
+			.map_err(|_| error("tried to parse length out of bounds"))?
+			.try_into()
+			.map_err(|_| error("length is too large"))?;
+
+		// Items always take some space, which prevents iterating over a huge length.
+		let required = length
+			.checked_mul(item_size.max(1))
+			.ok_or_else(|| error("length is too large"))?;
+
+		if self.input.len().saturating_sub(self.cursor) < required {
+			return Err(error("length exceeds input size"));
+		}
+
+		Ok(length)
+	}
+
+	/// Reader of the data following the cursor, with offsets relative to the cursor location.
+	fn frame_from_cursor(&self) -> Self {
+		Self {
+			input: self.input.get(self.cursor..).unwrap_or_default(),
+			cursor: 0,
+		}
+	}
+
 	/// Move the reading cursor with provided length, and return a range from the previous cursor
 	/// location to the new one.
 	/// Checks cursor overflows.
@@ -117,17 +217,22 @@ impl<'a> EvmDataReader<'a> {
 }
 
 /// Help build an EVM input/output data.
+/// Dynamic data is appended after the static data, and offsets pointing to it are computed when
+/// building the output.
 #[derive(Clone, Debug)]
 pub struct EvmDataWriter {
 	pub(crate) data: Vec<u8>,
-	arrays: Vec<Array>,
+	offset_data: Vec<OffsetDatum>,
+	selector: Option<u32>,
 }
 
+/// Dynamic data which will be appended after the static data.
 #[derive(Clone, Debug)]
-struct Array {
+struct OffsetDatum {
+	/// Position of the offset to override once the location of the data is known.
 	offset_position: usize,
+	/// Complete encoding of the data, with offsets relative to its start.
 	data: Vec<u8>,
-	inner_arrays: Vec<Array>,
 }
 
 impl EvmDataWriter {
@@ -135,36 +240,53 @@ impl EvmDataWriter {
 	pub fn new() -> Self {
 		Self {
 			data: vec![],
-			arrays: vec![],
+			offset_data: vec![],
+			selector: None,
 		}
 	}
 
-	/// Return the built data.
-	pub fn build(mut self) -> Vec<u8> {
-		Self::build_arrays(&mut self.data, self.arrays, 0);
+	/// Creates a new output builder starting with a selector, such as the call data of a Solidity
+	/// function or the data of a Solidity custom error.
+	pub fn new_with_selector<T: Into<u32>>(selector: T) -> Self {
+		Self::new().write_selector(selector)
+	}
 
-		self.data
+	/// Data of a Solidity `Error(string)` revert with provided reason, as produced by
+	/// `revert("reason")` or `require(condition, "reason")`.
+	/// The EVM version in use (evm 0.30, pinned by the frontier branch of the current Polkadot
+	/// version) only lets precompiles fail with an `ExitError` carrying a text, so this data
+	/// can't be returned by a precompile until `PrecompileFailure::Revert` is available (evm
+	/// 0.33). Until then it allows to compare reverts of contracts calling precompiles, and
+	/// `PrecompileTester::execute_reverts_with_output` to check the revert a precompile will
+	/// return.
+	pub fn revert_reason<T: AsRef<[u8]>>(reason: T) -> Vec<u8> {
+		Self::new_with_selector(ERROR_SELECTOR)
+			.write(Bytes::from(reason.as_ref()))
+			.build()
 	}
 
-	/// Build the array into data.
-	/// `global_offset` represents the start of the frame we are modifying.
-	/// While the main data will have a `global_offset` of 0, inner arrays will have a
-	/// `global_offset` corresponding to the start its parent array size data.
-	fn build_arrays(output: &mut Vec<u8>, arrays: Vec<Array>, global_offset: usize) {
-		for mut array in arrays {
-			let offset_position = array.offset_position;
+	/// Return the built data.
+	pub fn build(mut self) -> Vec<u8> {
+		for datum in self.offset_data {
+			let offset_position = datum.offset_position;
 			let offset_position_end = offset_position + 32;
-			let free_space_offset = output.len() + global_offset;
+			let free_space_offset = self.data.len();
 
 			// Override dummy offset to the offset it will be in the final output.
 			U256::from(free_space_offset)
-				.to_big_endian(&mut output[offset_position..offset_position_end]);
-
-			// Build inner arrays if any.
-			Self::build_arrays(&mut array.data, array.inner_arrays, free_space_offset);
+				.to_big_endian(&mut self.data[offset_position..offset_position_end]);
 
 			// Append this data at the end of the current output.
-			output.append(&mut array.data);
+			self.data.extend_from_slice(&datum.data);
+		}
+
+		match self.selector {
+			Some(selector) => {
+				let mut output = selector.to_be_bytes().to_vec();
+				output.append(&mut self.data);
+				output
+			}
+			None => self.data,
 		}
 	}
 
@@ -177,10 +299,11 @@ impl EvmDataWriter {
 
 	/// Write a selector.
 	/// The provided type must impl `Into<u32>`.
-	/// Doesn't handle any alignement checks, should be used only when adding the initial
-	/// selector of a Solidity call data.
-	pub fn write_selector<T: Into<u32>>(self, value: T) -> Self {
-		self.write_raw_bytes(&value.into().to_be_bytes())
+	/// The selector is put at the start of the output, and isn't accounted for in offsets as
+	/// expected for the call data of a Solidity function.
+	pub fn write_selector<T: Into<u32>>(mut self, value: T) -> Self {
+		self.selector = Some(value.into());
+		self
 	}
 
 	/// Write data of requested type.
@@ -188,6 +311,20 @@ impl EvmDataWriter {
 		T::write(&mut self, value);
 		self
 	}
+
+	/// Write a dummy offset which will point to the provided data once built.
+	/// The data must be the complete encoding of a dynamic type, with offsets relative to its
+	/// start.
+	pub fn write_pointer(&mut self, data: Vec<u8>) {
+		let offset_position = self.data.len();
+		// 0xff = When debugging it makes spoting offset values easier.
+		H256::write(self, H256::repeat_byte(0xff));
+
+		self.offset_data.push(OffsetDatum {
+			offset_position,
+			data,
+		});
+	}
 }
 
 impl Default for EvmDataWriter {
@@ -200,6 +337,15 @@ impl Default for EvmDataWriter {
 pub trait EvmData: Sized {
 	fn read(reader: &mut EvmDataReader) -> EvmResult<Self>;
 	fn write(writer: &mut EvmDataWriter, value: Self);
+
+	/// Whether the type is static. Dynamic types are encoded as an offset to their data.
+	fn has_static_size() -> bool;
+
+	/// Size taken by the type in the static data: its size if it is static, or the size of an
+	/// offset otherwise.
+	fn head_size() -> usize {
+		32
+	}
 }
 
 impl EvmData for H256 {
@@ -217,6 +363,10 @@ impl EvmData for H256 {
 	fn write(writer: &mut EvmDataWriter, value: Self) {
 		writer.data.extend_from_slice(&value.as_bytes());
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 impl EvmData for Address {
@@ -234,6 +384,10 @@ impl EvmData for Address {
 	fn write(writer: &mut EvmDataWriter, value: Self) {
 		H256::write(writer, value.0.into());
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 impl EvmData for U256 {
@@ -253,6 +407,10 @@ impl EvmData for U256 {
 		value.to_big_endian(&mut buffer);
 		writer.data.extend_from_slice(&buffer);
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 macro_rules! impl_evmdata_for_uints {
@@ -279,6 +437,10 @@ macro_rules! impl_evmdata_for_uints {
 					buffer[32 - core::mem::size_of::<Self>()..].copy_from_slice(&value.to_be_bytes());
 					writer.data.extend_from_slice(&buffer);
 				}
+
+				fn has_static_size() -> bool {
+					true
+				}
 			}
 		)*
 	};
@@ -304,6 +466,10 @@ impl EvmData for u8 {
 
 		writer.data.extend_from_slice(&buffer);
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 impl EvmData for bool {
@@ -321,59 +487,195 @@ impl EvmData for bool {
 
 		writer.data.extend_from_slice(&buffer);
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
-impl<T: EvmData> EvmData for Vec<T> {
+impl EvmData for Bytes {
 	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
-		let array_start: usize = reader
-			.read::<U256>()
-			.map_err(|_| error("tried to parse array offset out of bounds"))?
-			.try_into()
-			.map_err(|_| error("array offset is too large"))?;
+		let mut inner = reader.read_pointer()?;
 
-		// We temporarily move the cursor to the offset, we'll set it back afterward.
-		let original_cursor = reader.cursor;
-		reader.cursor = array_start;
+		// Each byte takes 1 byte, the content being right-padded to a multiple of 32 bytes.
+		let length = inner.read_length(1)?;
+		let padded_length = length
+			.checked_add(31)
+			.ok_or_else(|| error("length is too large"))?
+			/ 32 * 32;
 
-		let array_size: usize = reader
-			.read::<U256>()
-			.map_err(|_| error("tried to parse array length out of bounds"))?
-			.try_into()
-			.map_err(|_| error("array length is too large"))?;
+		let data = inner
+			.read_raw_bytes(padded_length)
+			.map_err(|_| error("tried to parse bytes out of bounds"))?;
+
+		Ok(Bytes(data[..length].to_vec()))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let length = value.0.len();
+		let padded_length = (length + 31) / 32 * 32;
+
+		let mut data = EvmDataWriter::new().write(U256::from(length)).build();
+		data.extend_from_slice(&value.0);
+		data.resize(32 + padded_length, 0);
+
+		writer.write_pointer(data);
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+}
+
+impl EvmData for String {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let bytes: Bytes = reader.read()?;
+
+		String::from_utf8(bytes.0).map_err(|_| error("string is not valid UTF-8"))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		Bytes::write(writer, Bytes(value.into_bytes()));
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+}
+
+impl<T: EvmData> EvmData for Vec<T> {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let mut inner = reader.read_pointer()?;
+		let array_size = inner.read_length(T::head_size())?;
 
-		let mut array = vec![];
+		// Offsets of dynamic items are relative to the start of the items, after the length.
+		let mut items = inner.frame_from_cursor();
 
+		let mut array = Vec::with_capacity(array_size);
 		for _ in 0..array_size {
-			array.push(reader.read()?);
+			array.push(items.read()?);
 		}
 
-		// We set back the cursor to its original location.
-		reader.cursor = original_cursor;
-
 		Ok(array)
 	}
 
 	fn write(writer: &mut EvmDataWriter, value: Self) {
-		let offset_position = writer.data.len();
-		H256::write(writer, H256::repeat_byte(0xff));
-		// 0xff = When debugging it makes spoting offset values easier.
+		let mut data = EvmDataWriter::new().write(U256::from(value.len())).build();
 
-		let mut inner_writer = EvmDataWriter::new();
+		let mut items = EvmDataWriter::new();
+		for item in value {
+			items = items.write(item);
+		}
+		data.append(&mut items.build());
 
-		// Write length.
-		inner_writer = inner_writer.write(U256::from(value.len()));
+		writer.write_pointer(data);
+	}
 
-		// Write elements of array.
-		for inner in value {
-			inner_writer = inner_writer.write(inner);
-		}
+	fn has_static_size() -> bool {
+		false
+	}
+}
 
-		let array = Array {
-			offset_position,
-			data: inner_writer.data,
-			inner_arrays: inner_writer.arrays,
+impl<T: EvmData, const N: usize> EvmData for [T; N] {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let mut inner = if Self::has_static_size() {
+			*reader
+		} else {
+			reader.read_pointer()?
 		};
 
-		writer.arrays.push(array);
+		let mut array = Vec::with_capacity(N);
+		for _ in 0..N {
+			array.push(inner.read()?);
+		}
+
+		if Self::has_static_size() {
+			reader.cursor = inner.cursor;
+		}
+
+		array
+			.try_into()
+			.map_err(|_| error("fixed array has wrong size"))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		if Self::has_static_size() {
+			for item in core::array::IntoIter::new(value) {
+				T::write(writer, item);
+			}
+		} else {
+			let mut items = EvmDataWriter::new();
+			for item in core::array::IntoIter::new(value) {
+				items = items.write(item);
+			}
+			writer.write_pointer(items.build());
+		}
+	}
+
+	fn has_static_size() -> bool {
+		N == 0 || T::has_static_size()
+	}
+
+	fn head_size() -> usize {
+		if Self::has_static_size() {
+			N * T::head_size()
+		} else {
+			32
+		}
 	}
 }
+
+/// Tuples are static if all their fields are static, in which case they are encoded inline.
+/// Otherwise they are encoded as an offset to their data.
+macro_rules! impl_evmdata_for_tuples {
+	($(($($ident:ident),+),)*) => {
+		$(
+			impl<$($ident: EvmData),+> EvmData for ($($ident,)+) {
+				#[allow(non_snake_case)]
+				fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+					if Self::has_static_size() {
+						Ok(($($ident::read(reader)?,)+))
+					} else {
+						let mut inner = reader.read_pointer()?;
+						Ok(($($ident::read(&mut inner)?,)+))
+					}
+				}
+
+				#[allow(non_snake_case)]
+				fn write(writer: &mut EvmDataWriter, value: Self) {
+					let ($($ident,)+) = value;
+
+					if Self::has_static_size() {
+						$($ident::write(writer, $ident);)+
+					} else {
+						let inner = EvmDataWriter::new()$(.write($ident))+;
+						writer.write_pointer(inner.build());
+					}
+				}
+
+				fn has_static_size() -> bool {
+					$($ident::has_static_size())&&+
+				}
+
+				fn head_size() -> usize {
+					if Self::has_static_size() {
+						0 $(+ $ident::head_size())+
+					} else {
+						32
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_evmdata_for_tuples!(
+	(A),
+	(A, B),
+	(A, B, C),
+	(A, B, C, D),
+	(A, B, C, D, E),
+	(A, B, C, D, E, F),
+	(A, B, C, D, E, F, G),
+	(A, B, C, D, E, F, G, H),
+);
@@ -18,19 +18,30 @@
 
 extern crate alloc;
 
-use evm::ExitError;
+use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::{
-	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
-	traits::Get,
+	dispatch::{DispatchError, Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{CallMetadata, Get, GetCallMetadata},
 };
 use pallet_evm::{GasWeightMapping, Log};
-use sp_core::{H160, H256};
+use sp_core::{H160, H256, U256};
 use sp_std::{marker::PhantomData, vec, vec::Vec};
 
 mod data;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod xcm;
 
-pub use data::{Address, EvmData, EvmDataReader, EvmDataWriter};
-pub use precompile_utils_macro::{generate_function_selector, keccak256};
+pub use data::{Address, Bytes, EvmData, EvmDataReader, EvmDataWriter, ERROR_SELECTOR};
+pub use precompile_utils_macro::{generate_function_selector, keccak256, precompile};
+
+// Used by the code generated by the `#[precompile]` macro.
+#[doc(hidden)]
+pub use num_enum;
+
+// Allows the `#[precompile]` macro to refer to this crate inside its own tests.
+#[cfg(test)]
+extern crate self as precompile_utils;
 
 #[cfg(test)]
 mod tests;
@@ -39,10 +50,29 @@ mod tests;
 pub type EvmResult<T = ()> = Result<T, ExitError>;
 
 /// Return an error with provided (static) text.
+/// It is the reason of the `Error(string)` revert of the call once precompiles can revert with an
+/// output (see `EvmDataWriter::revert_reason`).
 pub fn error<T: Into<alloc::borrow::Cow<'static, str>>>(text: T) -> ExitError {
 	ExitError::Other(text.into())
 }
 
+/// Descriptive reason of a failed dispatch, including the name of the pallet error if any.
+pub fn dispatch_error_reason(error: DispatchError) -> alloc::string::String {
+	match error {
+		DispatchError::Module {
+			message: Some(message),
+			..
+		} => message.into(),
+		DispatchError::Module {
+			index,
+			error,
+			message: None,
+		} => alloc::format!("Module(index: {}, error: {})", index, error),
+		DispatchError::Other(message) if !message.is_empty() => message.into(),
+		error => alloc::format!("{:?}", error),
+	}
+}
+
 /// Builder for PrecompileOutput.
 #[derive(Clone, Debug)]
 pub struct LogsBuilder {
@@ -156,10 +186,11 @@ pub struct RuntimeHelper<Runtime>(PhantomData<Runtime>);
 impl<Runtime> RuntimeHelper<Runtime>
 where
 	Runtime: pallet_evm::Config,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 {
 	/// Try to dispatch a Substrate call.
-	/// Return an error if there are not enough gas, or if the call fails.
+	/// Return an error if there are not enough gas, or if the call fails, in which case the error
+	/// names the call and the pallet error.
 	/// If successful returns the used gas using the Runtime GasWeightMapping.
 	pub fn try_dispatch<Call>(
 		origin: <Runtime::Call as Dispatchable>::Origin,
@@ -171,6 +202,10 @@ where
 	{
 		let call = Runtime::Call::from(call);
 		let dispatch_info = call.get_dispatch_info();
+		let CallMetadata {
+			pallet_name,
+			function_name,
+		} = call.get_call_metadata();
 
 		// Make sure there is enough gas.
 		if let Some(gas_limit) = target_gas {
@@ -187,7 +222,14 @@ where
 		// computations.
 		let used_weight = call
 			.dispatch(origin)
-			.map_err(|e| error(alloc::format!("Dispatched call failed with error: {:?}", e)))?
+			.map_err(|e| {
+				error(alloc::format!(
+					"Dispatched call {}::{} failed: {}",
+					pallet_name,
+					function_name,
+					dispatch_error_reason(e.error)
+				))
+			})?
 			.actual_weight;
 
 		// Return used weight by converting weight to gas.
@@ -214,6 +256,118 @@ where
 			<Runtime as frame_system::Config>::DbWeight::get().read,
 		)
 	}
+
+	/// Cost of provided amount of Substrate DB reads in gas.
+	pub fn db_reads_gas_cost(reads: u64) -> u64 {
+		<Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
+			<Runtime as frame_system::Config>::DbWeight::get().reads(reads),
+		)
+	}
+
+	/// Run a closure reading storage items through the provided `DeclaredReads`, which records in
+	/// the gasometer the cost of each declared read before running it.
+	/// The amount of items a read declares is not checked against the storage accesses it
+	/// performs, it must match the pallet code it calls.
+	/// Returns an error if there is not enough gas to pay for a read.
+	pub fn with_declared_reads<R>(
+		gasometer: &mut Gasometer,
+		f: impl FnOnce(&mut DeclaredReads) -> EvmResult<R>,
+	) -> EvmResult<R> {
+		f(&mut DeclaredReads::new(gasometer, Self::db_read_gas_cost()))
+	}
+}
+
+/// Reader of storage items inside `RuntimeHelper::with_declared_reads`.
+/// Each read declares the amount of storage items it accesses, so that paths reading more items
+/// are charged more. The cost is recorded before the read is run, which doesn't happen if it runs
+/// out of gas.
+#[derive(Debug)]
+pub struct DeclaredReads<'a> {
+	gasometer: &'a mut Gasometer,
+	read_cost: u64,
+	count: u64,
+}
+
+impl<'a> DeclaredReads<'a> {
+	/// Record the cost of reads in `gasometer`, each read costing `read_cost` gas.
+	pub fn new(gasometer: &'a mut Gasometer, read_cost: u64) -> Self {
+		Self {
+			gasometer,
+			read_cost,
+			count: 0,
+		}
+	}
+
+	/// Read one storage item with provided closure (usually a storage getter).
+	pub fn read<R>(&mut self, f: impl FnOnce() -> R) -> EvmResult<R> {
+		self.read_many(1, f)
+	}
+
+	/// Read at most provided amount of storage items with provided closure, for functions of
+	/// pallets reading multiple storage items. All of them are charged, even if the closure reads
+	/// less.
+	pub fn read_many<R>(&mut self, items: u64, f: impl FnOnce() -> R) -> EvmResult<R> {
+		let cost = self
+			.read_cost
+			.checked_mul(items)
+			.ok_or(ExitError::OutOfGas)?;
+		self.gasometer.record_cost(cost)?;
+		self.count = self.count.saturating_add(items);
+
+		Ok(f())
+	}
+
+	/// Amount of storage items declared so far.
+	pub fn count(&self) -> u64 {
+		self.count
+	}
+}
+
+/// Modifier of a precompile function, with the same meaning as in Solidity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionModifier {
+	/// Function which doesn't modify the state. It can be called in a static context but
+	/// doesn't accept value.
+	View,
+	/// Function which can modify the state but doesn't accept value.
+	NonPayable,
+	/// Function which can modify the state and accepts value.
+	Payable,
+}
+
+/// Returns an error if the call is not allowed for a function with provided modifier:
+/// - non-view functions cannot be called in a static context,
+/// - only payable functions can receive value.
+///
+/// `Precompile::execute` doesn't provide `is_static` in the EVM version currently used, so
+/// precompiles take it in an `execute_with_static` function, which `Precompile::execute` calls
+/// with `false` until it does. The value check is enforced in all cases.
+pub fn check_function_modifier(
+	context: &Context,
+	is_static: bool,
+	modifier: FunctionModifier,
+) -> EvmResult {
+	if is_static && modifier != FunctionModifier::View {
+		return Err(error("can't call non-static function in static context"));
+	}
+
+	if modifier != FunctionModifier::Payable && context.apparent_value > U256::zero() {
+		return Err(error("function is not payable"));
+	}
+
+	Ok(())
+}
+
+/// Returns an error if the precompile at `code_address` is executed with DELEGATECALL or
+/// CALLCODE, in which case the context (caller and storage address) is the one of the calling
+/// contract. Precompiles acting on behalf of their caller must not allow it, otherwise any
+/// contract could act on behalf of the accounts calling it.
+pub fn check_not_delegated(code_address: H160, context: &Context) -> EvmResult {
+	if context.address != code_address {
+		return Err(error("cannot be called with DELEGATECALL or CALLCODE"));
+	}
+
+	Ok(())
 }
 
 /// Custom Gasometer to record costs in precompiles.
@@ -298,3 +452,67 @@ impl Gasometer {
 		})
 	}
 }
+
+/// State of a precompile call, given to the functions of a `#[precompile]` impl block.
+#[derive(Debug)]
+pub struct PrecompileHandle<'a> {
+	/// Gasometer of the call.
+	pub gasometer: Gasometer,
+	/// Context of the call.
+	pub context: &'a Context,
+	/// Whether the call is made in a static context (STATICCALL). See `check_function_modifier`.
+	pub is_static: bool,
+	logs: Vec<Log>,
+}
+
+impl<'a> PrecompileHandle<'a> {
+	/// Create a new handle for a call with provided gas limit and context, made in a static
+	/// context if `is_static`.
+	pub fn new(target_gas: Option<u64>, context: &'a Context, is_static: bool) -> Self {
+		Self {
+			gasometer: Gasometer::new(target_gas),
+			context,
+			is_static,
+			logs: vec![],
+		}
+	}
+
+	/// Returns an error if the call is not allowed for a function with provided modifier.
+	pub fn check_function_modifier(&self, modifier: FunctionModifier) -> EvmResult {
+		check_function_modifier(self.context, self.is_static, modifier)
+	}
+
+	/// Record the cost of the logs and add them to the output.
+	pub fn record_logs(&mut self, logs: Vec<Log>) -> EvmResult {
+		self.gasometer.record_log_costs(&logs)?;
+		self.logs.extend(logs);
+
+		Ok(())
+	}
+
+	/// Add logs to the output without charging them, for logs whose cost has already been paid
+	/// (for example by a subcall which emitted them).
+	pub fn add_logs(&mut self, logs: Vec<Log>) {
+		self.logs.extend(logs);
+	}
+
+	/// Output of a call returning data.
+	pub fn returned(self, output: Vec<u8>) -> PrecompileOutput {
+		PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: self.gasometer.used_gas(),
+			output,
+			logs: self.logs,
+		}
+	}
+
+	/// Output of a call without returned data.
+	pub fn stopped(self) -> PrecompileOutput {
+		PrecompileOutput {
+			exit_status: ExitSucceed::Stopped,
+			cost: self.gasometer.used_gas(),
+			output: Default::default(),
+			logs: self.logs,
+		}
+	}
+}
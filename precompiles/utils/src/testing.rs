@@ -0,0 +1,460 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers to test precompiles, enabled with the `testing` feature.
+//!
+//! ```ignore
+//! ExtBuilder::<Runtime>::default()
+//! 	.with_balances(vec![(Account::Alice, 1000)])
+//! 	.build()
+//! 	.execute_with(|| {
+//! 		Precompiles::<Runtime>::prepare_test(
+//! 			Account::Alice,
+//! 			Account::Precompile,
+//! 			EvmDataWriter::new_with_selector(Action::Transfer)
+//! 				.write(Address(Account::Bob.into()))
+//! 				.write(U256::from(400))
+//! 				.build(),
+//! 		)
+//! 		.expect_log(LogsBuilder::new(Account::Precompile.into()).log3(..).build())
+//! 		.execute_returns(EvmDataWriter::new().write(true).build());
+//! 	});
+//! ```
+
+use crate::{check_not_delegated, EvmData, EvmDataWriter};
+use codec::{Decode, Encode, MaxEncodedLen};
+use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
+use frame_support::{parameter_types, traits::GenesisBuild};
+use pallet_evm::{AddressMapping, Log, Precompile, PrecompileSet};
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+use sp_std::marker::PhantomData;
+
+/// Fluent builder of a call to a precompile set, asserting its result.
+/// The expectations (cost, logs) are checked by the `execute_*` functions, which panic if the
+/// result doesn't match.
+#[must_use]
+pub struct PrecompilesTester<P> {
+	to: H160,
+	from: H160,
+	data: Vec<u8>,
+	value: U256,
+	target_gas: Option<u64>,
+	expected_cost: Option<u64>,
+	expected_logs: Option<Vec<Log>>,
+	_phantom: PhantomData<P>,
+}
+
+impl<P: PrecompileSet> PrecompilesTester<P> {
+	/// Prepare a call from `from` to the precompile at `to` with provided input.
+	pub fn new(from: impl Into<H160>, to: impl Into<H160>, data: Vec<u8>) -> Self {
+		Self {
+			to: to.into(),
+			from: from.into(),
+			data,
+			value: U256::zero(),
+			target_gas: None,
+			expected_cost: None,
+			expected_logs: None,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Value transfered with the call.
+	pub fn with_value(mut self, value: impl Into<U256>) -> Self {
+		self.value = value.into();
+		self
+	}
+
+	/// Gas limit of the call. No limit by default.
+	pub fn with_target_gas(mut self, target_gas: Option<u64>) -> Self {
+		self.target_gas = target_gas;
+		self
+	}
+
+	/// Expect the call to cost provided gas.
+	pub fn expect_cost(mut self, cost: u64) -> Self {
+		self.expected_cost = Some(cost);
+		self
+	}
+
+	/// Expect the call to emit no logs.
+	pub fn expect_no_logs(mut self) -> Self {
+		self.expected_logs = Some(vec![]);
+		self
+	}
+
+	/// Expect the call to emit provided logs (usually built with `LogsBuilder`), in addition to
+	/// the ones already expected.
+	pub fn expect_log(mut self, logs: impl Into<Vec<Log>>) -> Self {
+		self.expected_logs
+			.get_or_insert_with(Vec::new)
+			.extend(logs.into());
+		self
+	}
+
+	/// Execute the call and return its raw result, without checking expectations.
+	pub fn execute(&self) -> Option<Result<PrecompileOutput, ExitError>> {
+		let context = Context {
+			address: self.to,
+			caller: self.from,
+			apparent_value: self.value,
+		};
+
+		P::execute(self.to, &self.data, self.target_gas, &context)
+	}
+
+	/// Execute the call and return its output, panicking if it fails or if expectations are not
+	/// met.
+	pub fn execute_some(self) -> PrecompileOutput {
+		let output = match self.execute() {
+			Some(Ok(output)) => output,
+			Some(Err(e)) => panic!("call to precompile failed with {:?}", e),
+			None => panic!("no precompile at address {:?}", self.to),
+		};
+
+		if let Some(cost) = self.expected_cost {
+			assert_eq!(output.cost, cost, "used gas doesn't match expected cost");
+		}
+
+		if let Some(logs) = &self.expected_logs {
+			assert_eq!(&output.logs, logs, "emitted logs don't match expected logs");
+		}
+
+		output
+	}
+
+	/// Execute the call and check it returns the provided data.
+	pub fn execute_returns(self, output: Vec<u8>) {
+		let result = self.execute_some();
+
+		assert_eq!(result.exit_status, ExitSucceed::Returned);
+		assert_eq!(
+			result.output, output,
+			"output doesn't match expected output"
+		);
+	}
+
+	/// Execute the call and check it returns the provided value, encoded with `EvmDataWriter`.
+	pub fn execute_returns_encoded<T: EvmData>(self, value: T) {
+		self.execute_returns(EvmDataWriter::new().write(value).build())
+	}
+
+	/// Execute the call and check it succeeds without returning data.
+	pub fn execute_stops(self) {
+		let result = self.execute_some();
+
+		assert_eq!(result.exit_status, ExitSucceed::Stopped);
+		assert!(result.output.is_empty(), "output is not empty");
+	}
+
+	/// Execute the call and check it fails with provided error.
+	pub fn execute_error(self, error: ExitError) {
+		match self.execute() {
+			Some(Err(e)) => assert_eq!(e, error),
+			Some(Ok(output)) => panic!("call to precompile succeeded with {:?}", output),
+			None => panic!("no precompile at address {:?}", self.to),
+		}
+	}
+
+	/// Execute the call and check it fails with a reason satisfying provided check.
+	pub fn execute_reverts(self, check: impl Fn(&str) -> bool) {
+		match self.execute() {
+			Some(Err(ExitError::Other(reason))) => {
+				assert!(check(&reason), "unexpected revert reason: {}", reason)
+			}
+			Some(Err(e)) => panic!("call to precompile failed with unexpected error {:?}", e),
+			Some(Ok(output)) => panic!("call to precompile succeeded with {:?}", output),
+			None => panic!("no precompile at address {:?}", self.to),
+		}
+	}
+
+	/// Execute the call and check it reverts with provided output, such as
+	/// `EvmDataWriter::revert_reason("reason")`. Precompiles can only fail with a text in the EVM
+	/// version in use, which is compared as the `Error(string)` revert it is once they can revert
+	/// with an output.
+	pub fn execute_reverts_with_output(self, output: Vec<u8>) {
+		match self.execute() {
+			Some(Err(ExitError::Other(reason))) => assert_eq!(
+				EvmDataWriter::revert_reason(reason.as_bytes()),
+				output,
+				"unexpected revert reason: {}",
+				reason
+			),
+			Some(Err(e)) => panic!("call to precompile failed with unexpected error {:?}", e),
+			Some(Ok(output)) => panic!("call to precompile succeeded with {:?}", output),
+			None => panic!("no precompile at address {:?}", self.to),
+		}
+	}
+
+	/// Check there is no precompile at the address.
+	pub fn execute_none(self) {
+		assert!(
+			self.execute().is_none(),
+			"found a precompile at address {:?}",
+			self.to
+		);
+	}
+}
+
+/// Allows to prepare a call from the precompile set type, as `Precompiles::prepare_test(..)`.
+pub trait PrecompileTesterExt: PrecompileSet + Sized {
+	fn prepare_test(
+		from: impl Into<H160>,
+		to: impl Into<H160>,
+		data: Vec<u8>,
+	) -> PrecompilesTester<Self> {
+		PrecompilesTester::new(from, to, data)
+	}
+}
+
+impl<T: PrecompileSet> PrecompileTesterExt for T {}
+
+/// Builder of the externalities of a mock runtime including `frame_system` and
+/// `pallet_balances`.
+pub struct ExtBuilder<Runtime: pallet_balances::Config> {
+	// endowed accounts with balances
+	balances: Vec<(Runtime::AccountId, Runtime::Balance)>,
+}
+
+impl<Runtime: pallet_balances::Config> Default for ExtBuilder<Runtime> {
+	fn default() -> Self {
+		Self { balances: vec![] }
+	}
+}
+
+impl<Runtime: pallet_balances::Config> ExtBuilder<Runtime> {
+	/// Endow accounts with provided balances.
+	pub fn with_balances(mut self, balances: Vec<(Runtime::AccountId, Runtime::Balance)>) -> Self {
+		self.balances = balances;
+		self
+	}
+
+	/// Build the externalities, at block 1.
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.expect("Frame system builds valid default genesis config");
+
+		<pallet_balances::GenesisConfig<Runtime> as GenesisBuild<Runtime>>::assimilate_storage(
+			&pallet_balances::GenesisConfig::<Runtime> {
+				balances: self.balances,
+			},
+			&mut t,
+		)
+		.expect("Pallet balances storage can be assimilated");
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| frame_system::Pallet::<Runtime>::set_block_number(1u32.into()));
+		ext
+	}
+}
+
+/// Address of the precompile in the mock runtimes, as `Account::Precompile`.
+pub const PRECOMPILE_ADDRESS: u64 = 1;
+
+/// A simple account type, used as `AccountId` of the mock runtimes.
+#[derive(
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Clone,
+	Encode,
+	Decode,
+	Debug,
+	MaxEncodedLen,
+	Serialize,
+	Deserialize,
+	derive_more::Display,
+)]
+pub enum Account {
+	Alice,
+	Bob,
+	Charlie,
+	Bogus,
+	Precompile,
+}
+
+impl Default for Account {
+	fn default() -> Self {
+		Self::Bogus
+	}
+}
+
+impl AddressMapping<Account> for Account {
+	fn into_account_id(h160_account: H160) -> Account {
+		match h160_account {
+			a if a == H160::repeat_byte(0xAA) => Self::Alice,
+			a if a == H160::repeat_byte(0xBB) => Self::Bob,
+			a if a == H160::repeat_byte(0xCC) => Self::Charlie,
+			a if a == H160::from_low_u64_be(PRECOMPILE_ADDRESS) => Self::Precompile,
+			_ => Self::Bogus,
+		}
+	}
+}
+
+impl From<Account> for H160 {
+	fn from(x: Account) -> H160 {
+		match x {
+			Account::Alice => H160::repeat_byte(0xAA),
+			Account::Bob => H160::repeat_byte(0xBB),
+			Account::Charlie => H160::repeat_byte(0xCC),
+			Account::Precompile => H160::from_low_u64_be(PRECOMPILE_ADDRESS),
+			Account::Bogus => Default::default(),
+		}
+	}
+}
+
+impl From<H160> for Account {
+	fn from(x: H160) -> Account {
+		Account::into_account_id(x)
+	}
+}
+
+impl From<Account> for H256 {
+	fn from(x: Account) -> H256 {
+		let x: H160 = x.into();
+		x.into()
+	}
+}
+
+/// Precompile set of a mock runtime, with the precompile `P` at `Account::Precompile`.
+/// Like in the runtimes, it cannot be called through `DELEGATECALL`.
+pub struct TestPrecompiles<P>(PhantomData<P>);
+
+impl<P: Precompile> PrecompileSet for TestPrecompiles<P> {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Option<Result<PrecompileOutput, ExitError>> {
+		match address {
+			a if a == H160::from_low_u64_be(PRECOMPILE_ADDRESS) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| P::execute(input, target_gas, context)),
+			),
+			_ => None,
+		}
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+	pub const MinimumPeriod: u64 = 5;
+	pub const ExistentialDeposit: u128 = 0;
+}
+
+/// Implement the configuration of `frame_system`, `pallet_timestamp`, `pallet_balances` and
+/// `pallet_evm` shared by the mock runtimes of the precompiles, with `Account` as account id and
+/// `$precompiles` as precompile set. `$runtime` must be declared with `construct_runtime!`,
+/// including `System` and `Balances`. Storage accesses are free unless a `RuntimeDbWeight` is
+/// provided as third argument.
+///
+/// ```ignore
+/// precompile_utils::impl_mock_runtime_config!(Runtime, TestPrecompiles<FooWrapper<Runtime>>);
+/// ```
+#[macro_export]
+macro_rules! impl_mock_runtime_config {
+	($runtime:ident, $precompiles:ty) => {
+		$crate::impl_mock_runtime_config!($runtime, $precompiles, ());
+	};
+	($runtime:ident, $precompiles:ty, $db_weight:ty) => {
+		impl frame_system::Config for $runtime {
+			type BaseCallFilter = frame_support::traits::Everything;
+			type DbWeight = $db_weight;
+			type Origin = Origin;
+			type Index = u64;
+			type BlockNumber = u64;
+			type Call = Call;
+			type Hash = sp_core::H256;
+			type Hashing = sp_runtime::traits::BlakeTwo256;
+			type AccountId = $crate::testing::Account;
+			type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+			type Header = sp_runtime::testing::Header;
+			type Event = Event;
+			type BlockHashCount = $crate::testing::BlockHashCount;
+			type Version = ();
+			type PalletInfo = PalletInfo;
+			type AccountData = pallet_balances::AccountData<u128>;
+			type OnNewAccount = ();
+			type OnKilledAccount = ();
+			type SystemWeightInfo = ();
+			type BlockWeights = ();
+			type BlockLength = ();
+			type SS58Prefix = $crate::testing::SS58Prefix;
+			type OnSetCode = ();
+		}
+
+		impl pallet_timestamp::Config for $runtime {
+			type Moment = u64;
+			type OnTimestampSet = ();
+			type MinimumPeriod = $crate::testing::MinimumPeriod;
+			type WeightInfo = ();
+		}
+
+		impl pallet_balances::Config for $runtime {
+			type MaxReserves = ();
+			type ReserveIdentifier = ();
+			type MaxLocks = ();
+			type Balance = u128;
+			type Event = Event;
+			type DustRemoval = ();
+			type ExistentialDeposit = $crate::testing::ExistentialDeposit;
+			type AccountStore = System;
+			type WeightInfo = ();
+		}
+
+		impl pallet_evm::Config for $runtime {
+			type FeeCalculator = ();
+			type GasWeightMapping = ();
+			type CallOrigin = pallet_evm::EnsureAddressRoot<$crate::testing::Account>;
+			type WithdrawOrigin = pallet_evm::EnsureAddressNever<$crate::testing::Account>;
+			type AddressMapping = $crate::testing::Account;
+			type Currency = Balances;
+			type Event = Event;
+			type Runner = pallet_evm::runner::stack::Runner<Self>;
+			type Precompiles = $precompiles;
+			type ChainId = ();
+			type OnChargeTransaction = ();
+			type BlockGasLimit = ();
+			type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+			type FindAuthor = ();
+		}
+	};
+}
+
+/// Check that the `.sol` file at `path`, relative to the crate being tested, contains the
+/// Solidity interface generated by the `#[precompile]` macro. Running the tests with the
+/// `UPDATE_SOLIDITY_INTERFACES` environment variable set rewrites the file instead.
+pub fn check_solidity_interface(interface: &str, path: &str) {
+	let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").expect("tests are run by cargo");
+	let path = std::path::Path::new(&manifest_dir).join(path);
+
+	if std::env::var_os("UPDATE_SOLIDITY_INTERFACES").is_some() {
+		std::fs::write(&path, interface).expect("Solidity interface can be written");
+		return;
+	}
+
+	let existing = std::fs::read_to_string(&path).unwrap_or_default();
+	assert!(
+		existing == interface,
+		"{} is out of date, run the tests with UPDATE_SOLIDITY_INTERFACES=1 to update it",
+		path.display()
+	);
+}
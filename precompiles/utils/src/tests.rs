@@ -15,6 +15,7 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
+use pallet_evm::Precompile;
 use sp_core::{H256, U256};
 
 fn u256_repeat_byte(byte: u8) -> U256 {
@@ -353,7 +354,7 @@ fn read_address_array_size_too_big() {
 	let mut reader = EvmDataReader::new(&writer_output);
 	match reader.read::<Vec<Address>>() {
 		Ok(_) => panic!("should not parse correctly"),
-		Err(ExitError::Other(err)) => assert_eq!(err, "tried to parse H160 out of bounds"),
+		Err(ExitError::Other(err)) => assert_eq!(err, "length exceeds input size"),
 		Err(_) => panic!("unexpected error"),
 	}
 }
@@ -384,11 +385,10 @@ fn write_address_nested_array() {
 
 	assert_eq!(reader.read::<U256>().expect("read offset"), 0x20.into()); // 0x00
 	assert_eq!(reader.read::<U256>().expect("read size"), 2.into()); // 0x20
-	assert_eq!(reader.read::<U256>().expect("read 1st offset"), 0x80.into()); // 0x40
-	assert_eq!(
-		reader.read::<U256>().expect("read 2st offset"),
-		0x100.into()
-	); // 0x60
+
+	// Offsets of inner arrays are relative to the start of the items of the outer array (0x40).
+	assert_eq!(reader.read::<U256>().expect("read 1st offset"), 0x40.into()); // 0x40
+	assert_eq!(reader.read::<U256>().expect("read 2st offset"), 0xc0.into()); // 0x60
 	assert_eq!(reader.read::<U256>().expect("read 1st size"), 3.into()); // 0x80
 	assert_eq!(reader.read::<Address>().expect("read 1-1"), array[0][0]); // 0xA0
 	assert_eq!(reader.read::<Address>().expect("read 1-2"), array[0][1]); // 0xC0
@@ -482,3 +482,878 @@ fn read_multiple_arrays() {
 	let parsed: Vec<H256> = reader.read().expect("to correctly parse Vec<H256>");
 	assert_eq!(array2, parsed);
 }
+
+#[test]
+fn read_array_after_selector() {
+	#[precompile_utils_macro::generate_function_selector]
+	#[derive(Debug, PartialEq, num_enum::TryFromPrimitive)]
+	enum FakeAction {
+		Action1 = "action1(address[])",
+	}
+
+	let array = vec![
+		Address(H160::repeat_byte(0x11)),
+		Address(H160::repeat_byte(0x22)),
+	];
+	let input = EvmDataWriter::new()
+		.write_selector(FakeAction::Action1 as u32)
+		.write(array.clone())
+		.build();
+
+	// Offsets don't account for the selector.
+	assert_eq!(&input[4..36], H256::from_low_u64_be(0x20).as_bytes());
+
+	let mut reader = EvmDataReader::new(&input);
+	assert_eq!(
+		reader.read_selector::<FakeAction>().unwrap(),
+		FakeAction::Action1
+	);
+	let parsed: Vec<Address> = reader.read().expect("to correctly parse Vec<Address>");
+
+	assert_eq!(array, parsed);
+}
+
+#[test]
+fn write_bytes() {
+	let value = Bytes::from(&[0x42u8; 40][..]);
+
+	let writer_output = EvmDataWriter::new().write(value).build();
+
+	assert_eq!(writer_output.len(), 0x80);
+	assert_eq!(
+		&writer_output[0x00..0x20],
+		H256::from_low_u64_be(0x20).as_bytes()
+	);
+	assert_eq!(
+		&writer_output[0x20..0x40],
+		H256::from_low_u64_be(40).as_bytes()
+	);
+	assert_eq!(&writer_output[0x40..0x68], &[0x42u8; 40][..]);
+	// Right-padded with zeros.
+	assert_eq!(&writer_output[0x68..0x80], &[0u8; 24][..]);
+}
+
+#[test]
+fn read_bytes() {
+	let value = Bytes::from(&[0x42u8; 40][..]);
+	let writer_output = EvmDataWriter::new().write(value.clone()).build();
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: Bytes = reader.read().expect("to correctly parse Bytes");
+
+	assert_eq!(value, parsed);
+}
+
+#[test]
+fn read_empty_bytes() {
+	let writer_output = EvmDataWriter::new().write(Bytes::default()).build();
+	assert_eq!(writer_output.len(), 0x40);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: Bytes = reader.read().expect("to correctly parse Bytes");
+
+	assert_eq!(parsed, Bytes::default());
+}
+
+#[test]
+fn read_bytes_missing_padding() {
+	let value = Bytes::from(&[0x42u8; 40][..]);
+	let writer_output = EvmDataWriter::new().write(value).build();
+
+	let mut reader = EvmDataReader::new(&writer_output[..0x70]);
+	match reader.read::<Bytes>() {
+		Ok(_) => panic!("should not parse correctly"),
+		Err(ExitError::Other(err)) => assert_eq!(err, "tried to parse bytes out of bounds"),
+		Err(_) => panic!("unexpected error"),
+	}
+}
+
+#[test]
+fn read_string() {
+	let value = String::from("Moonbeam is a smart contract platform");
+	let writer_output = EvmDataWriter::new().write(value.clone()).build();
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: String = reader.read().expect("to correctly parse String");
+
+	assert_eq!(value, parsed);
+}
+
+#[test]
+fn read_invalid_utf8_string() {
+	let writer_output = EvmDataWriter::new().write(Bytes(vec![0xff, 0xfe])).build();
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	match reader.read::<String>() {
+		Ok(_) => panic!("should not parse correctly"),
+		Err(ExitError::Other(err)) => assert_eq!(err, "string is not valid UTF-8"),
+		Err(_) => panic!("unexpected error"),
+	}
+}
+
+#[test]
+fn write_static_fixed_array() {
+	let array = [U256::from(1), U256::from(2), U256::from(3)];
+
+	let writer_output = EvmDataWriter::new().write(array).write(true).build();
+
+	// Static fixed arrays are encoded inline.
+	assert_eq!(writer_output.len(), 0x80);
+	assert_eq!(
+		&writer_output[0x40..0x60],
+		H256::from_low_u64_be(3).as_bytes()
+	);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: [U256; 3] = reader.read().expect("to correctly parse [U256; 3]");
+	assert_eq!(parsed, array);
+	assert_eq!(reader.read::<bool>(), Ok(true));
+}
+
+#[test]
+fn read_dynamic_fixed_array() {
+	let array = [Bytes::from("foo"), Bytes::from("bar")];
+
+	let writer_output = EvmDataWriter::new().write(array.clone()).build();
+
+	// Offset, 2 item offsets, 2 items of 2 words each.
+	assert_eq!(writer_output.len(), 0xe0);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: [Bytes; 2] = reader.read().expect("to correctly parse [Bytes; 2]");
+	assert_eq!(parsed, array);
+}
+
+#[test]
+fn write_static_tuple() {
+	let value = (42u8, Address(H160::repeat_byte(0x11)));
+
+	let writer_output = EvmDataWriter::new().write(value).write(7u32).build();
+
+	// Static tuples are encoded inline.
+	assert_eq!(
+		writer_output,
+		EvmDataWriter::new()
+			.write(42u8)
+			.write(Address(H160::repeat_byte(0x11)))
+			.write(7u32)
+			.build()
+	);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	assert_eq!(reader.read::<(u8, Address)>(), Ok(value));
+	assert_eq!(reader.read::<u32>(), Ok(7));
+}
+
+#[test]
+fn read_dynamic_tuple() {
+	let value = (
+		1u8,
+		vec![Bytes::from("foo"), Bytes::from(&[0x42u8; 33][..])],
+	);
+
+	let writer_output = EvmDataWriter::new().write(value.clone()).build();
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	// Dynamic tuples are encoded as an offset to their data.
+	assert_eq!(reader.read::<U256>(), Ok(0x20.into()));
+	assert_eq!(reader.read::<U256>(), Ok(1.into()));
+	assert_eq!(reader.read::<U256>(), Ok(0x40.into()));
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: (u8, Vec<Bytes>) = reader.read().expect("to correctly parse tuple");
+	assert_eq!(parsed, value);
+}
+
+#[test]
+fn write_multilocation() {
+	use ::xcm::v1::{Junction, Junctions, MultiLocation, NetworkId};
+
+	let location = MultiLocation {
+		parents: 1,
+		interior: Junctions::X2(
+			Junction::Parachain(2),
+			Junction::AccountKey20 {
+				network: NetworkId::Any,
+				key: [0x11; 20],
+			},
+		),
+	};
+
+	let writer_output = EvmDataWriter::new().write(location).build();
+
+	let mut account_key = vec![3u8];
+	account_key.extend_from_slice(&[0x11; 20]);
+	account_key.push(0);
+
+	// Same encoding as the `(uint8 parents, bytes[] interior)` struct.
+	let expected_output = EvmDataWriter::new()
+		.write((1u8, vec![Bytes(vec![0, 0, 0, 0, 2]), Bytes(account_key)]))
+		.build();
+
+	assert_eq!(writer_output, expected_output);
+}
+
+#[test]
+fn read_multilocation() {
+	use ::xcm::v0::{BodyId, BodyPart};
+	use ::xcm::v1::{Junction, Junctions, MultiLocation, NetworkId};
+
+	let locations = vec![
+		MultiLocation::here(),
+		MultiLocation::parent(),
+		MultiLocation {
+			parents: 1,
+			interior: Junctions::X1(Junction::AccountId32 {
+				network: NetworkId::Named(b"foo".to_vec()),
+				id: [0x22; 32],
+			}),
+		},
+		MultiLocation {
+			parents: 0,
+			interior: Junctions::X8(
+				Junction::Parachain(1000),
+				Junction::AccountIndex64 {
+					network: NetworkId::Kusama,
+					index: 42,
+				},
+				Junction::PalletInstance(3),
+				Junction::GeneralIndex(u128::MAX),
+				Junction::GeneralKey(b"key".to_vec()),
+				Junction::OnlyChild,
+				Junction::Plurality {
+					id: BodyId::Executive,
+					part: BodyPart::Voice,
+				},
+				Junction::AccountKey20 {
+					network: NetworkId::Polkadot,
+					key: [0x33; 20],
+				},
+			),
+		},
+	];
+
+	for location in locations {
+		let writer_output = EvmDataWriter::new().write(location.clone()).build();
+
+		let mut reader = EvmDataReader::new(&writer_output);
+		assert_eq!(reader.read::<::xcm::v1::MultiLocation>(), Ok(location));
+	}
+}
+
+#[test]
+fn read_invalid_multilocation() {
+	let invalid_interiors = vec![
+		(vec![], "junction is empty"),
+		(vec![0, 0, 0, 2], "junction data has wrong length"),
+		(vec![3, 0x11], "junction data is too short"),
+		(vec![4, 1, 0], "junction data has wrong length"),
+		(vec![3; 23], "network id is not supported"),
+		(vec![0x42], "junction is not supported"),
+	];
+
+	for (junction, expected_error) in invalid_interiors {
+		let input = EvmDataWriter::new()
+			.write((1u8, vec![Bytes(junction)]))
+			.build();
+
+		let mut reader = EvmDataReader::new(&input);
+		assert_eq!(
+			reader.read::<::xcm::v1::MultiLocation>(),
+			Err(error(expected_error))
+		);
+	}
+}
+
+#[test]
+fn read_offset_out_of_bounds() {
+	let mut writer_output = EvmDataWriter::new().write(vec![U256::from(1)]).build();
+
+	U256::from(0x40).to_big_endian(&mut writer_output[0x00..0x20]);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	match reader.read::<Vec<U256>>() {
+		Ok(_) => panic!("should not parse correctly"),
+		Err(ExitError::Other(err)) => assert_eq!(err, "offset points out of bounds"),
+		Err(_) => panic!("unexpected error"),
+	}
+
+	U256::max_value().to_big_endian(&mut writer_output[0x00..0x20]);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	match reader.read::<Vec<U256>>() {
+		Ok(_) => panic!("should not parse correctly"),
+		Err(ExitError::Other(err)) => assert_eq!(err, "offset is too large"),
+		Err(_) => panic!("unexpected error"),
+	}
+}
+
+#[test]
+fn read_huge_length() {
+	let mut writer_output = EvmDataWriter::new().write(vec![Bytes::from("foo")]).build();
+
+	U256::from(u64::MAX).to_big_endian(&mut writer_output[0x20..0x40]);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	match reader.read::<Vec<Bytes>>() {
+		Ok(_) => panic!("should not parse correctly"),
+		Err(ExitError::Other(err)) => assert_eq!(err, "length is too large"),
+		Err(_) => panic!("unexpected error"),
+	}
+}
+
+/// Small deterministic generator of pseudo-random values for the fuzz tests.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		// xorshift64*
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+	}
+
+	fn below(&mut self, max: u64) -> usize {
+		(self.next() % max) as usize
+	}
+
+	fn bytes(&mut self, max_len: u64) -> Vec<u8> {
+		let len = self.below(max_len + 1);
+		(0..len).map(|_| self.next() as u8).collect()
+	}
+
+	fn u256(&mut self) -> U256 {
+		U256([self.next(), self.next(), self.next(), self.next()])
+	}
+
+	fn address(&mut self) -> Address {
+		Address(H160::from_low_u64_be(self.next()))
+	}
+}
+
+type FuzzedStatic = (u8, H256, [Address; 2], u128);
+type FuzzedDynamic = (
+	Vec<(Address, Bytes)>,
+	[U256; 2],
+	String,
+	Vec<Vec<u32>>,
+	bool,
+);
+
+fn eth_uint(value: U256) -> ethabi::Token {
+	let mut buffer = [0u8; 32];
+	value.to_big_endian(&mut buffer);
+	ethabi::Token::Uint(ethabi::Uint::from_big_endian(&buffer))
+}
+
+fn eth_address(value: Address) -> ethabi::Token {
+	ethabi::Token::Address(ethabi::Address::from_slice(value.0.as_bytes()))
+}
+
+fn static_token(value: &FuzzedStatic) -> ethabi::Token {
+	ethabi::Token::Tuple(vec![
+		eth_uint(value.0.into()),
+		ethabi::Token::FixedBytes(value.1.as_bytes().to_vec()),
+		ethabi::Token::FixedArray(value.2.iter().copied().map(eth_address).collect()),
+		eth_uint(value.3.into()),
+	])
+}
+
+fn dynamic_token(value: &FuzzedDynamic) -> ethabi::Token {
+	ethabi::Token::Tuple(vec![
+		ethabi::Token::Array(
+			value
+				.0
+				.iter()
+				.map(|(address, bytes)| {
+					ethabi::Token::Tuple(vec![
+						eth_address(*address),
+						ethabi::Token::Bytes(bytes.0.clone()),
+					])
+				})
+				.collect(),
+		),
+		ethabi::Token::FixedArray(value.1.iter().copied().map(eth_uint).collect()),
+		ethabi::Token::String(value.2.clone()),
+		ethabi::Token::Array(
+			value
+				.3
+				.iter()
+				.map(|inner| {
+					ethabi::Token::Array(inner.iter().map(|x| eth_uint((*x).into())).collect())
+				})
+				.collect(),
+		),
+		ethabi::Token::Bool(value.4),
+	])
+}
+
+fn random_static(rng: &mut Rng) -> FuzzedStatic {
+	(
+		rng.next() as u8,
+		H256::from_low_u64_be(rng.next()),
+		[rng.address(), rng.address()],
+		((rng.next() as u128) << 64) | rng.next() as u128,
+	)
+}
+
+fn random_dynamic(rng: &mut Rng) -> FuzzedDynamic {
+	(
+		(0..rng.below(4))
+			.map(|_| (rng.address(), Bytes(rng.bytes(70))))
+			.collect(),
+		[rng.u256(), rng.u256()],
+		rng.bytes(70)
+			.into_iter()
+			.map(|b| char::from(b'a' + b % 26))
+			.collect(),
+		(0..rng.below(4))
+			.map(|_| (0..rng.below(4)).map(|_| rng.next() as u32).collect())
+			.collect(),
+		rng.next() % 2 == 0,
+	)
+}
+
+#[test]
+fn fuzz_static_types_against_reference_encoder() {
+	let mut rng = Rng(0x5eed);
+
+	for _ in 0..1_000 {
+		let value = random_static(&mut rng);
+
+		let writer_output = EvmDataWriter::new().write(value).build();
+		assert_eq!(writer_output, ethabi::encode(&[static_token(&value)]));
+
+		let mut reader = EvmDataReader::new(&writer_output);
+		assert_eq!(reader.read::<FuzzedStatic>(), Ok(value));
+	}
+}
+
+#[test]
+fn fuzz_dynamic_types_against_reference_encoder() {
+	let mut rng = Rng(0xdead_beef);
+
+	for _ in 0..1_000 {
+		let value = random_dynamic(&mut rng);
+		let expected = ethabi::encode(&[dynamic_token(&value)]);
+
+		let writer_output = EvmDataWriter::new().write(value.clone()).build();
+		assert_eq!(writer_output, expected);
+
+		let mut reader = EvmDataReader::new(&expected);
+		assert_eq!(reader.read::<FuzzedDynamic>(), Ok(value));
+	}
+}
+
+#[test]
+fn fuzz_corrupted_input_does_not_panic() {
+	let mut rng = Rng(0xc0ffee);
+
+	for _ in 0..1_000 {
+		let value = random_dynamic(&mut rng);
+		let mut data = EvmDataWriter::new().write(value).build();
+
+		// Corrupt a few bytes, preferably in offsets and lengths which are 32-byte aligned
+		// big-endian numbers.
+		for _ in 0..=rng.below(3) {
+			let word = rng.below((data.len() / 32) as u64);
+			let position = word * 32 + 24 + rng.below(8);
+			data[position] = rng.next() as u8;
+		}
+
+		// Truncate the input from time to time.
+		if rng.next() % 4 == 0 {
+			let len = rng.below(data.len() as u64);
+			data.truncate(len);
+		}
+
+		// Only the absence of panic matters.
+		let _ = EvmDataReader::new(&data).read::<FuzzedDynamic>();
+	}
+}
+
+#[test]
+fn error_selector() {
+	assert_eq!(
+		&ERROR_SELECTOR.to_be_bytes()[..],
+		&keccak256!("Error(string)")[0..4]
+	);
+}
+
+#[test]
+fn write_revert_reason() {
+	let reason = "balance too low";
+
+	let mut expected = keccak256!("Error(string)")[0..4].to_vec();
+	expected.append(&mut ethabi::encode(&[ethabi::Token::String(reason.into())]));
+
+	assert_eq!(EvmDataWriter::revert_reason(reason), expected);
+
+	// It can be decoded after the selector.
+	let data = EvmDataWriter::revert_reason(reason);
+	let mut reader = EvmDataReader::new(&data[4..]);
+	assert_eq!(reader.read::<String>(), Ok(reason.into()));
+}
+
+#[test]
+fn write_custom_error() {
+	#[precompile_utils_macro::generate_function_selector]
+	#[derive(Debug, PartialEq, num_enum::TryFromPrimitive)]
+	enum Error {
+		InsufficientBalance = "InsufficientBalance(uint256,uint256)",
+	}
+
+	let data = EvmDataWriter::new_with_selector(Error::InsufficientBalance as u32)
+		.write(U256::from(1))
+		.write(U256::from(2))
+		.build();
+
+	let mut expected = keccak256!("InsufficientBalance(uint256,uint256)")[0..4].to_vec();
+	expected.append(&mut ethabi::encode(&[
+		eth_uint(1.into()),
+		eth_uint(2.into()),
+	]));
+
+	assert_eq!(data, expected);
+}
+
+#[test]
+fn declared_reads_counts_reads() {
+	let mut gasometer = Gasometer::new(None);
+	let mut reads = DeclaredReads::new(&mut gasometer, 100);
+
+	assert_eq!(reads.read(|| 42u32), Ok(42));
+	assert_eq!(reads.count(), 1);
+
+	assert_eq!(reads.read_many(3, || true), Ok(true));
+	assert_eq!(reads.count(), 4);
+
+	assert_eq!(reads.read_many(0, || ()), Ok(()));
+	assert_eq!(reads.count(), 4);
+
+	assert_eq!(gasometer.used_gas(), 400);
+}
+
+/// Read a value, only if it is registered in an index read first.
+fn read_indexed_value(reads: &mut DeclaredReads, registered: bool) -> EvmResult<Option<u32>> {
+	if !reads.read(|| registered)? {
+		return Ok(None);
+	}
+
+	reads.read(|| 42).map(Some)
+}
+
+#[test]
+fn declared_reads_charge_each_path_for_its_reads() {
+	let mut gasometer = Gasometer::new(None);
+	let value = read_indexed_value(&mut DeclaredReads::new(&mut gasometer, 100), false);
+	assert_eq!(value, Ok(None));
+	assert_eq!(gasometer.used_gas(), 100);
+
+	let mut gasometer = Gasometer::new(None);
+	let value = read_indexed_value(&mut DeclaredReads::new(&mut gasometer, 100), true);
+	assert_eq!(value, Ok(Some(42)));
+	assert_eq!(gasometer.used_gas(), 200);
+}
+
+#[test]
+fn declared_reads_charge_before_reading() {
+	let mut gasometer = Gasometer::new(Some(150));
+	let mut reads = DeclaredReads::new(&mut gasometer, 100);
+
+	assert_eq!(reads.read(|| true), Ok(true));
+
+	let mut second_read = false;
+	assert_eq!(reads.read(|| second_read = true), Err(ExitError::OutOfGas));
+	assert!(!second_read);
+	assert_eq!(reads.count(), 1);
+}
+
+#[test]
+fn dispatch_error_reasons() {
+	assert_eq!(
+		dispatch_error_reason(DispatchError::Module {
+			index: 3,
+			error: 2,
+			message: Some("InsufficientBalance"),
+		}),
+		"InsufficientBalance"
+	);
+	assert_eq!(
+		dispatch_error_reason(DispatchError::Module {
+			index: 3,
+			error: 2,
+			message: None,
+		}),
+		"Module(index: 3, error: 2)"
+	);
+	assert_eq!(
+		dispatch_error_reason(DispatchError::Other("custom failure")),
+		"custom failure"
+	);
+	assert_eq!(dispatch_error_reason(DispatchError::BadOrigin), "BadOrigin");
+}
+
+struct ExamplePrecompile;
+
+/// Example precompile
+#[precompile(interface = "Example")]
+impl ExamplePrecompile {
+	/// Adds two numbers
+	#[precompile::public("add(uint64,uint64)")]
+	#[precompile::view]
+	fn add(handle: &mut PrecompileHandle, a: u64, b: u64) -> EvmResult<U256> {
+		handle.gasometer.record_cost(10)?;
+
+		Ok(U256::from(a) + U256::from(b))
+	}
+
+	/// Returns the caller and the value
+	#[precompile::public("whoami()")]
+	#[precompile::payable]
+	fn whoami(handle: &mut PrecompileHandle) -> EvmResult<(Address, U256)> {
+		Ok((
+			Address(handle.context.caller),
+			handle.context.apparent_value,
+		))
+	}
+
+	#[precompile::public("ping(bytes32)")]
+	fn ping(handle: &mut PrecompileHandle, topic: H256) -> EvmResult {
+		let logs = LogsBuilder::new(handle.context.address)
+			.log1(topic, vec![])
+			.build();
+		handle.record_logs(logs)
+	}
+}
+
+fn example_context(value: u64) -> Context {
+	Context {
+		address: H160::repeat_byte(0xaa),
+		caller: H160::repeat_byte(0xbb),
+		apparent_value: value.into(),
+	}
+}
+
+#[test]
+fn precompile_macro_generates_selectors() {
+	assert_eq!(
+		&(Action::Add as u32).to_be_bytes()[..],
+		&keccak256!("add(uint64,uint64)")[0..4],
+	);
+	assert_eq!(
+		&(Action::Whoami as u32).to_be_bytes()[..],
+		&keccak256!("whoami()")[0..4],
+	);
+	assert_eq!(
+		&(Action::Ping as u32).to_be_bytes()[..],
+		&keccak256!("ping(bytes32)")[0..4],
+	);
+}
+
+#[test]
+fn precompile_macro_decodes_input_and_encodes_output() {
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Add as u32)
+		.write(40u64)
+		.write(2u64)
+		.build();
+
+	let output =
+		ExamplePrecompile::execute(&input, None, &example_context(0)).expect("call to succeed");
+
+	assert_eq!(output.exit_status, ExitSucceed::Returned);
+	assert_eq!(output.cost, 10);
+	assert_eq!(
+		output.output,
+		EvmDataWriter::new().write(U256::from(42)).build()
+	);
+	assert!(output.logs.is_empty());
+}
+
+#[test]
+fn precompile_macro_encodes_multiple_outputs() {
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Whoami as u32)
+		.build();
+
+	let output =
+		ExamplePrecompile::execute(&input, None, &example_context(5)).expect("call to succeed");
+
+	assert_eq!(output.exit_status, ExitSucceed::Returned);
+	assert_eq!(
+		output.output,
+		EvmDataWriter::new()
+			.write(Address(H160::repeat_byte(0xbb)))
+			.write(U256::from(5))
+			.build()
+	);
+}
+
+#[test]
+fn precompile_macro_returns_logs() {
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Ping as u32)
+		.write(H256::repeat_byte(0x11))
+		.build();
+
+	let output =
+		ExamplePrecompile::execute(&input, None, &example_context(0)).expect("call to succeed");
+
+	assert_eq!(output.exit_status, ExitSucceed::Stopped);
+	assert!(output.output.is_empty());
+	assert_eq!(
+		output.logs,
+		LogsBuilder::new(H160::repeat_byte(0xaa))
+			.log1(H256::repeat_byte(0x11), vec![])
+			.build()
+	);
+	assert_eq!(output.cost, 375 + 375);
+}
+
+#[test]
+fn precompile_macro_rejects_value_for_non_payable_functions() {
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Add as u32)
+		.write(40u64)
+		.write(2u64)
+		.build();
+
+	assert_eq!(
+		ExamplePrecompile::execute(&input, None, &example_context(1)).map(|_| ()),
+		Err(error("function is not payable"))
+	);
+}
+
+#[test]
+fn precompile_macro_rejects_static_calls_to_non_view_functions() {
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Ping as u32)
+		.write(H256::repeat_byte(0x11))
+		.build();
+
+	assert_eq!(
+		ExamplePrecompile::execute_with_static(&input, None, &example_context(0), true).map(|_| ()),
+		Err(error("can't call non-static function in static context"))
+	);
+
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Add as u32)
+		.write(40u64)
+		.write(2u64)
+		.build();
+
+	assert!(
+		ExamplePrecompile::execute_with_static(&input, None, &example_context(0), true).is_ok()
+	);
+}
+
+#[test]
+fn check_function_modifiers() {
+	use FunctionModifier::*;
+
+	let not_payable = Err(error("function is not payable"));
+	let not_static = Err(error("can't call non-static function in static context"));
+
+	for (value, is_static, modifier, expected) in vec![
+		(0, false, View, Ok(())),
+		(0, false, NonPayable, Ok(())),
+		(0, false, Payable, Ok(())),
+		(1, false, View, not_payable.clone()),
+		(1, false, NonPayable, not_payable.clone()),
+		(1, false, Payable, Ok(())),
+		(0, true, View, Ok(())),
+		(0, true, NonPayable, not_static.clone()),
+		(0, true, Payable, not_static.clone()),
+	] {
+		assert_eq!(
+			check_function_modifier(&example_context(value), is_static, modifier),
+			expected,
+			"value: {}, is_static: {}, modifier: {:?}",
+			value,
+			is_static,
+			modifier
+		);
+	}
+}
+
+#[test]
+fn check_not_delegated_works() {
+	let context = example_context(0);
+
+	assert_eq!(
+		check_not_delegated(H160::repeat_byte(0xaa), &context),
+		Ok(())
+	);
+	assert_eq!(
+		check_not_delegated(H160::repeat_byte(0xdd), &context),
+		Err(error("cannot be called with DELEGATECALL or CALLCODE"))
+	);
+}
+
+#[test]
+fn generated_function_modifiers() {
+	#[precompile_utils_macro::generate_function_selector]
+	#[derive(Debug, PartialEq)]
+	enum FakeAction {
+		#[view]
+		Get = "get()",
+		Set = "set(uint256)",
+		#[payable]
+		Deposit = "deposit()",
+	}
+
+	assert_eq!(FakeAction::Get.modifier(), FunctionModifier::View);
+	assert_eq!(FakeAction::Set.modifier(), FunctionModifier::NonPayable);
+	assert_eq!(FakeAction::Deposit.modifier(), FunctionModifier::Payable);
+}
+
+#[test]
+fn precompile_macro_checks_arguments() {
+	let input = EvmDataWriter::new()
+		.write_selector(Action::Add as u32)
+		.write(40u64)
+		.build();
+
+	assert_eq!(
+		ExamplePrecompile::execute(&input, None, &example_context(0)).map(|_| ()),
+		Err(error("input doesn't match expected length"))
+	);
+
+	assert_eq!(
+		ExamplePrecompile::execute(&[0x01, 0x02, 0x03, 0x04], None, &example_context(0))
+			.map(|_| ()),
+		Err(error("unknown selector"))
+	);
+}
+
+#[test]
+fn precompile_macro_generates_solidity_interface() {
+	let expected = format!(
+		"// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// Example precompile
+/// This file is generated from the Rust implementation of the precompile.
+interface Example {{
+    /// Adds two numbers
+    /// Selector: {:08x}
+    function add(uint64 a, uint64 b) external view returns (uint256);
+
+    /// Returns the caller and the value
+    /// Selector: {:08x}
+    function whoami() external payable returns (address, uint256);
+
+    /// Selector: {:08x}
+    function ping(bytes32 topic) external;
+}}
+",
+		Action::Add as u32,
+		Action::Whoami as u32,
+		Action::Ping as u32,
+	);
+
+	assert_eq!(Action::SOLIDITY_INTERFACE, expected);
+}
@@ -0,0 +1,237 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Encoding of XCM types as Solidity types.
+//!
+//! A `MultiLocation` is encoded as the `(uint8 parents, bytes[] interior)` struct, each junction
+//! of the interior being a `bytes` whose first byte is the kind of the junction:
+//!
+//! | Selector | Junction         | Data                                        |
+//! |----------|------------------|---------------------------------------------|
+//! | 0x00     | `Parachain`      | `u32` (big endian)                          |
+//! | 0x01     | `AccountId32`    | 32 bytes id, then the network               |
+//! | 0x02     | `AccountIndex64` | `u64` (big endian), then the network        |
+//! | 0x03     | `AccountKey20`   | 20 bytes key, then the network              |
+//! | 0x04     | `PalletInstance` | `u8`                                        |
+//! | 0x05     | `GeneralIndex`   | `u128` (big endian)                         |
+//! | 0x06     | `GeneralKey`     | the key                                     |
+//! | 0x07     | `OnlyChild`      | nothing                                     |
+//! | 0x08     | `Plurality`      | SCALE encoded `(BodyId, BodyPart)`          |
+//!
+//! The network is a byte `0x00` for `Any`, `0x01` followed by the name for `Named`, `0x02` for
+//! `Polkadot` and `0x03` for `Kusama`.
+
+use crate::{error, Bytes, EvmData, EvmDataReader, EvmDataWriter, EvmResult};
+use codec::{Decode, Encode};
+use sp_std::{convert::TryInto, vec, vec::Vec};
+use xcm::{
+	v0::{BodyId, BodyPart},
+	v1::{Junction, Junctions, MultiLocation, NetworkId},
+};
+
+fn network_id_to_bytes(network: NetworkId) -> Vec<u8> {
+	match network {
+		NetworkId::Any => vec![0],
+		NetworkId::Named(mut name) => {
+			let mut encoded = vec![1];
+			encoded.append(&mut name);
+			encoded
+		}
+		NetworkId::Polkadot => vec![2],
+		NetworkId::Kusama => vec![3],
+	}
+}
+
+fn network_id_from_bytes(encoded: &[u8]) -> EvmResult<NetworkId> {
+	match encoded.split_first() {
+		Some((0, [])) => Ok(NetworkId::Any),
+		Some((1, name)) => Ok(NetworkId::Named(name.to_vec())),
+		Some((2, [])) => Ok(NetworkId::Polkadot),
+		Some((3, [])) => Ok(NetworkId::Kusama),
+		_ => Err(error("network id is not supported")),
+	}
+}
+
+/// Split the `N` first bytes of the data of a junction from the rest.
+fn split_junction_data<const N: usize>(data: &[u8]) -> EvmResult<([u8; N], &[u8])> {
+	if data.len() < N {
+		return Err(error("junction data is too short"));
+	}
+
+	let (head, tail) = data.split_at(N);
+	let head = head
+		.try_into()
+		.map_err(|_| error("junction data is too short"))?;
+
+	Ok((head, tail))
+}
+
+/// Data of a junction which must be exactly `N` bytes long.
+fn exact_junction_data<const N: usize>(data: &[u8]) -> EvmResult<[u8; N]> {
+	data.try_into()
+		.map_err(|_| error("junction data has wrong length"))
+}
+
+fn junction_to_bytes(junction: Junction) -> Vec<u8> {
+	let mut encoded = Vec::new();
+
+	match junction {
+		Junction::Parachain(id) => {
+			encoded.push(0);
+			encoded.extend_from_slice(&id.to_be_bytes());
+		}
+		Junction::AccountId32 { network, id } => {
+			encoded.push(1);
+			encoded.extend_from_slice(&id);
+			encoded.append(&mut network_id_to_bytes(network));
+		}
+		Junction::AccountIndex64 { network, index } => {
+			encoded.push(2);
+			encoded.extend_from_slice(&index.to_be_bytes());
+			encoded.append(&mut network_id_to_bytes(network));
+		}
+		Junction::AccountKey20 { network, key } => {
+			encoded.push(3);
+			encoded.extend_from_slice(&key);
+			encoded.append(&mut network_id_to_bytes(network));
+		}
+		Junction::PalletInstance(instance) => {
+			encoded.push(4);
+			encoded.push(instance);
+		}
+		Junction::GeneralIndex(index) => {
+			encoded.push(5);
+			encoded.extend_from_slice(&index.to_be_bytes());
+		}
+		Junction::GeneralKey(mut key) => {
+			encoded.push(6);
+			encoded.append(&mut key);
+		}
+		Junction::OnlyChild => {
+			encoded.push(7);
+		}
+		Junction::Plurality { id, part } => {
+			encoded.push(8);
+			encoded.append(&mut (id, part).encode());
+		}
+	}
+
+	encoded
+}
+
+fn junction_from_bytes(encoded: &[u8]) -> EvmResult<Junction> {
+	let (selector, data) = encoded
+		.split_first()
+		.ok_or_else(|| error("junction is empty"))?;
+
+	Ok(match selector {
+		0 => Junction::Parachain(u32::from_be_bytes(exact_junction_data(data)?)),
+		1 => {
+			let (id, network) = split_junction_data(data)?;
+			Junction::AccountId32 {
+				network: network_id_from_bytes(network)?,
+				id,
+			}
+		}
+		2 => {
+			let (index, network) = split_junction_data(data)?;
+			Junction::AccountIndex64 {
+				network: network_id_from_bytes(network)?,
+				index: u64::from_be_bytes(index),
+			}
+		}
+		3 => {
+			let (key, network) = split_junction_data(data)?;
+			Junction::AccountKey20 {
+				network: network_id_from_bytes(network)?,
+				key,
+			}
+		}
+		4 => Junction::PalletInstance(u8::from_be_bytes(exact_junction_data(data)?)),
+		5 => Junction::GeneralIndex(u128::from_be_bytes(exact_junction_data(data)?)),
+		6 => Junction::GeneralKey(data.to_vec()),
+		7 if data.is_empty() => Junction::OnlyChild,
+		8 => {
+			let mut data = data;
+			let (id, part) = <(BodyId, BodyPart)>::decode(&mut data)
+				.map_err(|_| error("failed to decode plurality"))?;
+
+			if !data.is_empty() {
+				return Err(error("junction data has wrong length"));
+			}
+
+			Junction::Plurality { id, part }
+		}
+		_ => return Err(error("junction is not supported")),
+	})
+}
+
+impl EvmData for Junction {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let encoded: Bytes = reader.read()?;
+
+		junction_from_bytes(&encoded.0)
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		Bytes::write(writer, Bytes(junction_to_bytes(value)));
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+}
+
+impl EvmData for Junctions {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let items: Vec<Junction> = reader.read()?;
+
+		let mut junctions = Junctions::Here;
+		for item in items {
+			junctions
+				.push(item)
+				.map_err(|_| error("too many junctions"))?;
+		}
+
+		Ok(junctions)
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let items: Vec<Junction> = value.iter().cloned().collect();
+
+		Vec::<Junction>::write(writer, items);
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+}
+
+impl EvmData for MultiLocation {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let (parents, interior) = reader.read()?;
+
+		Ok(MultiLocation { parents, interior })
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		<(u8, Junctions)>::write(writer, (value.parents, value.interior));
+	}
+
+	fn has_static_size() -> bool {
+		<(u8, Junctions)>::has_static_size()
+	}
+}
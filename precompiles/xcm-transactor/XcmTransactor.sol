@@ -0,0 +1,15 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// This file is generated from the Rust implementation of the precompile.
+interface XcmTransactor {
+    /// The account to which the derivative index is registered.
+    /// Selector: 3fdc4f36
+    function indexToAccount(uint16 index) external view returns (address);
+
+    /// Dispatch the encoded relay chain call `inner_call` from the derivative account `index`,
+    /// paying `fee_amount` of the relay chain token to buy `weight` on the relay chain.
+    /// Selector: cda0615a
+    function transactThroughDerivative(uint16 index, uint256 fee_amount, uint64 weight, bytes memory inner_call) external;
+}
@@ -0,0 +1,102 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to dispatch relay chain calls from derivative accounts through xcm-transactor.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::GetCallMetadata,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{
+	error, precompile, Address, Bytes, EvmResult, PrecompileHandle, RuntimeHelper,
+};
+use sp_core::{H160, U256};
+use sp_std::{
+	convert::{TryFrom, TryInto},
+	marker::PhantomData,
+};
+
+#[cfg(test)]
+mod tests;
+
+pub type TransactorBalanceOf<Runtime> = <Runtime as pallet_xcm_transactor::Config>::Balance;
+
+/// A precompile to wrap the functionality from xcm-transactor.
+///
+/// Relay chain calls can be encoded with the relay encoder precompile, and are dispatched from
+/// the derivative account `index` of the sovereign account of this parachain, which must be
+/// registered to the caller.
+pub struct XcmTransactorWrapper<Runtime>(PhantomData<Runtime>);
+
+#[precompile(interface = "XcmTransactor")]
+impl<Runtime> XcmTransactorWrapper<Runtime>
+where
+	Runtime: pallet_xcm_transactor::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::AccountId: Into<H160>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<pallet_xcm_transactor::Call<Runtime>>,
+	TransactorBalanceOf<Runtime>: TryFrom<U256>,
+{
+	/// The account to which the derivative index is registered.
+	#[precompile::public("indexToAccount(uint16)")]
+	#[precompile::view]
+	fn index_to_account(handle: &mut PrecompileHandle, index: u16) -> EvmResult<Address> {
+		let account =
+			RuntimeHelper::<Runtime>::with_declared_reads(&mut handle.gasometer, |reads| {
+				reads.read(|| pallet_xcm_transactor::Pallet::<Runtime>::index_to_account(index))
+			})?;
+
+		let account = account.ok_or_else(|| error("no account registered for this index"))?;
+
+		Ok(Address(account.into()))
+	}
+
+	/// Dispatch the encoded relay chain call `inner_call` from the derivative account `index`,
+	/// paying `fee_amount` of the relay chain token to buy `weight` on the relay chain.
+	#[precompile::public("transactThroughDerivative(uint16,uint256,uint64,bytes)")]
+	fn transact_through_derivative(
+		handle: &mut PrecompileHandle,
+		index: u16,
+		fee_amount: U256,
+		weight: u64,
+		inner_call: Bytes,
+	) -> EvmResult {
+		let fee_amount: TransactorBalanceOf<Runtime> = fee_amount
+			.try_into()
+			.map_err(|_| error("amount is too large for provided balance type"))?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = pallet_xcm_transactor::Call::<Runtime>::transact_through_derivative(
+			index,
+			fee_amount,
+			weight,
+			inner_call.0,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/// @author The Moonbeam Team
+/// @title The interface through which solidity contracts will interact with xtokens
+/// We follow this same interface including four-byte function selectors, in the precompile that
+/// wraps the pallet
+interface Xtokens {
+    /// @dev A multilocation is defined by its number of parents and the encoded junctions
+    /// (interior). Each junction is encoded as bytes whose first byte is the kind of junction:
+    /// 0x00 Parachain:       uint32 (4 bytes)
+    /// 0x01 AccountId32:     bytes32 followed by the network
+    /// 0x02 AccountIndex64:  uint64 (8 bytes) followed by the network
+    /// 0x03 AccountKey20:    bytes20 followed by the network
+    /// 0x04 PalletInstance:  uint8 (1 byte)
+    /// 0x05 GeneralIndex:    uint128 (16 bytes)
+    /// 0x06 GeneralKey:      the key
+    /// 0x07 OnlyChild:       nothing
+    /// 0x08 Plurality:       SCALE encoded (BodyId, BodyPart)
+    /// The network is 0x00 for Any, 0x01 followed by the name for Named, 0x02 for Polkadot
+    /// and 0x03 for Kusama.
+    /// Example: the relay chain account 0x1234...cdef is (1, [0x011234...cdef00])
+    struct Multilocation {
+        uint8 parents;
+        bytes[] interior;
+    }
+
+    /// @dev Transfer a token through XCM based on its address
+    /// Selector: b9f813ff
+    /// @param currency_address The ERC20 address of the currency we want to transfer
+    /// @param amount The amount of tokens we want to transfer
+    /// @param destination The Multilocation to which we want to send the tokens
+    /// @param weight The weight we want to buy in the destination chain
+    function transfer(
+        address currency_address,
+        uint256 amount,
+        Multilocation memory destination,
+        uint64 weight
+    ) external;
+
+    /// @dev Transfer a token through XCM based on its MultiLocation
+    /// Selector: b4f76f96
+    /// @param asset The asset we want to transfer, defined by its multilocation.
+    /// Currently only Concrete Fungible assets
+    /// @param amount The amount of tokens we want to transfer
+    /// @param destination The Multilocation to which we want to send the tokens
+    /// @param weight The weight we want to buy in the destination chain
+    function transferMultiasset(
+        Multilocation memory asset,
+        uint256 amount,
+        Multilocation memory destination,
+        uint64 weight
+    ) external;
+}
@@ -0,0 +1,127 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to transfer assets to other chains through orml xtokens.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::GetCallMetadata,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{error, precompile, Address, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_core::U256;
+use sp_std::{
+	boxed::Box,
+	convert::{TryFrom, TryInto},
+	marker::PhantomData,
+};
+use xcm::v1::{AssetId, Fungibility, MultiAsset, MultiLocation};
+use xcm_primitives::AccountIdToCurrencyId;
+
+pub type XBalanceOf<Runtime> = <Runtime as orml_xtokens::Config>::Balance;
+
+pub type CurrencyIdOf<Runtime> = <Runtime as orml_xtokens::Config>::CurrencyId;
+
+/// A precompile to wrap the functionality from xtokens.
+///
+/// Assets are either given by the address of their ERC-20 precompile, which the runtime maps to a
+/// `CurrencyId` through `AccountIdToCurrencyId`, or by their `MultiLocation`.
+/// `MultiLocation`s are encoded as the `(uint8 parents, bytes[] interior)` struct, see
+/// `Xtokens.sol` for the encoding of the junctions.
+pub struct XtokensWrapper<Runtime>(PhantomData<Runtime>);
+
+#[precompile(interface = "Xtokens")]
+impl<Runtime> XtokensWrapper<Runtime>
+where
+	Runtime: orml_xtokens::Config + pallet_evm::Config + frame_system::Config,
+	Runtime: AccountIdToCurrencyId<Runtime::AccountId, CurrencyIdOf<Runtime>>,
+	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime::Call: From<orml_xtokens::Call<Runtime>>,
+	XBalanceOf<Runtime>: TryFrom<U256>,
+{
+	/// Transfer `amount` of the asset whose ERC-20 precompile is at `currency_address` to
+	/// `destination`, buying `weight` of execution on the destination chain.
+	#[precompile::public("transfer(address,uint256,(uint8,bytes[]),uint64)")]
+	fn transfer(
+		handle: &mut PrecompileHandle,
+		currency_address: Address,
+		amount: U256,
+		destination: MultiLocation,
+		weight: u64,
+	) -> EvmResult {
+		let currency_account = Runtime::AddressMapping::into_account_id(currency_address.0);
+		let currency_id = Runtime::account_to_currency_id(currency_account)
+			.ok_or_else(|| error("cannot convert into currency id"))?;
+
+		let amount: XBalanceOf<Runtime> = amount
+			.try_into()
+			.map_err(|_| error("amount is too large for provided balance type"))?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = orml_xtokens::Call::<Runtime>::transfer(
+			currency_id,
+			amount,
+			Box::new(destination),
+			weight,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+
+	/// Transfer `amount` of the fungible asset identified by its `asset` location to
+	/// `destination`, buying `weight` of execution on the destination chain.
+	#[precompile::public("transferMultiasset((uint8,bytes[]),uint256,(uint8,bytes[]),uint64)")]
+	fn transfer_multiasset(
+		handle: &mut PrecompileHandle,
+		asset: MultiLocation,
+		amount: U256,
+		destination: MultiLocation,
+		weight: u64,
+	) -> EvmResult {
+		let amount: u128 = amount
+			.try_into()
+			.map_err(|_| error("amount is too large"))?;
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context.caller);
+		let call = orml_xtokens::Call::<Runtime>::transfer_multiasset(
+			Box::new(MultiAsset {
+				id: AssetId::Concrete(asset),
+				fun: Fungibility::Fungible(amount),
+			}),
+			Box::new(destination),
+			weight,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.gasometer.remaining_gas()?,
+		)?;
+		handle.gasometer.record_cost(used_gas)?;
+
+		Ok(())
+	}
+}
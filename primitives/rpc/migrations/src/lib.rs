@@ -0,0 +1,46 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// These clippy lints are disabled because the macro-generated code triggers them.
+#![allow(clippy::unnecessary_mut_passed)]
+#![allow(clippy::too_many_arguments)]
+
+use codec::{Decode, Encode};
+use sp_std::vec::Vec;
+
+/// How far a migration of pallet-migrations got
+#[derive(Eq, PartialEq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum MigrationState {
+	NotStarted,
+	InProgress,
+	Completed,
+}
+
+#[derive(Eq, PartialEq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct MigrationInfo {
+	pub name: Vec<u8>,
+	pub state: MigrationState,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait MigrationsRuntimeApi {
+		/// Every migration of the MigrationsList, in order, with its state
+		fn migrations() -> Vec<MigrationInfo>;
+		/// Whether every migration has completed
+		fn is_fully_upgraded() -> bool;
+	}
+}
@@ -37,6 +37,8 @@ use sp_runtime::traits::Zero;
 use sp_std::borrow::Borrow;
 use sp_std::{convert::TryInto, marker::PhantomData};
 
+pub mod relay_calls;
+
 /// Converter struct implementing `AssetIdConversion` converting a numeric asset ID
 /// (must be `TryFrom/TryInto<u128>`) into a MultiLocation Value and Viceversa through
 /// an intermediate generic type AssetType.
@@ -325,3 +327,10 @@ pub trait UnitsToWeightRatio<AssetId> {
 	// Get units per second from asset type
 	fn get_units_per_second(asset_id: AssetId) -> Option<u128>;
 }
+
+// Defines the trait to obtain the CurrencyId of the asset behind an account
+// This is used to convert the address of an ERC-20 asset into the CurrencyId used by xtokens
+pub trait AccountIdToCurrencyId<Account, CurrencyId> {
+	// Get the CurrencyId of the asset represented by the account
+	fn account_to_currency_id(account: Account) -> Option<CurrencyId>;
+}
@@ -0,0 +1,144 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! SCALE encoding of relay chain calls, to be sent to the relay chain through XCM `Transact`.
+//!
+//! The relay chain runtime is not a dependency: calls are encoded from the indices of their
+//! pallet and of the call, which each runtime configures for its relay chain.
+
+use parity_scale_codec::{Compact, Decode, Encode};
+use sp_runtime::{AccountId32, MultiAddress};
+use sp_std::{vec, vec::Vec};
+
+/// Indices of the staking pallet of the relay chain and of its calls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakingCallIndices {
+	pub pallet: u8,
+	pub bond: u8,
+	pub bond_extra: u8,
+	pub unbond: u8,
+	pub withdraw_unbonded: u8,
+	pub nominate: u8,
+	pub chill: u8,
+	pub set_payee: u8,
+}
+
+/// Indices of the utility pallet of the relay chain and of its calls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UtilityCallIndices {
+	pub pallet: u8,
+	pub as_derivative: u8,
+}
+
+/// Where staking rewards are paid, encoded as the `RewardDestination` of the relay chain.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum RewardDestination {
+	/// Pay into the stash account, increasing the amount at stake accordingly.
+	Staked,
+	/// Pay into the stash account, not increasing the amount at stake.
+	Stash,
+	/// Pay into the controller account.
+	Controller,
+	/// Pay into a specified account.
+	Account(AccountId32),
+	/// Receive no reward.
+	None,
+}
+
+/// Staking calls of the relay chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StakingCall {
+	/// Bond `value` of the stash (the sender) to `controller`, rewards being paid to `payee`.
+	Bond {
+		controller: AccountId32,
+		value: u128,
+		payee: RewardDestination,
+	},
+	/// Bond an additional amount of the stash.
+	BondExtra(u128),
+	/// Schedule an amount to be unbonded.
+	Unbond(u128),
+	/// Withdraw the unbonded funds, given the number of slashing spans of the stash.
+	WithdrawUnbonded(u32),
+	/// Nominate the given validators.
+	Nominate(Vec<AccountId32>),
+	/// Stop nominating or validating.
+	Chill,
+	/// Change where the rewards are paid.
+	SetPayee(RewardDestination),
+}
+
+/// Accounts are given to relay chain calls through its `AccountIdLookup`.
+type RelayAddress = MultiAddress<AccountId32, ()>;
+
+impl StakingCall {
+	/// SCALE encoding of the call for a relay chain with the provided indices.
+	pub fn encode_call(self, indices: &StakingCallIndices) -> Vec<u8> {
+		let mut encoded = vec![indices.pallet];
+
+		match self {
+			StakingCall::Bond {
+				controller,
+				value,
+				payee,
+			} => {
+				encoded.push(indices.bond);
+				RelayAddress::Id(controller).encode_to(&mut encoded);
+				Compact(value).encode_to(&mut encoded);
+				payee.encode_to(&mut encoded);
+			}
+			StakingCall::BondExtra(value) => {
+				encoded.push(indices.bond_extra);
+				Compact(value).encode_to(&mut encoded);
+			}
+			StakingCall::Unbond(value) => {
+				encoded.push(indices.unbond);
+				Compact(value).encode_to(&mut encoded);
+			}
+			StakingCall::WithdrawUnbonded(num_slashing_spans) => {
+				encoded.push(indices.withdraw_unbonded);
+				num_slashing_spans.encode_to(&mut encoded);
+			}
+			StakingCall::Nominate(targets) => {
+				encoded.push(indices.nominate);
+				targets
+					.into_iter()
+					.map(RelayAddress::Id)
+					.collect::<Vec<_>>()
+					.encode_to(&mut encoded);
+			}
+			StakingCall::Chill => {
+				encoded.push(indices.chill);
+			}
+			StakingCall::SetPayee(payee) => {
+				encoded.push(indices.set_payee);
+				payee.encode_to(&mut encoded);
+			}
+		}
+
+		encoded
+	}
+}
+
+/// SCALE encoding of `utility.as_derivative(index, call)` for a relay chain with the provided
+/// indices, `call` being an already encoded relay chain call.
+pub fn encode_as_derivative(indices: &UtilityCallIndices, index: u16, call: Vec<u8>) -> Vec<u8> {
+	let mut encoded = vec![indices.pallet, indices.as_derivative];
+	index.encode_to(&mut encoded);
+	encoded.extend(call);
+
+	encoded
+}
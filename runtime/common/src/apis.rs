@@ -192,6 +192,29 @@ macro_rules! impl_runtime_apis_plus_common {
 				}
 			}
 
+			impl moonbeam_rpc_primitives_migrations::MigrationsRuntimeApi<Block> for Runtime {
+				fn migrations() -> Vec<moonbeam_rpc_primitives_migrations::MigrationInfo> {
+					use moonbeam_rpc_primitives_migrations::{MigrationInfo, MigrationState};
+					use pallet_migrations::MigrationStatus;
+
+					Migrations::migrations_status()
+						.into_iter()
+						.map(|(name, status)| MigrationInfo {
+							name,
+							state: match status {
+								MigrationStatus::NotStarted => MigrationState::NotStarted,
+								MigrationStatus::InProgress(_) => MigrationState::InProgress,
+								MigrationStatus::Completed => MigrationState::Completed,
+							},
+						})
+						.collect()
+				}
+
+				fn is_fully_upgraded() -> bool {
+					Migrations::is_fully_upgraded()
+				}
+			}
+
 			impl fp_rpc::EthereumRuntimeRPCApi<Block> for Runtime {
 				fn chain_id() -> u64 {
 					<Runtime as pallet_evm::Config>::ChainId::get()
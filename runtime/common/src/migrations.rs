@@ -31,8 +31,16 @@ impl Migration for MM_001_AuthorMappingAddDeposit {
 	fn friendly_name(&self) -> &str {
 		"AuthorMappingAddDeposit"
 	}
-	fn migrate(&self, _available_weight: Weight) -> Weight {
-		0u64.into()
+	fn migrate(&self, _available_weight: Weight, _cursor: Option<Vec<u8>>) -> MigrationStepResult {
+		MigrationStepResult::completed(0u64.into())
+	}
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade(&self) -> Result<(), &'static str> {
+		Ok(())
+	}
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(&self) -> Result<(), &'static str> {
+		Ok(())
 	}
 }
 
@@ -42,8 +50,8 @@ impl Migration for MM_002_StakingFixTotalBalance {
 	fn friendly_name(&self) -> &str {
 		"StakingFixTotalBalance"
 	}
-	fn migrate(&self, _available_weight: Weight) -> Weight {
-		0u64.into()
+	fn migrate(&self, _available_weight: Weight, _cursor: Option<Vec<u8>>) -> MigrationStepResult {
+		MigrationStepResult::completed(0u64.into())
 	}
 }
 
@@ -53,8 +61,11 @@ impl Migration for MM_003_StakingUnboundedCollatorNominations {
 	fn friendly_name(&self) -> &str {
 		"StakingUnboundedCollatorNominations"
 	}
-	fn migrate(&self, _available_weight: Weight) -> Weight {
-		0u64.into()
+	fn dependencies(&self) -> Vec<&str> {
+		vec!["StakingFixTotalBalance"]
+	}
+	fn migrate(&self, _available_weight: Weight, _cursor: Option<Vec<u8>>) -> MigrationStepResult {
+		MigrationStepResult::completed(0u64.into())
 	}
 }
 */
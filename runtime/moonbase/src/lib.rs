@@ -165,7 +165,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_version: 0701,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 };
 
 /// The version information used to identify this runtime when compiled natively.
@@ -314,7 +314,14 @@ impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 }
 
-impl pallet_ethereum_chain_id::Config for Runtime {}
+parameter_types! {
+	pub const MaxPreviousChainIds: u32 = 16;
+}
+
+impl pallet_ethereum_chain_id::Config for Runtime {
+	type Event = Event;
+	type MaxPreviousChainIds = MaxPreviousChainIds;
+}
 
 impl pallet_randomness_collective_flip::Config for Runtime {}
 
@@ -637,10 +644,12 @@ impl cumulus_pallet_parachain_system::Config for Runtime {
 	type Event = Event;
 	type OnValidationData = ();
 	type SelfParaId = ParachainInfo;
-	type DmpMessageHandler = DmpQueue;
+	// Inbound messages go through maintenance mode, which queues them while in maintenance and
+	// otherwise forwards them to DmpQueue and XcmpQueue.
+	type DmpMessageHandler = MaintenanceMode;
 	type ReservedDmpWeight = ReservedDmpWeight;
 	type OutboundXcmpMessageSource = XcmpQueue;
-	type XcmpMessageHandler = XcmpQueue;
+	type XcmpMessageHandler = MaintenanceMode;
 	type ReservedXcmpWeight = ReservedXcmpWeight;
 }
 
@@ -843,9 +852,17 @@ impl pallet_proxy::Config for Runtime {
 	type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
+parameter_types! {
+	// Leave room in the block for the mandatory inherents and the other hooks
+	pub const MigrationsBudget: Perbill = Perbill::from_percent(50);
+}
+
 impl pallet_migrations::Config for Runtime {
 	type Event = Event;
 	type MigrationsList = runtime_common::migrations::CommonMigrations;
+	type MaintenanceMode = MaintenanceMode;
+	type MigrationsOrigin = EnsureRoot<AccountId>;
+	type MigrationsBudget = MigrationsBudget;
 }
 
 parameter_types! {
@@ -1179,6 +1196,29 @@ where
 	}
 }
 
+// The prefix of the addresses of the ERC-20 precompiles of foreign assets, which are followed by
+// the AssetId
+pub const FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8; 4];
+
+// How to convert from the address of an ERC-20 precompile to a CurrencyId
+// The native currency is the balances ERC-20 precompile, while foreign assets must be registered
+// in the AssetManager
+impl xcm_primitives::AccountIdToCurrencyId<AccountId, CurrencyId> for Runtime {
+	fn account_to_currency_id(account: AccountId) -> Option<CurrencyId> {
+		match account {
+			a if a == H160::from_low_u64_be(2050) => Some(CurrencyId::SelfReserve),
+			a if &a.as_bytes()[0..4] == FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX => {
+				let mut data = [0u8; 16];
+				data.copy_from_slice(&a.as_bytes()[4..20]);
+				let asset_id = AssetId::from_be_bytes(data);
+
+				AssetManager::asset_id_type(asset_id).map(|_| CurrencyId::OtherReserve(asset_id))
+			}
+			_ => None,
+		}
+	}
+}
+
 parameter_types! {
 	pub const BaseXcmWeight: Weight = 100_000_000;
 	// This is how we are going to detect whether the asset is a Reserve asset
@@ -1205,6 +1245,67 @@ impl orml_xtokens::Config for Runtime {
 	type LocationInverter = LocationInverter<Ancestry>;
 }
 
+parameter_types! {
+	// Indices of the staking pallet of the relay chain (Westend) and of its calls
+	pub const RelayStakingCallIndices: xcm_primitives::relay_calls::StakingCallIndices =
+		xcm_primitives::relay_calls::StakingCallIndices {
+			pallet: 6,
+			bond: 0,
+			bond_extra: 1,
+			unbond: 2,
+			withdraw_unbonded: 3,
+			nominate: 5,
+			chill: 6,
+			set_payee: 7,
+		};
+	// Indices of the utility pallet of the relay chain (Westend) and of `as_derivative`
+	pub const RelayUtilityCallIndices: xcm_primitives::relay_calls::UtilityCallIndices =
+		xcm_primitives::relay_calls::UtilityCallIndices {
+			pallet: 16,
+			as_derivative: 1,
+		};
+	// Relay chain calls sent through a derivative account are limited to 2 KiB, and each byte is
+	// priced like a byte of Ethereum calldata (16 gas).
+	pub const MaxCallLength: u32 = 2048;
+	pub const WeightPerCallByte: Weight = 16 * WEIGHT_PER_GAS;
+}
+
+impl pallet_xcm_transactor::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AccountIdToMultiLocation = xcm_primitives::AccountIdToMultiLocation<AccountId>;
+	type AssetTransactor = AssetTransactors;
+	type XcmSender = XcmRouter;
+	type BaseXcmWeight = BaseXcmWeight;
+	type RelayUtilityCallIndices = RelayUtilityCallIndices;
+	type DerivativeAddressRegistrationOrigin = EnsureRoot<AccountId>;
+	type MaxCallLength = MaxCallLength;
+	type WeightPerCallByte = WeightPerCallByte;
+}
+
+/// The storage root of the relay parent, as set by the parachain inherent of the current block.
+pub struct RelayParentStorageRoot;
+impl Get<Option<H256>> for RelayParentStorageRoot {
+	fn get() -> Option<H256> {
+		ParachainSystem::validation_data().map(|data| data.relay_parent_storage_root)
+	}
+}
+
+parameter_types! {
+	pub const RandomnessDeposit: Balance = 1 * currency::UNIT;
+	pub const RandomnessMinBlockDelay: BlockNumber = 2;
+	pub const RandomnessMaxBlockDelay: BlockNumber = 2 * HOURS;
+}
+
+impl pallet_randomness::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Deposit = RandomnessDeposit;
+	type MinBlockDelay = RandomnessMinBlockDelay;
+	type MaxBlockDelay = RandomnessMaxBlockDelay;
+	type RelayStorageRoot = RelayParentStorageRoot;
+}
+
 /// Call filter used during Phase 3 of the Moonriver rollout
 pub struct MaintenanceFilter;
 impl Contains<Call> for MaintenanceFilter {
@@ -1215,6 +1316,7 @@ impl Contains<Call> for MaintenanceFilter {
 			Call::Ethereum(_) => false,
 			Call::EVM(_) => false,
 			Call::XTokens(_) => false,
+			Call::XcmTransactor(_) => false,
 			_ => true,
 		}
 	}
@@ -1239,12 +1341,87 @@ impl Contains<Call> for NormalFilter {
 	}
 }
 
+/// Pallets whose calls can never be paused through the maintenance mode pallet.
+/// The maintenance mode itself, the technical committee that controls it and sudo must remain
+/// available so that paused calls can always be unpaused. The pallets providing inherents cannot
+/// be paused either, as a block missing one of them would be invalid.
+pub struct UnpausablePallets;
+impl Contains<Vec<u8>> for UnpausablePallets {
+	fn contains(pallet_name: &Vec<u8>) -> bool {
+		matches!(
+			pallet_name.as_slice(),
+			b"System"
+				| b"MaintenanceMode"
+				| b"TechComitteeCollective"
+				| b"Sudo" | b"ParachainSystem"
+				| b"Timestamp" | b"AuthorInherent"
+				| b"Randomness"
+		)
+	}
+}
+
+parameter_types! {
+	pub const MaxPausedNameLength: u32 = 64;
+	/// Inbound XCM messages queued during maintenance are stored in chunks of at most 100KiB
+	pub const MaxQueuedXcmSize: u32 = 100 * 1024;
+}
+
+/// The hooks run while in maintenance mode. These are the same pallets and order as `AllPallets`
+/// except for:
+/// * ParachainStaking, so that the round transition is postponed until maintenance is over. Its
+///   hook catches up on the first block after resuming because the round only checks whether
+///   enough blocks have elapsed.
+/// * XcmpQueue and DmpQueue, so that the messages they already hold are not executed during
+///   maintenance either.
+/// Pallets that act on exact block numbers (Scheduler, Democracy, Treasury) are kept, as
+/// skipping their hooks would lose work rather than postpone it.
+pub type MaintenanceHooks = (
+	Randomness,
+	XcmTransactor,
+	AssetManager,
+	XTokens,
+	Assets,
+	PolkadotXcm,
+	CumulusXcm,
+	CrowdloanRewards,
+	Treasury,
+	TechComitteeCollective,
+	CouncilCollective,
+	Democracy,
+	Scheduler,
+	Ethereum,
+	EVM,
+	EthereumChainId,
+	Sudo,
+	Migrations,
+	Identity,
+	MaintenanceMode,
+	Proxy,
+	Utility,
+	AuthorMapping,
+	AuthorFilter,
+	AuthorInherent,
+	TransactionPayment,
+	Balances,
+	ParachainInfo,
+	Timestamp,
+	RandomnessCollectiveFlip,
+	ParachainSystem,
+);
+
 impl pallet_maintenance_mode::Config for Runtime {
 	type Event = Event;
 	type NormalCallFilter = NormalFilter;
 	type MaintenanceCallFilter = MaintenanceFilter;
 	type MaintenanceOrigin =
 		pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, TechCommitteeInstance>;
+	type UnpausablePallets = UnpausablePallets;
+	type MaxNameLength = MaxPausedNameLength;
+	type NormalDmpHandler = DmpQueue;
+	type NormalXcmpHandler = XcmpQueue;
+	type MaxQueuedXcmSize = MaxQueuedXcmSize;
+	type NormalExecutiveHooks = AllPallets;
+	type MaintenanceExecutiveHooks = MaintenanceHooks;
 }
 
 construct_runtime! {
@@ -1275,13 +1452,13 @@ construct_runtime! {
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 31,
 		MaintenanceMode: pallet_maintenance_mode::{Pallet, Call, Config, Storage, Event} = 32,
 		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>} = 33,
-		Migrations: pallet_migrations::{Pallet, Storage, Config, Event<T>} = 34,
+		Migrations: pallet_migrations::{Pallet, Call, Storage, Config, Event<T>} = 34,
 
 		// Sudo
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 40,
 
 		// Ethereum compatibility
-		EthereumChainId: pallet_ethereum_chain_id::{Pallet, Storage, Config} = 50,
+		EthereumChainId: pallet_ethereum_chain_id::{Pallet, Call, Storage, Config, Event} = 50,
 		EVM: pallet_evm::{Pallet, Config, Call, Storage, Event<T>} = 51,
 		Ethereum: pallet_ethereum::{Pallet, Call, Storage, Event, Config, ValidateUnsigned} = 52,
 
@@ -1310,6 +1487,10 @@ construct_runtime! {
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 104,
 		XTokens: orml_xtokens::{Pallet, Call, Storage, Event<T>} = 105,
 		AssetManager: pallet_asset_manager::{Pallet, Call, Storage, Event<T>} = 106,
+		XcmTransactor: pallet_xcm_transactor::{Pallet, Call, Storage, Event<T>} = 107,
+
+		// Randomness stuff.
+		Randomness: pallet_randomness::{Pallet, Call, Storage, Event<T>, Inherent} = 110,
 	}
 }
 
@@ -1345,6 +1526,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	AdjustPriority<frame_system::CheckWeight<Runtime>, Divide, CHECK_WEIGHT_PRIORITY_DIVISOR>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_ethereum_chain_id::CheckRetiredChainId<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -1356,7 +1538,7 @@ pub type Executive = frame_executive::Executive<
 	Block,
 	frame_system::ChainContext<Runtime>,
 	Runtime,
-	AllPallets,
+	pallet_maintenance_mode::ExecutiveHooks<Runtime>,
 	MigratePalletVersionToStorageVersion,
 >;
 
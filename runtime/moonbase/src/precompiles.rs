@@ -16,20 +16,32 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use author_mapping_precompiles::AuthorMappingWrapper;
 use crowdloan_rewards_precompiles::CrowdloanRewardsWrapper;
 use evm::{executor::PrecompileOutput, Context, ExitError};
+use pallet_collective_precompiles::CollectiveWrapper;
 use pallet_democracy_precompiles::DemocracyWrapper;
 use pallet_evm::{AddressMapping, Precompile, PrecompileSet};
 use pallet_evm_precompile_balances_erc20::Erc20BalancesPrecompile;
+use pallet_evm_precompile_batch::BatchPrecompile;
+use pallet_evm_precompile_blake2::Blake2F;
 use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
 use pallet_evm_precompile_dispatch::Dispatch;
 use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_proxy::ProxyWrapper;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
+use pallet_identity_precompiles::IdentityWrapper;
+use pallet_treasury_precompiles::TreasuryWrapper;
 use parachain_staking_precompiles::ParachainStakingWrapper;
+use precompile_utils::check_not_delegated;
+use randomness_precompiles::RandomnessWrapper;
+use relay_encoder_precompiles::RelayEncoderWrapper;
 use sp_core::H160;
 use sp_std::fmt::Debug;
 use sp_std::marker::PhantomData;
+use xcm_transactor_precompiles::XcmTransactorWrapper;
+use xtokens_precompiles::XtokensWrapper;
 
 /// The PrecompileSet installed in the Moonbase runtime.
 /// We include the nine Istanbul precompiles
@@ -45,7 +57,12 @@ where
 	/// Return all addresses that contain precompiles. This can be used to populate dummy code
 	/// under the precompile.
 	pub fn used_addresses() -> impl Iterator<Item = R::AccountId> {
-		sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 1024, 1025, 1026, 2048, 2049, 2050, 2051]
+		let addresses = sp_std::vec![
+			1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026, 2048, 2049, 2050, 2051, 2052, 2053, 2054,
+			2055, 2056, 2057, 2058, 2059, 2060, 2061, 2062,
+		];
+
+		addresses
 			.into_iter()
 			.map(|x| R::AddressMapping::into_account_id(hash(x)))
 	}
@@ -62,6 +79,16 @@ where
 	CrowdloanRewardsWrapper<R>: Precompile,
 	Erc20BalancesPrecompile<R>: Precompile,
 	DemocracyWrapper<R>: Precompile,
+	BatchPrecompile<R>: Precompile,
+	XtokensWrapper<R>: Precompile,
+	XcmTransactorWrapper<R>: Precompile,
+	ProxyWrapper<R>: Precompile,
+	IdentityWrapper<R>: Precompile,
+	CollectiveWrapper<R, crate::CouncilInstance>: Precompile,
+	CollectiveWrapper<R, crate::TechCommitteeInstance>: Precompile,
+	AuthorMappingWrapper<R>: Precompile,
+	TreasuryWrapper<R>: Precompile,
+	RandomnessWrapper<R>: Precompile,
 {
 	fn execute(
 		address: H160,
@@ -79,6 +106,7 @@ where
 			a if a == hash(6) => Some(Bn128Add::execute(input, target_gas, context)),
 			a if a == hash(7) => Some(Bn128Mul::execute(input, target_gas, context)),
 			a if a == hash(8) => Some(Bn128Pairing::execute(input, target_gas, context)),
+			a if a == hash(9) => Some(Blake2F::execute(input, target_gas, context)),
 			// Non-Moonbeam specific nor Ethereum precompiles :
 			a if a == hash(1024) => Some(Sha3FIPS256::execute(input, target_gas, context)),
 			a if a == hash(1025) => Some(Dispatch::<R>::execute(input, target_gas, context)),
@@ -96,6 +124,51 @@ where
 			a if a == hash(2051) => {
 				Some(DemocracyWrapper::<R>::execute(input, target_gas, context))
 			}
+			a if a == hash(2052) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| BatchPrecompile::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2053) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| XtokensWrapper::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2054) => Some(
+				RelayEncoderWrapper::<crate::RelayStakingCallIndices>::execute(
+					input, target_gas, context,
+				),
+			),
+			a if a == hash(2055) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| XcmTransactorWrapper::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2056) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| ProxyWrapper::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2057) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| IdentityWrapper::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2058) => Some(check_not_delegated(a, context).and_then(|_| {
+				CollectiveWrapper::<R, crate::CouncilInstance>::execute(input, target_gas, context)
+			})),
+			a if a == hash(2059) => Some(check_not_delegated(a, context).and_then(|_| {
+				CollectiveWrapper::<R, crate::TechCommitteeInstance>::execute(
+					input, target_gas, context,
+				)
+			})),
+			a if a == hash(2060) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| AuthorMappingWrapper::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2061) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| TreasuryWrapper::<R>::execute(input, target_gas, context)),
+			),
+			a if a == hash(2062) => Some(
+				check_not_delegated(a, context)
+					.and_then(|_| RandomnessWrapper::<R>::execute(input, target_gas, context)),
+			),
 			_ => None,
 		}
 	}
@@ -17,7 +17,8 @@
 pub mod parachain;
 pub mod relay_chain;
 
-use sp_runtime::AccountId32;
+use polkadot_parachain::primitives::Id as ParaId;
+use sp_runtime::{traits::AccountIdConversion, AccountId32};
 use xcm_simulator::{decl_test_network, decl_test_parachain, decl_test_relay_chain};
 pub const PARAALICE: [u8; 20] = [1u8; 20];
 pub const RELAYALICE: AccountId32 = AccountId32::new([0u8; 32]);
@@ -25,8 +26,8 @@ pub const RELAYALICE: AccountId32 = AccountId32::new([0u8; 32]);
 decl_test_parachain! {
 	pub struct ParaA {
 		Runtime = parachain::Runtime,
-		XcmpMessageHandler = parachain::MsgQueue,
-		DmpMessageHandler = parachain::MsgQueue,
+		XcmpMessageHandler = parachain::MaintenanceMode,
+		DmpMessageHandler = parachain::MaintenanceMode,
 		new_ext = para_ext(1),
 	}
 }
@@ -34,8 +35,8 @@ decl_test_parachain! {
 decl_test_parachain! {
 	pub struct ParaB {
 		Runtime = parachain::Runtime,
-		XcmpMessageHandler = parachain::MsgQueue,
-		DmpMessageHandler = parachain::MsgQueue,
+		XcmpMessageHandler = parachain::MaintenanceMode,
+		DmpMessageHandler = parachain::MaintenanceMode,
 		new_ext = para_ext(2),
 	}
 }
@@ -43,8 +44,8 @@ decl_test_parachain! {
 decl_test_parachain! {
 	pub struct ParaC {
 		Runtime = parachain::Runtime,
-		XcmpMessageHandler = parachain::MsgQueue,
-		DmpMessageHandler = parachain::MsgQueue,
+		XcmpMessageHandler = parachain::MaintenanceMode,
+		DmpMessageHandler = parachain::MaintenanceMode,
 		new_ext = para_ext(3),
 	}
 }
@@ -70,6 +71,11 @@ decl_test_network! {
 
 pub const INITIAL_BALANCE: u128 = 1_000_000_000;
 
+/// The sovereign account of ParaA on the relay chain
+pub fn para_a_account() -> AccountId32 {
+	ParaId::from(1).into_account()
+}
+
 pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
 	use parachain::{MsgQueue, Runtime, System};
 
@@ -115,3 +121,6 @@ pub type AssetManager = pallet_asset_manager::Pallet<parachain::Runtime>;
 pub type XTokens = orml_xtokens::Pallet<parachain::Runtime>;
 pub type RelayBalances = pallet_balances::Pallet<relay_chain::Runtime>;
 pub type ParaBalances = pallet_balances::Pallet<parachain::Runtime>;
+pub type MaintenanceMode = pallet_maintenance_mode::Pallet<parachain::Runtime>;
+pub type XcmTransactor = pallet_xcm_transactor::Pallet<parachain::Runtime>;
+pub type RelayUtility = pallet_utility::Pallet<relay_chain::Runtime>;
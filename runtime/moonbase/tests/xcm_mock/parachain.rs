@@ -16,19 +16,23 @@
 
 //! Parachain runtime mock.
 
+use evm::{executor::PrecompileOutput, Context, ExitError};
 use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{Everything, Get, Nothing, PalletInfo as PalletInfoTrait},
 	weights::Weight,
 };
 use frame_system::EnsureRoot;
+use pallet_evm::{
+	EnsureAddressNever, EnsureAddressRoot, IdentityAddressMapping, Precompile, PrecompileSet,
+};
 use parity_scale_codec::{Decode, Encode};
-use sp_core::H256;
+use sp_core::{H160, H256};
 use sp_runtime::{
 	testing::Header,
 	traits::{Hash, IdentityLookup},
 };
-use sp_std::{convert::TryFrom, prelude::*};
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*};
 use xcm::{latest::prelude::*, VersionedXcm};
 
 use polkadot_core_primitives::BlockNumber as RelayBlockNumber;
@@ -46,7 +50,10 @@ use xcm_builder::{
 	SignedAccountKey20AsNative, SovereignSignedViaLocation, TakeWeightCredit,
 };
 use xcm_executor::{traits::JustTry, Config, XcmExecutor};
+use xcm_transactor_precompiles::XcmTransactorWrapper;
+use xtokens_precompiles::XtokensWrapper;
 
+use xcm_primitives::{relay_calls::UtilityCallIndices, AccountIdToCurrencyId};
 use xcm_simulator::{
 	DmpMessageHandlerT as DmpMessageHandler, XcmpMessageFormat,
 	XcmpMessageHandlerT as XcmpMessageHandler,
@@ -302,6 +309,29 @@ impl orml_xtokens::Config for Runtime {
 	type LocationInverter = LocationInverter<Ancestry>;
 }
 
+parameter_types! {
+	// The index of the utility pallet in the relay chain mock
+	pub const RelayUtilityCallIndices: UtilityCallIndices = UtilityCallIndices {
+		pallet: 5,
+		as_derivative: 1,
+	};
+	pub const MaxCallLength: u32 = 2048;
+	pub const WeightPerCallByte: Weight = 10;
+}
+
+impl pallet_xcm_transactor::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AccountIdToMultiLocation = xcm_primitives::AccountIdToMultiLocation<AccountId>;
+	type AssetTransactor = AssetTransactors;
+	type XcmSender = XcmRouter;
+	type BaseXcmWeight = BaseXcmWeight;
+	type RelayUtilityCallIndices = RelayUtilityCallIndices;
+	type DerivativeAddressRegistrationOrigin = EnsureRoot<AccountId>;
+	type MaxCallLength = MaxCallLength;
+	type WeightPerCallByte = WeightPerCallByte;
+}
+
 #[frame_support::pallet]
 pub mod mock_msg_queue {
 	use super::*;
@@ -443,6 +473,24 @@ impl mock_msg_queue::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 }
 
+parameter_types! {
+	pub const MaxPausedNameLength: u32 = 64;
+	pub const MaxQueuedXcmSize: u32 = 100 * 1024;
+}
+
+// Inbound XCM goes through maintenance mode before reaching the message queue
+impl pallet_maintenance_mode::Config for Runtime {
+	type Event = Event;
+	type NormalCallFilter = Everything;
+	type MaintenanceCallFilter = Everything;
+	type MaintenanceOrigin = EnsureRoot<AccountId>;
+	type UnpausablePallets = Nothing;
+	type MaxNameLength = MaxPausedNameLength;
+	type NormalDmpHandler = MsgQueue;
+	type NormalXcmpHandler = MsgQueue;
+	type MaxQueuedXcmSize = MaxQueuedXcmSize;
+}
+
 pub type LocalOriginToLocation =
 	xcm_primitives::SignedToAccountId20<Origin, AccountId, RelayNetwork>;
 
@@ -546,6 +594,84 @@ impl pallet_asset_manager::Config for Runtime {
 	type AssetModifierOrigin = EnsureRoot<AccountId>;
 }
 
+// The prefix of the addresses of the ERC-20 precompiles of foreign assets, which are followed by
+// the AssetId
+pub const FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8; 4];
+
+// Same conversion as in the runtime: the native currency is the balances ERC-20 precompile, while
+// foreign assets must be registered in the AssetManager
+impl AccountIdToCurrencyId<AccountId, CurrencyId> for Runtime {
+	fn account_to_currency_id(account: AccountId) -> Option<CurrencyId> {
+		match account {
+			a if a == H160::from_low_u64_be(2050) => Some(CurrencyId::SelfReserve),
+			a if &a.as_bytes()[0..4] == FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX => {
+				let mut data = [0u8; 16];
+				data.copy_from_slice(&a.as_bytes()[4..20]);
+				let asset_id = AssetId::from_be_bytes(data);
+
+				AssetManager::asset_id_type(asset_id).map(|_| CurrencyId::OtherReserve(asset_id))
+			}
+			_ => None,
+		}
+	}
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1000;
+}
+
+impl pallet_timestamp::Config for Runtime {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+/// The precompiles of the mock, with the xtokens and xcm-transactor precompiles at the same
+/// addresses as in the runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct Precompiles<R>(PhantomData<R>);
+
+impl<R> PrecompileSet for Precompiles<R>
+where
+	XtokensWrapper<R>: Precompile,
+	XcmTransactorWrapper<R>: Precompile,
+{
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Option<Result<PrecompileOutput, ExitError>> {
+		match address {
+			a if a == H160::from_low_u64_be(2053) => {
+				Some(XtokensWrapper::<R>::execute(input, target_gas, context))
+			}
+			a if a == H160::from_low_u64_be(2055) => Some(XcmTransactorWrapper::<R>::execute(
+				input, target_gas, context,
+			)),
+			_ => None,
+		}
+	}
+}
+
+impl pallet_evm::Config for Runtime {
+	type FeeCalculator = ();
+	type GasWeightMapping = ();
+	type CallOrigin = EnsureAddressRoot<AccountId>;
+	type WithdrawOrigin = EnsureAddressNever<AccountId>;
+	type AddressMapping = IdentityAddressMapping;
+	type Currency = Balances;
+	type Event = Event;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type Precompiles = Precompiles<Self>;
+	type ChainId = ();
+	type OnChargeTransaction = ();
+	type BlockGasLimit = ();
+	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+	type FindAuthor = ();
+}
+
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
 type Block = frame_system::mocking::MockBlock<Runtime>;
 
@@ -563,5 +689,8 @@ construct_runtime!(
 		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin},
 		XTokens: orml_xtokens::{Pallet, Call, Storage, Event<T>},
 		AssetManager: pallet_asset_manager::{Pallet, Call, Storage, Event<T>},
+		MaintenanceMode: pallet_maintenance_mode::{Pallet, Call, Storage, Event},
+		EVM: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		XcmTransactor: pallet_xcm_transactor::{Pallet, Call, Storage, Event<T>},
 	}
 );
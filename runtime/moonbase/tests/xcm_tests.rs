@@ -17,10 +17,17 @@
 //! Moonbase Runtime Integration Tests
 
 mod xcm_mock;
-use frame_support::assert_ok;
+use evm::{executor::PrecompileOutput, Context, ExitError};
+use frame_support::{assert_noop, assert_ok, traits::OnIdle, weights::Weight};
+use pallet_evm::PrecompileSet;
+use parity_scale_codec::Encode;
+use precompile_utils::{error, Address, Bytes, EvmDataWriter};
+use sp_core::{H160, U256};
 use xcm_mock::parachain;
 use xcm_mock::relay_chain;
 use xcm_mock::*;
+use xcm_transactor_precompiles::Action as XcmTransactorAction;
+use xtokens_precompiles::Action as XtokensAction;
 
 use xcm::v1::{
 	Junction::{self, AccountId32, AccountKey20, PalletInstance, Parachain},
@@ -623,3 +630,614 @@ fn error_when_not_paying_enough() {
 		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
 	});
 }
+
+#[test]
+fn receive_relay_asset_is_queued_during_maintenance_mode() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+		assert_ok!(MaintenanceMode::enter_maintenance_mode(
+			parachain::Origin::root()
+		));
+	});
+
+	let dest: MultiLocation = AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, 123).into()),
+			0,
+			123,
+		));
+	});
+
+	ParaA::execute_with(|| {
+		// The message is held back until maintenance is over
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+		assert!(!MaintenanceMode::xcm_queue_is_empty());
+
+		// Nothing gets executed while we are still in maintenance
+		MaintenanceMode::on_idle(2, Weight::max_value());
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+
+		assert_ok!(MaintenanceMode::resume_normal_operation(
+			parachain::Origin::root()
+		));
+		MaintenanceMode::on_idle(3, Weight::max_value());
+
+		// free execution, full amount received
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 123);
+		assert!(MaintenanceMode::xcm_queue_is_empty());
+	});
+}
+
+#[test]
+fn send_para_a_asset_to_para_b_is_queued_during_maintenance_mode() {
+	MockNet::reset();
+
+	let para_a_balances = MultiLocation::new(1, X2(Parachain(1), PalletInstance(1u8)));
+	let source_location = parachain::AssetType::Xcm(para_a_balances);
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"ParaAToken".to_vec(),
+		symbol: b"ParaA".to_vec(),
+		decimals: 18,
+	};
+
+	ParaB::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+		assert_ok!(MaintenanceMode::enter_maintenance_mode(
+			parachain::Origin::root()
+		));
+	});
+
+	let dest = MultiLocation {
+		parents: 1,
+		interior: X2(
+			Parachain(2),
+			AccountKey20 {
+				network: NetworkId::Any,
+				key: PARAALICE.into(),
+			},
+		),
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(XTokens::transfer(
+			parachain::Origin::signed(PARAALICE.into()),
+			parachain::CurrencyId::SelfReserve,
+			100,
+			Box::new(dest),
+			800000
+		));
+	});
+
+	ParaB::execute_with(|| {
+		// The message is held back until maintenance is over
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+
+		assert_ok!(MaintenanceMode::resume_normal_operation(
+			parachain::Origin::root()
+		));
+		MaintenanceMode::on_idle(2, Weight::max_value());
+
+		// free execution, full amount received
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 100);
+	});
+}
+
+// Call the xtokens precompile of the parachain on behalf of PARAALICE
+fn call_xtokens_precompile(input: Vec<u8>) -> Result<PrecompileOutput, ExitError> {
+	let address = H160::from_low_u64_be(2053);
+	let context = Context {
+		address,
+		caller: PARAALICE.into(),
+		apparent_value: U256::zero(),
+	};
+
+	parachain::Precompiles::<parachain::Runtime>::execute(address, &input, None, &context)
+		.expect("xtokens precompile is installed")
+}
+
+// Address of the ERC-20 precompile of a foreign asset
+fn asset_precompile_address(asset_id: parachain::AssetId) -> H160 {
+	let mut address = [255u8; 20];
+	address[4..20].copy_from_slice(&asset_id.to_be_bytes());
+	address.into()
+}
+
+#[test]
+fn send_relay_asset_to_relay_through_xtokens_precompile() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+	});
+
+	let dest: MultiLocation = Junction::AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, 123).into()),
+			0,
+			123,
+		));
+	});
+
+	ParaA::execute_with(|| {
+		// free execution, full amount received
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 123);
+	});
+
+	let mut balance_before_sending = 0;
+	Relay::execute_with(|| {
+		balance_before_sending = RelayBalances::free_balance(&RELAYALICE);
+	});
+
+	let dest = MultiLocation {
+		parents: 1,
+		interior: X1(AccountId32 {
+			network: NetworkId::Any,
+			id: RELAYALICE.into(),
+		}),
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(call_xtokens_precompile(
+			EvmDataWriter::new_with_selector(XtokensAction::Transfer as u32)
+				.write(Address(asset_precompile_address(source_id)))
+				.write(U256::from(123))
+				.write(dest)
+				.write(40000u64)
+				.build()
+		));
+
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+	});
+
+	Relay::execute_with(|| {
+		// free execution, full amount received
+		assert!(RelayBalances::free_balance(&RELAYALICE) > balance_before_sending);
+	});
+}
+
+#[test]
+fn send_relay_asset_to_relay_through_xtokens_precompile_multiasset() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+	});
+
+	let dest: MultiLocation = Junction::AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, 123).into()),
+			0,
+			123,
+		));
+	});
+
+	let mut balance_before_sending = 0;
+	Relay::execute_with(|| {
+		balance_before_sending = RelayBalances::free_balance(&RELAYALICE);
+	});
+
+	let dest = MultiLocation {
+		parents: 1,
+		interior: X1(AccountId32 {
+			network: NetworkId::Any,
+			id: RELAYALICE.into(),
+		}),
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(call_xtokens_precompile(
+			EvmDataWriter::new_with_selector(XtokensAction::TransferMultiasset as u32)
+				.write(MultiLocation::parent())
+				.write(U256::from(123))
+				.write(dest)
+				.write(40000u64)
+				.build()
+		));
+
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+	});
+
+	Relay::execute_with(|| {
+		// free execution, full amount received
+		assert!(RelayBalances::free_balance(&RELAYALICE) > balance_before_sending);
+	});
+}
+
+#[test]
+fn send_para_a_asset_to_para_b_through_xtokens_precompile() {
+	MockNet::reset();
+
+	let para_a_balances = MultiLocation::new(1, X2(Parachain(1), PalletInstance(1u8)));
+	let source_location = parachain::AssetType::Xcm(para_a_balances);
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"ParaAToken".to_vec(),
+		symbol: b"ParaA".to_vec(),
+		decimals: 18,
+	};
+
+	ParaB::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+	});
+
+	let dest = MultiLocation {
+		parents: 1,
+		interior: X2(
+			Parachain(2),
+			AccountKey20 {
+				network: NetworkId::Any,
+				key: PARAALICE.into(),
+			},
+		),
+	};
+
+	ParaA::execute_with(|| {
+		// The native currency is identified by the address of the balances ERC-20 precompile
+		assert_ok!(call_xtokens_precompile(
+			EvmDataWriter::new_with_selector(XtokensAction::Transfer as u32)
+				.write(Address(H160::from_low_u64_be(2050)))
+				.write(U256::from(100))
+				.write(dest)
+				.write(800000u64)
+				.build()
+		));
+
+		assert_eq!(
+			ParaBalances::free_balance(&PARAALICE.into()),
+			INITIAL_BALANCE - 100
+		);
+	});
+
+	ParaB::execute_with(|| {
+		// free execution, full amount received
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 100);
+	});
+}
+
+#[test]
+fn xtokens_precompile_rejects_unknown_currency() {
+	MockNet::reset();
+
+	let dest = MultiLocation {
+		parents: 1,
+		interior: X1(AccountId32 {
+			network: NetworkId::Any,
+			id: RELAYALICE.into(),
+		}),
+	};
+
+	ParaA::execute_with(|| {
+		// The asset is not registered in the AssetManager
+		let source_id: parachain::AssetId =
+			parachain::AssetType::Xcm(MultiLocation::parent()).into();
+
+		assert_eq!(
+			call_xtokens_precompile(
+				EvmDataWriter::new_with_selector(XtokensAction::Transfer as u32)
+					.write(Address(asset_precompile_address(source_id)))
+					.write(U256::from(100))
+					.write(dest)
+					.write(40000u64)
+					.build()
+			)
+			.map(|_| ()),
+			Err(error("cannot convert into currency id"))
+		);
+	});
+}
+
+// Register the relay token in ParaA and send it to PARAALICE, so that the sovereign account of
+// ParaA on the relay chain holds `amount`. Returns the asset id of the relay token in ParaA.
+fn register_and_receive_relay_asset(amount: u128) -> parachain::AssetId {
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+	});
+
+	let dest: MultiLocation = Junction::AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, amount).into()),
+			0,
+			123,
+		));
+	});
+
+	ParaA::execute_with(|| {
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), amount);
+	});
+
+	source_id
+}
+
+// Fund the derivative account `index` of ParaA on the relay chain, and return it
+fn fund_derivative_account(index: u16, amount: u128) -> relay_chain::AccountId {
+	let derivative = RelayUtility::derivative_account_id(para_a_account(), index);
+
+	Relay::execute_with(|| {
+		assert_ok!(RelayBalances::transfer(
+			relay_chain::Origin::signed(RELAYALICE),
+			derivative.clone(),
+			amount
+		));
+	});
+
+	derivative
+}
+
+// A relay chain transfer of `amount` to RELAYALICE
+fn encoded_relay_transfer(amount: u128) -> Vec<u8> {
+	relay_chain::Call::Balances(pallet_balances::Call::<relay_chain::Runtime>::transfer(
+		RELAYALICE, amount,
+	))
+	.encode()
+}
+
+#[test]
+fn transact_through_derivative() {
+	MockNet::reset();
+
+	let source_id = register_and_receive_relay_asset(123);
+
+	ParaA::execute_with(|| {
+		assert_ok!(XcmTransactor::register(
+			parachain::Origin::root(),
+			PARAALICE.into(),
+			0
+		));
+	});
+
+	let derivative = fund_derivative_account(0, 100);
+
+	let mut balance_before_transact = 0;
+	Relay::execute_with(|| {
+		balance_before_transact = RelayBalances::free_balance(&RELAYALICE);
+	});
+
+	ParaA::execute_with(|| {
+		assert_ok!(XcmTransactor::transact_through_derivative(
+			parachain::Origin::signed(PARAALICE.into()),
+			0,
+			100u128,
+			4_000_000_000,
+			encoded_relay_transfer(50)
+		));
+
+		// The fees are paid with the relay token of PARAALICE
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 23);
+	});
+
+	Relay::execute_with(|| {
+		// The transfer was dispatched from the derivative account
+		assert_eq!(RelayBalances::free_balance(&derivative), 50);
+		assert_eq!(
+			RelayBalances::free_balance(&RELAYALICE),
+			balance_before_transact + 50
+		);
+	});
+}
+
+#[test]
+fn transact_through_derivative_of_another_account_fails() {
+	MockNet::reset();
+
+	register_and_receive_relay_asset(123);
+
+	ParaA::execute_with(|| {
+		assert_ok!(XcmTransactor::register(
+			parachain::Origin::root(),
+			[2u8; 20].into(),
+			0
+		));
+
+		assert_noop!(
+			XcmTransactor::transact_through_derivative(
+				parachain::Origin::signed(PARAALICE.into()),
+				0,
+				100u128,
+				4_000_000_000,
+				encoded_relay_transfer(50)
+			),
+			pallet_xcm_transactor::Error::<parachain::Runtime>::NotOwner
+		);
+	});
+}
+
+// Call the xcm-transactor precompile of the parachain on behalf of PARAALICE
+fn call_xcm_transactor_precompile(input: Vec<u8>) -> Result<PrecompileOutput, ExitError> {
+	let address = H160::from_low_u64_be(2055);
+	let context = Context {
+		address,
+		caller: PARAALICE.into(),
+		apparent_value: U256::zero(),
+	};
+
+	parachain::Precompiles::<parachain::Runtime>::execute(address, &input, None, &context)
+		.expect("xcm-transactor precompile is installed")
+}
+
+#[test]
+fn transact_through_derivative_through_xcm_transactor_precompile() {
+	MockNet::reset();
+
+	let source_id = register_and_receive_relay_asset(123);
+
+	ParaA::execute_with(|| {
+		assert_eq!(
+			call_xcm_transactor_precompile(
+				EvmDataWriter::new_with_selector(XcmTransactorAction::IndexToAccount as u32)
+					.write(0u16)
+					.build()
+			)
+			.map(|_| ()),
+			Err(error("no account registered for this index"))
+		);
+
+		assert_ok!(XcmTransactor::register(
+			parachain::Origin::root(),
+			PARAALICE.into(),
+			0
+		));
+
+		let output = call_xcm_transactor_precompile(
+			EvmDataWriter::new_with_selector(XcmTransactorAction::IndexToAccount as u32)
+				.write(0u16)
+				.build(),
+		)
+		.expect("index is registered");
+		assert_eq!(
+			output.output,
+			EvmDataWriter::new()
+				.write(Address(PARAALICE.into()))
+				.build()
+		);
+	});
+
+	let derivative = fund_derivative_account(0, 100);
+
+	ParaA::execute_with(|| {
+		assert_ok!(call_xcm_transactor_precompile(
+			EvmDataWriter::new_with_selector(XcmTransactorAction::TransactThroughDerivative as u32)
+				.write(0u16)
+				.write(U256::from(100))
+				.write(4_000_000_000u64)
+				.write(Bytes(encoded_relay_transfer(50)))
+				.build()
+		));
+
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 23);
+	});
+
+	Relay::execute_with(|| {
+		assert_eq!(RelayBalances::free_balance(&derivative), 50);
+	});
+}
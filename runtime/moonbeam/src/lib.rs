@@ -142,7 +142,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_version: 0701,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 };
 
 /// The version information used to identify this runtime when compiled natively.
@@ -305,7 +305,14 @@ impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 }
 
-impl pallet_ethereum_chain_id::Config for Runtime {}
+parameter_types! {
+	pub const MaxPreviousChainIds: u32 = 16;
+}
+
+impl pallet_ethereum_chain_id::Config for Runtime {
+	type Event = Event;
+	type MaxPreviousChainIds = MaxPreviousChainIds;
+}
 
 impl pallet_randomness_collective_flip::Config for Runtime {}
 
@@ -820,9 +827,17 @@ impl pallet_proxy::Config for Runtime {
 	type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
+parameter_types! {
+	// Leave room in the block for the mandatory inherents and the other hooks
+	pub const MigrationsBudget: Perbill = Perbill::from_percent(50);
+}
+
 impl pallet_migrations::Config for Runtime {
 	type Event = Event;
 	type MigrationsList = runtime_common::migrations::CommonMigrations;
+	type MaintenanceMode = MaintenanceMode;
+	type MigrationsOrigin = EnsureRoot<AccountId>;
+	type MigrationsBudget = MigrationsBudget;
 }
 
 /// Call filter expected to be used during Phase 3 of the Moonbeam rollout
@@ -840,12 +855,76 @@ impl Contains<Call> for PhaseThreeFilter {
 	}
 }
 
+/// Pallets whose calls can never be paused through the maintenance mode pallet.
+/// The maintenance mode itself, the technical committee that controls it and sudo must remain
+/// available so that paused calls can always be unpaused. The pallets providing inherents cannot
+/// be paused either, as a block missing one of them would be invalid.
+pub struct UnpausablePallets;
+impl Contains<Vec<u8>> for UnpausablePallets {
+	fn contains(pallet_name: &Vec<u8>) -> bool {
+		matches!(
+			pallet_name.as_slice(),
+			b"System"
+				| b"MaintenanceMode"
+				| b"TechComitteeCollective"
+				| b"Sudo" | b"ParachainSystem"
+				| b"Timestamp" | b"AuthorInherent"
+		)
+	}
+}
+
+parameter_types! {
+	pub const MaxPausedNameLength: u32 = 64;
+	/// Inbound XCM messages queued during maintenance are stored in chunks of at most 100KiB
+	pub const MaxQueuedXcmSize: u32 = 100 * 1024;
+}
+
+/// The hooks run while in maintenance mode. These are the same pallets and order as `AllPallets`
+/// except for ParachainStaking, so that the round transition is postponed until maintenance is
+/// over. Its hook catches up on the first block after resuming because the round only checks
+/// whether enough blocks have elapsed.
+/// Pallets that act on exact block numbers (Scheduler, Democracy, Treasury) are kept, as
+/// skipping their hooks would lose work rather than postpone it.
+pub type MaintenanceHooks = (
+	CrowdloanRewards,
+	Treasury,
+	TechComitteeCollective,
+	CouncilCollective,
+	Democracy,
+	Scheduler,
+	Ethereum,
+	EVM,
+	EthereumChainId,
+	Sudo,
+	Migrations,
+	Identity,
+	MaintenanceMode,
+	Proxy,
+	Utility,
+	AuthorMapping,
+	AuthorFilter,
+	AuthorInherent,
+	TransactionPayment,
+	Balances,
+	ParachainInfo,
+	Timestamp,
+	RandomnessCollectiveFlip,
+	ParachainSystem,
+);
+
 impl pallet_maintenance_mode::Config for Runtime {
 	type Event = Event;
 	type NormalCallFilter = BaseFilter;
 	type MaintenanceCallFilter = PhaseThreeFilter;
 	type MaintenanceOrigin =
 		pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, TechCommitteeInstance>;
+	type UnpausablePallets = UnpausablePallets;
+	type MaxNameLength = MaxPausedNameLength;
+	type NormalDmpHandler = ();
+	type NormalXcmpHandler = ();
+	type MaxQueuedXcmSize = MaxQueuedXcmSize;
+	type NormalExecutiveHooks = AllPallets;
+	type MaintenanceExecutiveHooks = MaintenanceHooks;
 }
 
 construct_runtime! {
@@ -876,13 +955,13 @@ construct_runtime! {
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 31,
 		MaintenanceMode: pallet_maintenance_mode::{Pallet, Call, Config, Storage, Event} = 32,
 		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>} = 33,
-		Migrations: pallet_migrations::{Pallet, Storage, Config, Event<T>} = 34,
+		Migrations: pallet_migrations::{Pallet, Call, Storage, Config, Event<T>} = 34,
 
 		// Sudo.
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 40,
 
 		// Ethereum compatibility.
-		EthereumChainId: pallet_ethereum_chain_id::{Pallet, Storage, Config} = 50,
+		EthereumChainId: pallet_ethereum_chain_id::{Pallet, Call, Storage, Config, Event} = 50,
 		EVM: pallet_evm::{Pallet, Config, Call, Storage, Event<T>} = 51,
 		Ethereum: pallet_ethereum::{Pallet, Call, Storage, Event, Config, ValidateUnsigned} = 52,
 
@@ -936,6 +1015,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	AdjustPriority<frame_system::CheckWeight<Runtime>, Divide, CHECK_WEIGHT_PRIORITY_DIVISOR>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_ethereum_chain_id::CheckRetiredChainId<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -947,7 +1027,7 @@ pub type Executive = frame_executive::Executive<
 	Block,
 	frame_system::ChainContext<Runtime>,
 	Runtime,
-	AllPallets,
+	pallet_maintenance_mode::ExecutiveHooks<Runtime>,
 	MigratePalletVersionToStorageVersion,
 >;
 
@@ -169,13 +169,43 @@ fn verify_pallet_prefixes() {
 	);
 	assert_eq!(
 		<moonriver_runtime::MaintenanceMode as StorageInfoTrait>::storage_info(),
-		vec![StorageInfo {
-			pallet_name: b"MaintenanceMode".to_vec(),
-			storage_name: b"MaintenanceMode".to_vec(),
-			prefix: prefix(b"MaintenanceMode", b"MaintenanceMode"),
-			max_values: Some(1),
-			max_size: Some(1),
-		},]
+		vec![
+			StorageInfo {
+				pallet_name: b"MaintenanceMode".to_vec(),
+				storage_name: b"MaintenanceMode".to_vec(),
+				prefix: prefix(b"MaintenanceMode", b"MaintenanceMode"),
+				max_values: Some(1),
+				max_size: Some(1),
+			},
+			StorageInfo {
+				pallet_name: b"MaintenanceMode".to_vec(),
+				storage_name: b"PausedCalls".to_vec(),
+				prefix: prefix(b"MaintenanceMode", b"PausedCalls"),
+				max_values: None,
+				max_size: Some(148),
+			},
+			StorageInfo {
+				pallet_name: b"MaintenanceMode".to_vec(),
+				storage_name: b"QueuedXcmMessages".to_vec(),
+				prefix: prefix(b"MaintenanceMode", b"QueuedXcmMessages"),
+				max_values: None,
+				max_size: Some(102425),
+			},
+			StorageInfo {
+				pallet_name: b"MaintenanceMode".to_vec(),
+				storage_name: b"XcmQueueHead".to_vec(),
+				prefix: prefix(b"MaintenanceMode", b"XcmQueueHead"),
+				max_values: Some(1),
+				max_size: Some(4),
+			},
+			StorageInfo {
+				pallet_name: b"MaintenanceMode".to_vec(),
+				storage_name: b"XcmQueueTail".to_vec(),
+				prefix: prefix(b"MaintenanceMode", b"XcmQueueTail"),
+				max_values: Some(1),
+				max_size: Some(4),
+			},
+		]
 	);
 }
 
@@ -1118,3 +1148,243 @@ fn ethereum_invalid_transaction() {
 		);
 	});
 }
+
+#[test]
+fn ethereum_transaction_for_retired_chain_id_is_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Call::EthereumChainId(pallet_ethereum_chain_id::Call::<Runtime>::set_chain_id(
+				CHAIN_ID + 1
+			))
+			.dispatch(<Runtime as frame_system::Config>::Origin::signed(
+				AccountId::from(ALICE)
+			)),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(
+			Call::EthereumChainId(pallet_ethereum_chain_id::Call::<Runtime>::set_chain_id(
+				CHAIN_ID + 1
+			))
+			.dispatch(<Runtime as frame_system::Config>::Origin::root())
+		);
+		assert_eq!(
+			pallet_ethereum_chain_id::Pallet::<Runtime>::chain_id(),
+			CHAIN_ID + 1
+		);
+		assert_eq!(
+			pallet_ethereum_chain_id::Pallet::<Runtime>::previous_chain_ids(),
+			vec![CHAIN_ID]
+		);
+
+		// VALID_ETH_TX is signed for CHAIN_ID
+		assert_eq!(
+			Executive::apply_extrinsic(unchecked_eth_tx(VALID_ETH_TX)),
+			Err(
+				sp_runtime::transaction_validity::TransactionValidityError::Invalid(
+					sp_runtime::transaction_validity::InvalidTransaction::Custom(
+						pallet_ethereum_chain_id::RETIRED_CHAIN_ID_ERROR
+					)
+				)
+			)
+		);
+	});
+}
+
+/// The names of a tuple of pallets, in order.
+trait PalletNames {
+	fn pallet_names() -> Vec<&'static str>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(40)]
+impl PalletNames for Tuple {
+	fn pallet_names() -> Vec<&'static str> {
+		let mut names = Vec::new();
+		for_tuples!( #( names.extend(Tuple::pallet_names()); )* );
+		names
+	}
+}
+
+macro_rules! impl_pallet_names {
+	($($pallet:ident),*) => {
+		$(
+			impl PalletNames for moonriver_runtime::$pallet {
+				fn pallet_names() -> Vec<&'static str> {
+					vec![stringify!($pallet)]
+				}
+			}
+		)*
+	};
+}
+
+// Every pallet of the runtime but System, so that a new pallet must be added here to compile.
+impl_pallet_names!(
+	ParachainSystem,
+	RandomnessCollectiveFlip,
+	Timestamp,
+	ParachainInfo,
+	Balances,
+	TransactionPayment,
+	ParachainStaking,
+	AuthorInherent,
+	AuthorFilter,
+	AuthorMapping,
+	Utility,
+	Proxy,
+	MaintenanceMode,
+	Identity,
+	Migrations,
+	EthereumChainId,
+	EVM,
+	Ethereum,
+	Scheduler,
+	Democracy,
+	CouncilCollective,
+	TechComitteeCollective,
+	Treasury,
+	CrowdloanRewards
+);
+
+#[test]
+fn maintenance_hooks_are_all_pallets_but_the_excluded_ones() {
+	// The pallets whose hooks are suppressed during maintenance, see MaintenanceHooks
+	let excluded = ["ParachainStaking"];
+	let expected: Vec<_> = moonriver_runtime::AllPallets::pallet_names()
+		.into_iter()
+		.filter(|name| !excluded.contains(name))
+		.collect();
+
+	assert_eq!(
+		<moonriver_runtime::MaintenanceHooks as PalletNames>::pallet_names(),
+		expected
+	);
+}
+
+#[test]
+fn inherents_cannot_be_paused() {
+	ExtBuilder::default().build().execute_with(|| {
+		// The maintenance origin is two thirds of the technical committee
+		let origin: moonriver_runtime::Origin =
+			pallet_collective::RawOrigin::<AccountId, pallet_collective::Instance2>::Members(1, 1)
+				.into();
+
+		assert_noop!(
+			Call::MaintenanceMode(pallet_maintenance_mode::Call::pause_call(
+				b"Timestamp".to_vec(),
+				b"set".to_vec()
+			))
+			.dispatch(origin),
+			pallet_maintenance_mode::Error::<Runtime>::PalletCannotBePaused
+		);
+	});
+}
+
+#[test]
+fn precompiles_acting_on_behalf_of_the_caller_cannot_be_delegated() {
+	ExtBuilder::default().build().execute_with(|| {
+		// With DELEGATECALL the context is the one of the calling contract (Alice), while the
+		// caller is the account which called it (Bob).
+		let context = Context {
+			address: H160::from(ALICE),
+			caller: H160::from(BOB),
+			apparent_value: U256::zero(),
+		};
+
+		for precompile in vec![2052, 2060] {
+			assert_eq!(
+				Precompiles::execute(H160::from_low_u64_be(precompile), &[], None, &context),
+				Some(Err(ExitError::Other(
+					"cannot be called with DELEGATECALL or CALLCODE".into()
+				))),
+				"precompile {}",
+				precompile
+			);
+		}
+	});
+}
+
+#[test]
+fn blake2f_precompile_follows_eip_152() {
+	ExtBuilder::default().build().execute_with(|| {
+		let blake2f_precompile_address = H160::from_low_u64_be(9);
+
+		// Test vectors 4 to 7 of EIP-152: the compression of "abc" with a varying number of
+		// rounds and final block indicator flag.
+		let input = |rounds: &str, final_block: &str| {
+			hex::decode(format!(
+				"{}{}{}{}{}",
+				rounds,
+				"48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e\
+				511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+				format!("616263{}", "00".repeat(125)),
+				"03000000000000000000000000000000",
+				final_block,
+			))
+			.unwrap()
+		};
+		let execute = |input: Vec<u8>| {
+			Precompiles::execute(
+				blake2f_precompile_address,
+				&input,
+				None,
+				&evm_test_context(),
+			)
+		};
+		let expected_result = |output: &str, cost: u64| {
+			Some(Ok(PrecompileOutput {
+				exit_status: ExitSucceed::Returned,
+				output: hex::decode(output).unwrap(),
+				cost,
+				logs: Default::default(),
+			}))
+		};
+
+		// The gas is the number of rounds
+		assert_eq!(
+			execute(input("00000000", "01")),
+			expected_result(
+				"08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d282e6ad7f520e511f\
+				6c3e2b8c68059b9442be0454267ce079217e1319cde05b",
+				0
+			)
+		);
+		assert_eq!(
+			execute(input("0000000c", "01")),
+			expected_result(
+				"ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc\
+				252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+				12
+			)
+		);
+		assert_eq!(
+			execute(input("0000000c", "00")),
+			expected_result(
+				"75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d2875298743e7f6d5ea2f2d\
+				3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735",
+				12
+			)
+		);
+		assert_eq!(
+			execute(input("00000001", "01")),
+			expected_result(
+				"b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fba551b3a8333bcdf5f\
+				2f7e08993d53923de3d64fcc68c034e717b9293fed7a421",
+				1
+			)
+		);
+
+		// Test vectors 0 to 3 of EIP-152: the input must be exactly 213 bytes, and the final
+		// block indicator flag must be 0 or 1.
+		assert!(matches!(execute(vec![]), Some(Err(ExitError::Other(_)))));
+		let mut too_short = input("0000000c", "01");
+		too_short.remove(0);
+		assert!(matches!(execute(too_short), Some(Err(ExitError::Other(_)))));
+		let mut too_long = input("0000000c", "01");
+		too_long.insert(0, 0);
+		assert!(matches!(execute(too_long), Some(Err(ExitError::Other(_)))));
+		assert!(matches!(
+			execute(input("0000000c", "02")),
+			Some(Err(ExitError::Other(_)))
+		));
+	});
+}